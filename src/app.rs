@@ -1,27 +1,209 @@
 use eframe::egui;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::{mpsc, Arc};
+use std::sync::{mpsc, Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 use eframe::epaint::{Margin, CornerRadius};
 use egui::LayerId;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::Xxh3;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
 
-// Static variable for cancel flag
-static mut CANCEL_FLAG: Option<Arc<AtomicBool>> = None;
+use crate::git::{FetchConfig, GitHandler, RepositoryMetadata, SshConfig, DEFAULT_REMOTE_NAME, SSH_UNAVAILABLE_MESSAGE};
+use crate::directory::{lfs_pointer_size, DirectoryParser, DirectoryEntry, DirectoryStatistics, IgnorePreset, ParsedRepository, SymlinkMode};
+use crate::ui::{UiHandler, StatusKind, StatusVerbosity, ThemeMode};
+use crate::config::ProjectConfig;
+use crate::export::{self, ExportFormat};
+use crate::dialogs;
+use crate::events::{EventLog, EVENT_LOG_CAPACITY};
+use crate::preview::{FileContentCache, FilePreview, PREVIEW_CACHE_ENTRIES, PREVIEW_HEX_BYTES, PREVIEW_MAX_BYTES};
 
-use crate::git::GitHandler;
-use crate::directory::{DirectoryParser, DirectoryEntry};
-use crate::ui::UiHandler;
+/// Lock file the application was built with, for showing dependency versions
+const CARGO_LOCK: &str = include_str!("../Cargo.lock");
+
+/// Finds the version of a package in a Cargo.lock file
+///
+/// # Arguments
+/// * `lock` - Contents of the lock file
+/// * `name` - Package name
+///
+/// # Returns
+/// The version of the first package with that name, or `None` if it is not locked
+fn locked_version<'a>(lock: &'a str, name: &str) -> Option<&'a str> {
+    let mut lines = lock.lines();
+    while let Some(line) = lines.next() {
+        if line.strip_prefix("name = ").map(|value| value.trim_matches('"')) == Some(name) {
+            return lines.next()
+                .and_then(|line| line.strip_prefix("version = "))
+                .map(|value| value.trim_matches('"'));
+        }
+    }
+    None
+}
+
+/// Hash of a file's raw contents (XXH3, 64 bits), used to find duplicates
+pub type ContentHash = u64;
+
+/// Size of the chunks files are hashed in, so large files aren't loaded into memory
+const HASH_CHUNK_BYTES: usize = 64 * 1024;
 
 /// Represents a file's metadata for the list view
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct FileInfo {
     pub index: usize,          // Order in the list
     pub path: PathBuf,         // Full path to the file
     pub tokens: usize,         // Number of tokens in the file (or size in bytes for binary files)
     pub selected: bool,        // Whether the file is selected
     pub is_binary: bool,       // Whether the file is a binary file
+    pub size_bytes: usize,     // Size of the file on disk
+    pub content_hash: ContentHash, // Hash of the raw file contents, for finding duplicates
+    pub lfs_size: Option<usize>, // Size of the real file if this is a Git LFS pointer whose content wasn't fetched
+    pub pinned: bool,          // Whether the file stays at the top of the list whatever the sort
+    pub modified: Option<SystemTime>, // Last modification time on disk, read when the file is counted
+}
+
+/// What the main value column of the file list measures
+///
+/// `FileInfo::tokens` holds the token count of text files but the size of
+/// binary files; the basis says how to read it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MetricBasis {
+    /// Tokens for text files and sizes for binary files
+    #[default]
+    Hybrid,
+    /// Tokens only; binary files have no value
+    Tokens,
+    /// Size on disk for every file
+    Bytes,
+}
+
+impl MetricBasis {
+    /// Gets the name shown in the settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Hybrid => "Tokens and binary sizes",
+            Self::Tokens => "Tokens only",
+            Self::Bytes => "Bytes only",
+        }
+    }
+    
+    /// Gets the header of the value column
+    pub fn column_header(&self) -> &'static str {
+        match self {
+            Self::Hybrid => "Tokens/Size",
+            Self::Tokens => "Tokens",
+            Self::Bytes => "Size",
+        }
+    }
+    
+    /// Gets the value of a file in this basis
+    ///
+    /// # Arguments
+    /// * `file` - The file to measure
+    ///
+    /// # Returns
+    /// * `Option<usize>` - Tokens or bytes, or `None` for binary files when
+    ///   counting tokens only
+    pub fn value(&self, file: &FileInfo) -> Option<usize> {
+        match self {
+            Self::Hybrid => Some(file.tokens),
+            Self::Tokens => (!file.is_binary).then_some(file.tokens),
+            Self::Bytes => Some(file.size_bytes),
+        }
+    }
+    
+    /// Checks whether a file's value is a size in bytes rather than a token count
+    ///
+    /// # Arguments
+    /// * `file` - The file to check
+    pub fn is_size(&self, file: &FileInfo) -> bool {
+        match self {
+            Self::Hybrid => file.is_binary,
+            Self::Tokens => false,
+            Self::Bytes => true,
+        }
+    }
+    
+    /// Formats a file's value for display, with "—" for files without one
+    ///
+    /// # Arguments
+    /// * `file` - The file to format
+    /// * `format_tokens` - Formats a token count, e.g. compactly
+    pub fn format(&self, file: &FileInfo, format_tokens: impl Fn(usize) -> String) -> String {
+        match self.value(file) {
+            None => "—".to_string(),
+            Some(value) if self.is_size(file) => format_file_size(value),
+            Some(value) => format_tokens(value),
+        }
+    }
+}
+
+/// Files with fewer tokens per byte than this are flagged as likely generated or minified
+const LOW_TOKEN_DENSITY: f64 = 0.05;
+
+/// Files smaller than this are never flagged, since short files have noisy ratios
+const LOW_TOKEN_DENSITY_MIN_BYTES: usize = 1024;
+
+impl FileInfo {
+    /// Calculates the number of tokens per byte of a text file
+    ///
+    /// # Returns
+    /// * `Option<f64>` - The ratio, or `None` for binary and empty files
+    pub fn tokens_per_byte(&self) -> Option<f64> {
+        if self.is_binary || self.size_bytes == 0 {
+            None
+        } else {
+            Some(self.tokens as f64 / self.size_bytes as f64)
+        }
+    }
+    
+    /// Checks whether the file looks generated or minified
+    ///
+    /// Minified code has little whitespace, so it splits into very few tokens
+    /// for its size.
+    ///
+    /// # Returns
+    /// * `bool` - True if the file is large enough and has a low token density
+    pub fn is_likely_generated(&self) -> bool {
+        self.size_bytes >= LOW_TOKEN_DENSITY_MIN_BYTES &&
+            self.tokens_per_byte().is_some_and(|ratio| ratio < LOW_TOKEN_DENSITY)
+    }
+    
+    /// Gets how deep the file sits below the repository root
+    ///
+    /// # Arguments
+    /// * `root` - Repository root
+    ///
+    /// # Returns
+    /// * `usize` - Number of directories between the root and the file
+    pub fn depth(&self, root: Option<&Path>) -> usize {
+        tree_depth(&self.path, root)
+    }
+    
+    /// Gets the directory directly below the repository root that holds the file
+    ///
+    /// # Arguments
+    /// * `root` - Repository root
+    ///
+    /// # Returns
+    /// * `Option<String>` - The directory name, e.g. "src", or `None` for files in the root
+    pub fn top_level_dir(&self, root: Option<&Path>) -> Option<String> {
+        if self.depth(root) == 0 {
+            return None;
+        }
+        let relative = root
+            .and_then(|root| self.path.strip_prefix(root).ok())
+            .unwrap_or(&self.path);
+        relative.components()
+            .find_map(|component| match component {
+                std::path::Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                _ => None,
+            })
+    }
 }
 
 /// Formats a file size in bytes to a human-readable string
@@ -31,7 +213,7 @@ pub struct FileInfo {
 ///
 /// # Returns
 /// * `String` - The formatted size string (e.g., "1.23 MB (1234567 bytes)")
-fn format_file_size(size_bytes: usize) -> String {
+pub(crate) fn format_file_size(size_bytes: usize) -> String {
     if size_bytes > 1024 * 1024 * 1024 {
         format!("{:.2} GB ({} bytes)",
             size_bytes as f64 / (1024.0 * 1024.0 * 1024.0),
@@ -49,1837 +231,8783 @@ fn format_file_size(size_bytes: usize) -> String {
     }
 }
 
-/// Counts tokens in a file by splitting on whitespace
-/// Uses streaming to reduce memory usage for large files
+/// Formats a count compactly, e.g. for token totals
+///
+/// # Arguments
+/// * `count` - The count to format
 ///
 /// # Returns
-/// * `(usize, bool)` - (token count or file size, is_binary)
-fn count_tokens(path: &Path) -> (usize, bool) {
-    // Define text file extensions
-    let text_extensions = [
-        "txt", "rs", "py", "js", "md", "html", "css", "json", "yaml", "toml",
-    ];
-
-    // Check if the file has a text extension
-    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-        if !text_extensions.contains(&ext.to_lowercase().as_str()) {
-            // For binary files, return the file size in bytes
-            return match std::fs::metadata(path) {
-                Ok(metadata) => (metadata.len() as usize, true),
-                Err(_) => (0, true),
-            };
-        }
-    } else {
-        // No extension, assume binary and return file size
-        return match std::fs::metadata(path) {
-            Ok(metadata) => (metadata.len() as usize, true),
-            Err(_) => (0, true),
-        };
+/// * `String` - The count with a k, M or B suffix above 999 (e.g., "14.2k", "1.4M")
+pub(crate) fn format_count(count: usize) -> String {
+    const UNITS: [(f64, &str); 3] = [(1e3, "k"), (1e6, "M"), (1e9, "B")];
+    
+    if count < 1000 {
+        return count.to_string();
     }
+    
+    // Move up a unit when rounding would show 1000.0 of the smaller one
+    let value = count as f64;
+    let (divisor, suffix) = UNITS.iter()
+        .rev()
+        .find(|(divisor, _)| value / divisor >= 0.99995)
+        .copied()
+        .unwrap_or(UNITS[0]);
+    format!("{:.1}{}", value / divisor, suffix)
+}
 
-    // Stream file content and count words to reduce memory usage
-    match std::fs::File::open(path) {
-        Ok(file) => {
-            use std::io::{BufRead, BufReader};
-            let reader = BufReader::new(file);
-            let token_count = reader.lines()
-                .filter_map(Result::ok)
-                .map(|line| line.split_whitespace().count())
-                .sum();
-            (token_count, false) // Not binary, return token count
-        },
-        Err(_) => (0, false), // Return 0 if file can't be read
-    }
+/// Default extensions of files expected to hold text, as shown in the settings
+pub(crate) const DEFAULT_TEXT_EXTENSIONS: &str = "txt, md, rst, adoc, rs, py, pyi, js, jsx, mjs, cjs, ts, tsx, \
+    go, c, h, cc, cpp, cxx, hpp, hh, cs, java, kt, kts, scala, swift, m, mm, rb, php, pl, lua, r, \
+    dart, ex, exs, erl, hs, ml, clj, sh, bash, zsh, fish, ps1, bat, sql, html, htm, css, scss, \
+    sass, less, vue, svelte, xml, svg, json, yaml, yml, toml, ini, cfg, conf, env, lock, \
+    gradle, cmake, mk, proto, graphql, tex, csv";
+
+/// Number of bytes read from the start of a file to decide whether it is binary
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Parses a comma or whitespace separated list of file extensions
+///
+/// # Arguments
+/// * `list` - The list, e.g. "rs, .py txt"
+///
+/// # Returns
+/// * `Vec<String>` - Lowercased extensions without leading dots
+pub(crate) fn parse_extension_list(list: &str) -> Vec<String> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .map(|ext| ext.trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
 }
 
-/// Enum for sortable columns
-#[derive(PartialEq, Clone, Copy)]
-pub enum SortColumn {
-    Index,
-    Name,
-    Tokens,
+/// A named set of file extensions the file list can be limited to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionPreset {
+    /// Name shown in the preset dropdown
+    pub name: String,
+    /// Extensions of the included files; empty to include every file
+    pub extensions: Vec<String>,
 }
 
-/// Enum for sort direction
-#[derive(PartialEq, Clone, Copy)]
-pub enum SortDirection {
-    Ascending,
-    Descending,
+impl ExtensionPreset {
+    /// Creates a preset from a comma or whitespace separated extension list
+    ///
+    /// # Arguments
+    /// * `name` - Name shown in the preset dropdown
+    /// * `extensions` - The extension list, e.g. "rs, .py txt"
+    pub fn new(name: &str, extensions: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            extensions: parse_extension_list(extensions),
+        }
+    }
+    
+    /// Checks whether a file is included by the preset
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    ///
+    /// # Returns
+    /// * `bool` - True if the preset includes every file or the file's extension
+    pub fn matches(&self, path: &Path) -> bool {
+        self.extensions.is_empty() || path.extension()
+            .is_some_and(|ext| self.extensions.contains(&ext.to_string_lossy().to_lowercase()))
+    }
 }
 
-/// Progress information for cloning operations
-enum CloneProgress {
-    Progress(f32),
-    Completed(Result<PathBuf, String>),
+/// Number of built-in extension presets, which come before any custom ones
+const BUILTIN_PRESET_COUNT: usize = 4;
+
+/// Gets the presets that are always available, starting with one that includes every file
+///
+/// # Returns
+/// * `Vec<ExtensionPreset>` - "All", "Code", "Docs" and "Config"
+fn builtin_extension_presets() -> Vec<ExtensionPreset> {
+    vec![
+        ExtensionPreset::new("All", ""),
+        ExtensionPreset::new("Code", "rs, py, pyi, js, jsx, mjs, cjs, ts, tsx, go, c, h, cc, cpp, cxx, hpp, hh, \
+            cs, java, kt, kts, scala, swift, m, mm, rb, php, pl, lua, r, dart, ex, exs, erl, hs, ml, clj, \
+            sh, bash, zsh, fish, ps1, bat, sql, html, htm, css, scss, sass, less, vue, svelte, proto, graphql"),
+        ExtensionPreset::new("Docs", "md, markdown, mdx, rst, adoc, txt, org, tex"),
+        ExtensionPreset::new("Config", "toml, yaml, yml, json, ini, cfg, conf, env, xml, properties, gradle, cmake, mk"),
+    ]
 }
 
-/// Main application state for Git Scroll
-pub struct GitScrollApp {
-    // Input state
-    git_url: String,
-    keep_repository: bool,
-    
-    // Application state
-    status_message: String,
-    is_cloning: bool,
-    cancel_requested: bool, // Flag to cancel cloning operation
-    
-    // Repository data
-    repository_path: Option<PathBuf>,
-    directory_structure: Option<DirectoryEntry>,
-    
-    // Module handlers
-    git_handler: GitHandler,
-    directory_parser: DirectoryParser,
-    ui_handler: UiHandler,
+/// Checks whether a file's content looks binary
+///
+/// Only the start of the file is read. A NUL byte always means binary; files
+/// without a known text extension must also be valid UTF-8 to count as text.
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `expect_text` - Whether the file's extension is in the text extension list
+///
+/// # Returns
+/// * `bool` - True if the file should be treated as binary
+fn looks_binary(path: &Path, expect_text: bool) -> bool {
+    use std::io::Read;
     
-    // UI state
-    show_stats_panel: bool,
-    filter_pattern: String,
-    show_advanced_filters: bool,
-    filter_extension: String,
-    filter_token_min: usize,
-    filter_token_max: usize,
+    let mut buffer = Vec::with_capacity(SNIFF_BYTES);
+    let read = std::fs::File::open(path)
+        .and_then(|file| file.take(SNIFF_BYTES as u64).read_to_end(&mut buffer));
+    if read.is_err() {
+        return !expect_text;
+    }
     
-    // File list state
-    file_list: Vec<FileInfo>,
-    sort_column: SortColumn,
-    sort_direction: SortDirection,
-    is_loading_tokens: bool,
+    if buffer.contains(&0) {
+        return true;
+    }
     
-    // Table UI state
-    column_widths: [f32; 3], // Widths for Index, Name, Tokens columns
-    current_page: usize,     // Current page for pagination
+    // A multi-byte character cut off by the read limit is still valid text
+    let cut_off = buffer.len() == SNIFF_BYTES;
+    !expect_text && std::str::from_utf8(&buffer).is_err_and(|e| e.error_len().is_some() || !cut_off)
+}
+
+/// How lines of text are split into tokens
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenizerKind {
+    /// Whitespace-separated words
+    #[default]
+    Words,
+    /// Words split further at brackets, operators and punctuation, each of
+    /// which counts as a token, for a closer estimate of LLM tokens in code
+    Code,
+}
+
+impl TokenizerKind {
+    /// Gets the name shown in the settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Words => "Words",
+            Self::Code => "Code-aware",
+        }
+    }
+}
     
-    // Background processing channels
-    clone_receiver: mpsc::Receiver<CloneProgress>,
-    parse_receiver: mpsc::Receiver<Result<DirectoryEntry, String>>,
-    token_receiver: mpsc::Receiver<(usize, PathBuf, usize, bool)>,
+/// Bytes read at a time when counting tokens, so memory stays bounded on long lines
+const TOKEN_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Longest word tallied for the common words; longer runs of text are skipped
+const WORD_MAX_BYTES: usize = 256;
+
+/// Counts tokens in text that arrives in pieces, such as chunks of a file
+///
+/// The state of the current word carries over between pieces, so a token
+/// split across a chunk edge is counted once.
+struct TokenCounter<'a> {
+    tokenizer: TokenizerKind,
+    count: usize,
+    in_word: bool,                         // Whether the last character continued a token
+    words: Option<&'a mut WordFrequencies>, // Tally to add whole words to, if any
+    word: String,                          // Current whitespace-separated word, for the tally
+    word_too_long: bool,                   // Whether the current word passed WORD_MAX_BYTES
 }
 
-impl GitScrollApp {
-    /// Creates a new instance of the GitScrollApp
+impl<'a> TokenCounter<'a> {
+    /// Creates a counter with no tokens
     ///
-    /// Returns a new GitScrollApp with default values
-    pub fn new() -> Self {
-        // Create channels for background processing
-        let (_clone_sender, clone_receiver) = mpsc::channel::<CloneProgress>();
-        let (_parse_sender, parse_receiver) = mpsc::channel();
-        let (_token_sender, token_receiver) = mpsc::channel();
-        
-        // Initialize with default values
+    /// # Arguments
+    /// * `tokenizer` - How text is split into tokens
+    /// * `words` - Tally to add the words to, if any
+    fn new(tokenizer: TokenizerKind, words: Option<&'a mut WordFrequencies>) -> Self {
         Self {
-            git_url: String::new(),
-            keep_repository: false,
-            status_message: String::from("Ready"),
-            is_cloning: false,
-            cancel_requested: false,
-            repository_path: None,
-            directory_structure: None,
-            
-            // Initialize module handlers
-            git_handler: GitHandler::new(false),
-            directory_parser: DirectoryParser::new(),
-            ui_handler: UiHandler::new(),
-            
-            // UI state
-            show_stats_panel: true,
-            filter_pattern: String::new(),
-            show_advanced_filters: false,
-            filter_extension: String::new(),
-            filter_token_min: 0,
-            filter_token_max: 0,
-            
-            // File list state
-            file_list: Vec::new(),
-            sort_column: SortColumn::Index,
-            sort_direction: SortDirection::Ascending,
-            is_loading_tokens: false,
+            tokenizer,
+            count: 0,
+            in_word: false,
+            words,
+            word: String::new(),
+            word_too_long: false,
+        }
+    }
+    
+    /// Counts the tokens in the next piece of text
+    ///
+    /// # Arguments
+    /// * `text` - The text following the previous piece
+    fn feed(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.in_word = false;
+                self.end_word();
+                continue;
+            }
             
-            // Table UI state
-            column_widths: [60.0, 400.0, 100.0], // Default widths for columns
-            current_page: 0,                     // Start at first page
+            if self.words.is_some() && !self.word_too_long {
+                if self.word.len() + c.len_utf8() > WORD_MAX_BYTES {
+                    self.word_too_long = true;
+                    self.word.clear();
+                } else {
+                    self.word.push(c);
+                }
+            }
             
-            // Background processing channels
-            clone_receiver,
-            parse_receiver,
-            token_receiver,
+            if self.tokenizer == TokenizerKind::Code && c.is_ascii_punctuation() && c != '_' {
+                // Delimiters end the current word and count on their own
+                self.count += 1;
+                self.in_word = false;
+            } else if !self.in_word {
+                self.count += 1;
+                self.in_word = true;
+            }
         }
     }
     
-    /// Validates the Git URL format
-    /// 
+    /// Counts the tokens in the next bytes of UTF-8 text
+    ///
+    /// Invalid sequences count as a replacement character. A character cut
+    /// off at the end is left for the next call.
+    ///
     /// # Arguments
-    /// * `url` - The Git URL to validate
-    /// 
+    /// * `bytes` - The bytes following the previous piece
+    ///
     /// # Returns
-    /// * `bool` - True if the URL is valid, false otherwise
-    fn validate_git_url(&self, url: &str) -> bool {
-        GitHandler::validate_url(url)
+    /// * `usize` - The number of bytes consumed
+    fn feed_bytes(&mut self, mut bytes: &[u8]) -> usize {
+        let total = bytes.len();
+        loop {
+            match std::str::from_utf8(bytes) {
+                Ok(text) => {
+                    self.feed(text);
+                    return total;
+                }
+                Err(e) => {
+                    let (valid, rest) = bytes.split_at(e.valid_up_to());
+                    self.feed(std::str::from_utf8(valid).unwrap_or_default());
+                    match e.error_len() {
+                        Some(invalid) => {
+                            self.feed(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]));
+                            bytes = &rest[invalid..];
+                        }
+                        None => return total - rest.len(),
+                    }
+                }
+            }
+        }
     }
     
-    /// Handles the clone button click
-    ///
-    /// Initiates the repository cloning process if the URL is valid
-    fn handle_clone_button(&mut self) {
-        if self.is_cloning {
-            return; // Already cloning
-        }
-        
-        if !self.validate_git_url(&self.git_url) {
-            self.status_message = String::from("Invalid Git URL format");
-            return;
-        }
-        
-        // Update state
-        self.is_cloning = true;
-        self.cancel_requested = false; // Reset cancel flag
-        self.status_message = String::from("Cloning repository...");
-        self.ui_handler.set_loading(true);
-        
-        // Create channels for this operation
-        let (clone_sender, clone_receiver) = mpsc::channel::<CloneProgress>();
-        let (parse_sender, parse_receiver) = mpsc::channel();
-        self.clone_receiver = clone_receiver;
-        self.parse_receiver = parse_receiver;
-        
-        // Update git handler with keep_repository preference
-        let git_handler = GitHandler::new(self.keep_repository);
-        
-        // Clone the git URL for the background thread
-        let git_url = self.git_url.clone();
-        
-        // Create a temporary directory for the repository
-        let temp_dir = match tempfile::Builder::new()
-            .prefix("git_scroll_")
-            .tempdir() {
-                Ok(dir) => dir,
-                Err(e) => {
-                    self.status_message = format!("Failed to create temporary directory: {}", e);
-                    self.is_cloning = false;
-                    self.ui_handler.set_loading(false);
-                    return;
-                }
-            };
-        
-        // Create a shared cancel flag that can be checked from the background thread
-        let cancel_flag = Arc::new(AtomicBool::new(false));
-        let cancel_flag_clone = cancel_flag.clone();
-        
-        // Spawn a background thread to perform the cloning and parsing
-        thread::spawn(move || {
-            use git2::RemoteCallbacks;
-            use git2::build::RepoBuilder;
-
-            // Set up callbacks to track progress
-            let mut callbacks = RemoteCallbacks::new();
-            let mut fetch_options = git2::FetchOptions::new();
-
-            callbacks.transfer_progress(|stats| {
-                // Check if cancellation was requested
-                if cancel_flag.load(Ordering::SeqCst) {
-                    // Return false to abort the transfer
-                    return false;
-                }
-                
-                let progress = if stats.total_objects() > 0 {
-                    (stats.received_objects() as f32 / stats.total_objects() as f32).min(1.0)
-                } else {
-                    0.0
-                };
-                let _ = clone_sender.send(CloneProgress::Progress(progress));
-                true
-            });
-
-            fetch_options.remote_callbacks(callbacks);
-            let mut builder = RepoBuilder::new();
-            builder.fetch_options(fetch_options);
-
-            // Clone the repository with progress tracking
-            println!("Cloning {} to {:?}", git_url, temp_dir.path());
-            let repo_result = builder.clone(&git_url, temp_dir.path());
-            
-            // Check if cancellation was requested
-            if cancel_flag.load(Ordering::SeqCst) {
-                let _ = clone_sender.send(CloneProgress::Completed(Err("Clone operation cancelled by user".to_string())));
-                return;
-            }
-            
-            // Send the final result
-            match repo_result {
-                Ok(repo) => {
-                    let repo_path = repo.path().parent().unwrap_or(repo.path()).to_path_buf();
-                    let _ = clone_sender.send(CloneProgress::Completed(Ok(repo_path.clone())));
-                    
-                    // Parse the directory structure
-                    let parser = DirectoryParser::new();
-                    let parse_result = parser.parse_directory(&repo_path);
-                    let _ = parse_sender.send(parse_result);
-                },
-                Err(e) => {
-                    let _ = clone_sender.send(CloneProgress::Completed(Err(e.to_string())));
-                }
-            }
-        });
-        
-        // Store the cancel flag for later use in the static variable
-        unsafe {
-            CANCEL_FLAG = Some(cancel_flag_clone);
+    /// Adds the current word to the tally, if it is being kept
+    fn end_word(&mut self) {
+        if let Some(words) = self.words.as_deref_mut() {
+            if !self.word_too_long && !self.word.is_empty() {
+                words.add_word(&self.word);
+            }
         }
+        self.word.clear();
+        self.word_too_long = false;
     }
     
-    // Square-related methods removed (handle_zoom, handle_layout_change, handle_theme_change)
-    
-    /// Handles filter pattern change
+    /// Ends the text
+    ///
+    /// # Returns
+    /// * `usize` - The number of tokens counted
+    fn finish(mut self) -> usize {
+        self.end_word();
+        self.count
+    }
+}
+
+/// Number of most frequent words shown in the common words window
+const COMMON_WORDS_SHOWN: usize = 50;
+
+/// Distinct words tracked while counting; the rarest are dropped beyond twice this
+const WORD_TRACK_LIMIT: usize = 20_000;
+
+/// English stopwords and common keywords left out of the common words
+const COMMON_WORD_STOPLIST: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "have", "if", "in",
+    "is", "it", "its", "not", "of", "on", "or", "that", "the", "this", "to", "was", "we", "with",
+    "you", "async", "await", "break", "case", "class", "const", "continue", "def", "else", "enum",
+    "false", "fn", "function", "import", "impl", "int", "let", "match", "mut", "new", "null",
+    "pub", "return", "self", "static", "struct", "true", "use", "var", "void", "while",
+];
+
+/// Tally of the words in text files, for the common words window
+///
+/// Words are whitespace tokens with surrounding punctuation trimmed, compared
+/// without case. Numbers, single characters and stopwords are skipped.
+#[derive(Debug, Default)]
+pub(crate) struct WordFrequencies {
+    counts: HashMap<String, usize>,
+}
+
+impl WordFrequencies {
+    /// Adds a whitespace-separated word of text
     ///
     /// # Arguments
-    /// * `pattern` - The new filter pattern
-    fn handle_filter_change(&mut self, pattern: String) {
-        if self.filter_pattern != pattern {
-            self.filter_pattern = pattern.clone();
-            
-            // Update directory parser with the new filter
-            if !pattern.is_empty() {
-                self.directory_parser.add_ignore_pattern(pattern);
-                
-                // Re-parse the directory structure if we have a repository
-                if let Some(repo_path) = &self.repository_path {
-                    if let Ok(root_entry) = self.directory_parser.parse_directory(repo_path) {
-                        self.directory_structure = Some(root_entry.clone());
-                        
-                        // Refresh the file list with updated filters
-                        self.populate_file_list(&root_entry);
-                    }
-                }
-            }
+    /// * `word` - The word to tally, possibly with surrounding punctuation
+    fn add_word(&mut self, word: &str) {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+        if word.chars().count() < 2 || word.chars().all(|c| c.is_numeric()) {
+            return;
         }
+        let word = word.to_lowercase();
+        if COMMON_WORD_STOPLIST.contains(&word.as_str()) {
+            return;
+        }
+        *self.counts.entry(word).or_insert(0) += 1;
     }
     
-    /// Renders the statistics panel
+    /// Adds another tally into this one, dropping rare words if it grows too large
     ///
     /// # Arguments
-    /// * `ui` - The egui UI to render to
-    fn render_stats_panel(&self, ui: &mut egui::Ui) {
-        ui.heading("Repository Statistics");
-        ui.add_space(10.0);
+    /// * `other` - The tally to merge, e.g. for a single file
+    fn merge(&mut self, other: WordFrequencies) {
+        for (word, count) in other.counts {
+            *self.counts.entry(word).or_insert(0) += count;
+        }
         
-        if !self.file_list.is_empty() {
-            let total_files = self.file_list.len();
-            let text_files = self.file_list.iter().filter(|f| !f.is_binary).count();
-            let binary_files = self.file_list.iter().filter(|f| f.is_binary).count();
-            let total_tokens = self.file_list.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum::<usize>();
-            let avg_tokens = if text_files > 0 { total_tokens / text_files } else { 0 };
-            
-            ui.label(format!("Total Files: {}", total_files));
-            ui.label(format!("Text Files: {}", text_files));
-            ui.label(format!("Binary Files: {}", binary_files));
-            ui.label(format!("Total Tokens (text files): {}", total_tokens));
-            ui.label(format!("Average Tokens per Text File: {}", avg_tokens));
-            
-            // Add binary file size statistics
-            if binary_files > 0 {
-                let total_binary_size = self.file_list.iter()
-                    .filter(|f| f.is_binary)
-                    .map(|f| f.tokens)
-                    .sum::<usize>();
-                
-                let avg_binary_size = if binary_files > 0 { total_binary_size / binary_files } else { 0 };
-                
-                ui.add_space(5.0);
-                ui.label(format!("Average Binary Size: {}", format_file_size(avg_binary_size)));
-                ui.label(format!("Total Binary Size: {}", format_file_size(total_binary_size)));
-            }
-            
-            ui.add_space(10.0);
-            ui.separator();
-            ui.add_space(10.0);
-            
-            // Add token count legend (for text files only)
-            ui.label("Token Count Legend (Text Files):");
-            ui.horizontal(|ui| {
-                // Calculate max tokens for color scaling (text files only)
-                let max_tokens = self.file_list.iter()
-                    .filter(|f| !f.is_binary)
-                    .map(|f| f.tokens)
-                    .max()
-                    .unwrap_or(1);
-                
-                let low_color = crate::ui::style::token_count_color(0, max_tokens, self.ui_handler.is_dark_mode());
-                let mid_color = crate::ui::style::token_count_color(max_tokens / 2, max_tokens, self.ui_handler.is_dark_mode());
-                let high_color = crate::ui::style::token_count_color(max_tokens, max_tokens, self.ui_handler.is_dark_mode());
-                
-                ui.label(egui::RichText::new("Low").color(low_color));
-                ui.label(" → ");
-                ui.label(egui::RichText::new("Medium").color(mid_color));
-                ui.label(" → ");
-                ui.label(egui::RichText::new("High").color(high_color));
-            });
-            
-            ui.add_space(10.0);
-            ui.separator();
-            ui.add_space(10.0);
-            ui.heading("Top Text Files by Token Count");
-            ui.add_space(5.0);
-            
-            // Get top text files by token count (exclude binary files)
-            let mut top_files = self.file_list.iter()
-                .filter(|f| !f.is_binary)
-                .cloned()
-                .collect::<Vec<_>>();
-            top_files.sort_by(|a, b| b.tokens.cmp(&a.tokens));
-            top_files.sort_by(|a, b| b.tokens.cmp(&a.tokens));
-            
-            // Display top files
-            for file in top_files.iter().take(10) {
-                let file_name = file.path.file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy();
-                ui.label(format!("{}: {} tokens", file_name, file.tokens));
-            }
-        } else {
-            ui.label("No statistics available");
+        // Memory stays bounded; words that rare never reach the top list anyway
+        if self.counts.len() > 2 * WORD_TRACK_LIMIT {
+            let kept: HashSet<String> = self.top(WORD_TRACK_LIMIT).into_iter().map(|(word, _)| word).collect();
+            self.counts.retain(|word, _| kept.contains(word));
         }
     }
     
-    /// Renders the settings panel
+    /// Gets the most frequent words
     ///
     /// # Arguments
-    /// * `ui` - The egui UI to render to
-    fn render_settings_panel(&mut self, ui: &mut egui::Ui) {
-        ui.heading("File List Settings");
-        ui.add_space(10.0);
-        
-        // Sort settings
-        ui.label("Sort By:");
-        ui.horizontal(|ui| {
-            if ui.radio_value(&mut self.sort_column, SortColumn::Index, "Number").clicked() {
-                self.sort_file_list();
-            }
-            if ui.radio_value(&mut self.sort_column, SortColumn::Name, "Name").clicked() {
-                self.sort_file_list();
-            }
-            if ui.radio_value(&mut self.sort_column, SortColumn::Tokens, "Tokens").clicked() {
-                self.sort_file_list();
-            }
-        });
-        
-        ui.add_space(5.0);
+    /// * `limit` - Maximum number of words to return
+    ///
+    /// # Returns
+    /// * `Vec<(String, usize)>` - Words with their counts, most frequent first
+    ///   and alphabetical among equal counts
+    fn top(&self, limit: usize) -> Vec<(String, usize)> {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
         
-        // Sort direction
-        ui.label("Sort Direction:");
-        ui.horizontal(|ui| {
-            if ui.radio_value(&mut self.sort_direction, SortDirection::Ascending, "Ascending").clicked() {
-                self.sort_file_list();
+        // Min-heap of the best words so far; the weakest is popped when full
+        let mut heap = BinaryHeap::with_capacity(limit + 1);
+        for (word, &count) in &self.counts {
+            heap.push(Reverse((count, Reverse(word))));
+            if heap.len() > limit {
+                heap.pop();
             }
-            if ui.radio_value(&mut self.sort_direction, SortDirection::Descending, "Descending").clicked() {
-                self.sort_file_list();
-            }
-        });
-        
-        ui.add_space(10.0);
-        
-        // Theme selection removed - no longer needed
-        
-        ui.add_space(10.0);
-        
-        // Filter pattern
-        ui.label("Filter Pattern:");
-        let mut pattern = self.filter_pattern.clone();
-        if ui.text_edit_singleline(&mut pattern).changed() {
-            self.handle_filter_change(pattern);
         }
         
-        if ui.button("Apply Filter").clicked() {
-            self.handle_filter_change(self.filter_pattern.clone());
-        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((count, Reverse(word)))| (word.clone(), count))
+            .collect()
     }
 }
 
-impl GitScrollApp {
-    /// Shows an error dialog with the given message
-    ///
-    /// # Arguments
-    /// * `ctx` - The egui context
-    /// * `error_message` - The error message to display
-    fn show_error_dialog(&self, ctx: &egui::Context, error_message: &str) {
-        egui::Window::new("Error")
-            .collapsible(false)
-            .resizable(false)
-            .show(ctx, |ui| {
-                ui.label(error_message);
-                if ui.button("OK").clicked() {
-                    // Dialog will close automatically when button is clicked
-                }
-            });
+/// Counts tokens in a file with the given tokenizer
+/// Reads fixed-size chunks so memory stays bounded, even for a file that is one long line
+///
+/// The extension list is a hint: listed files are text unless they contain
+/// NUL bytes, and other files are text if their content is valid UTF-8.
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `text_extensions` - Lowercased extensions expected to hold text
+/// * `tokenizer` - How lines are split into tokens
+/// * `words` - Tally to add the words of a text file to, if any
+///
+/// # Returns
+/// * `(usize, bool)` - (token count or file size, is_binary)
+fn count_tokens(
+    path: &Path,
+    text_extensions: &[String],
+    tokenizer: TokenizerKind,
+    words: Option<&mut WordFrequencies>,
+) -> (usize, bool) {
+    // Check if the file has a text extension
+    let expect_text = path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| text_extensions.iter().any(|text_ext| text_ext.eq_ignore_ascii_case(ext)));
+
+    if looks_binary(path, expect_text) {
+        // For binary files, return the file size in bytes
+        return match std::fs::metadata(path) {
+            Ok(metadata) => (metadata.len() as usize, true),
+            Err(_) => (0, true),
+        };
     }
 
-    /// Populates the file list from the directory structure
-    /// Uses parallel processing with rayon for better performance
-    fn populate_file_list(&mut self, root_entry: &DirectoryEntry) {
-        self.file_list.clear();
-        let files = self.directory_parser.get_all_files(root_entry);
-        
-        if files.is_empty() {
-            return; // No files to process
-        }
-        
-        // Create a new channel for this operation
-        let (token_sender, token_receiver) = mpsc::channel();
-        self.token_receiver = token_receiver;
-        self.is_loading_tokens = true;
-        
-        // Create a placeholder for each file with 0 tokens initially
-        self.file_list = files
-            .iter()
-            .enumerate()
-            .map(|(index, path)| FileInfo {
-                index,
-                path: path.clone(),
-                tokens: 0, // Will be updated asynchronously
-                selected: false, // Not selected by default
-                is_binary: false, // Will be updated asynchronously
-            })
-            .collect();
-        
-        // Process files in parallel using rayon
-        let files_to_process = files.clone();
-        thread::spawn(move || {
-            // Use par_iter for parallel processing with a thread pool
-            files_to_process.par_iter().enumerate().for_each(|(index, path)| {
-                let (tokens, is_binary) = count_tokens(path);
-                let _ = token_sender.send((index, path.clone(), tokens, is_binary));
-            });
-        });
-        
-        // Initial sort (will be updated as tokens are counted)
-        self.sort_file_list();
+    match std::fs::File::open(path) {
+        Ok(file) => (count_reader_tokens(file, tokenizer, words), false), // Not binary, return token count
+        Err(_) => (0, false), // Return 0 if file can't be read
     }
+}
 
-    /// Sorts the file list based on current sort settings
-    fn sort_file_list(&mut self) {
-        match self.sort_column {
-            SortColumn::Index => {
-                self.file_list.sort_by(|a, b| {
-                    match self.sort_direction {
-                        SortDirection::Ascending => a.index.cmp(&b.index),
-                        SortDirection::Descending => b.index.cmp(&a.index),
-                    }
-                });
-            }
-            SortColumn::Name => {
-                self.file_list.sort_by(|a, b| {
-                    let a_name = a.path.file_name().unwrap_or_default().to_string_lossy();
-                    let b_name = b.path.file_name().unwrap_or_default().to_string_lossy();
-                    match self.sort_direction {
-                        SortDirection::Ascending => a_name.cmp(&b_name),
-                        SortDirection::Descending => b_name.cmp(&a_name),
-                    }
-                });
-            }
-            SortColumn::Tokens => {
-                self.file_list.sort_by(|a, b| {
-                    match self.sort_direction {
-                        SortDirection::Ascending => a.tokens.cmp(&b.tokens),
-                        SortDirection::Descending => b.tokens.cmp(&a.tokens),
-                    }
-                });
-            }
-        }
+/// Counts tokens in UTF-8 text read in chunks of `TOKEN_CHUNK_BYTES`
+///
+/// # Arguments
+/// * `reader` - Source of the text
+/// * `tokenizer` - How the text is split into tokens
+/// * `words` - Tally to add the words to, if any
+///
+/// # Returns
+/// * `usize` - The number of tokens read before the end or a read error
+fn count_reader_tokens(mut reader: impl std::io::Read, tokenizer: TokenizerKind, words: Option<&mut WordFrequencies>) -> usize {
+    let mut counter = TokenCounter::new(tokenizer, words);
+    let mut buffer = vec![0u8; TOKEN_CHUNK_BYTES];
+    let mut pending = 0; // Bytes of a character cut off at the end of the last chunk
+    
+    loop {
+        let read = match reader.read(&mut buffer[pending..]) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => read,
+        };
+        let filled = pending + read;
+        let consumed = counter.feed_bytes(&buffer[..filled]);
+        buffer.copy_within(consumed..filled, 0);
+        pending = filled - consumed;
     }
+    
+    // A character still cut off at the end is invalid
+    if pending > 0 {
+        counter.feed(char::REPLACEMENT_CHARACTER.encode_utf8(&mut [0; 4]));
+    }
+    counter.finish()
+}
 
-    /// Checks for results from background operations
-    ///
-    /// # Arguments
-    /// * `ctx` - The egui context
-    fn check_background_operations(&mut self, ctx: &egui::Context) {
-        // Check if cancel was requested and update the cancel flag
-        if self.cancel_requested {
-            // Access the static cancel flag
-            unsafe {
-                if let Some(ref cancel_flag) = CANCEL_FLAG {
-                    cancel_flag.store(true, Ordering::SeqCst);
-                }
-            }
-        }
-        
-        // Check for clone progress and results
-        if let Ok(clone_msg) = self.clone_receiver.try_recv() {
-            match clone_msg {
-                CloneProgress::Progress(progress) => {
-                    // Update progress in UI
-                    self.ui_handler.set_progress(progress);
-                    self.status_message = format!("Cloning repository... {:.0}%", progress * 100.0);
-                    ctx.request_repaint(); // Force UI update to show progress
-                },
-                CloneProgress::Completed(result) => {
-                    match result {
-                        Ok(repo_path) => {
-                            self.repository_path = Some(repo_path);
-                            self.status_message = String::from("Repository cloned successfully, parsing directory...");
-                            self.cancel_requested = false; // Reset cancel flag
-                        },
-                        Err(e) => {
-                            let error_message = format!("Failed to clone repository: {}", e);
-                            self.status_message = error_message.clone();
-                            self.is_cloning = false;
-                            self.ui_handler.set_loading(false);
-                            self.cancel_requested = false; // Reset cancel flag
-                            
-                            // Show error dialog for critical errors
-                            self.show_error_dialog(ctx, &error_message);
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Check for parse results
-        if let Ok(parse_result) = self.parse_receiver.try_recv() {
-            match parse_result {
-                Ok(root_entry) => {
-                    // Set the directory structure
-                    self.directory_structure = Some(root_entry.clone());
-                    
-                    // Populate file list
-                    self.populate_file_list(&root_entry);
-                    
-                    // Update state
-                    self.status_message = String::from("Repository parsed successfully");
-                    self.is_cloning = false;
-                    self.ui_handler.set_loading(false);
-                },
-                Err(e) => {
-                    // Failed to parse directory
-                    let error_message = format!("Failed to parse repository: {}", e);
-                    self.status_message = error_message.clone();
-                    
-                    // Clean up the repository if not keeping it
-                    if !self.keep_repository && self.repository_path.is_some() {
-                        let _ = self.git_handler.cleanup(self.repository_path.as_ref().unwrap());
-                    }
-                    
-                    self.is_cloning = false;
-                    self.ui_handler.set_loading(false);
-                    
-                    // Show error dialog for critical errors
-                    self.show_error_dialog(ctx, &error_message);
-                }
-            }
+/// Calculates the tokens per byte across all text files
+///
+/// # Arguments
+/// * `files` - The files to aggregate
+///
+/// # Returns
+/// * `Option<f64>` - The ratio, or `None` if there are no non-empty text files
+fn aggregate_tokens_per_byte(files: &[FileInfo]) -> Option<f64> {
+    let (tokens, bytes) = files.iter()
+        .filter(|f| !f.is_binary)
+        .fold((0, 0), |(tokens, bytes), f| (tokens + f.tokens, bytes + f.size_bytes));
+    if bytes == 0 {
+        None
+    } else {
+        Some(tokens as f64 / bytes as f64)
+    }
+}
+
+/// Hashes the raw contents of a file
+///
+/// The file is streamed through a fixed buffer, so a multi-gigabyte asset
+/// takes no more memory than a small file.
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `cancel` - Checked between chunks; once set, hashing stops early
+///
+/// # Returns
+/// * `ContentHash` - The XXH3 hash of the contents, or 0 if the file can't be read or hashing was cancelled
+fn hash_file(path: &Path, cancel: Option<&AtomicBool>) -> ContentHash {
+    use std::io::Read;
+    
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return 0;
+    };
+    
+    // Stream the file so large binaries aren't loaded into memory
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0u8; HASH_CHUNK_BYTES];
+    loop {
+        if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            return 0;
         }
-        
-        // Check for token counting results with improved responsiveness
-        if self.is_loading_tokens {
-            let mut received_count = 0;
-            let mut all_received = false;
-            let total_files = self.file_list.len();
-            let mut needs_sort = false;
-            
-            // Try to receive token results in batches without blocking
-            for _ in 0..20 { // Process up to 20 results per frame for smoother UI
-                match self.token_receiver.try_recv() {
-                    Ok((index, path, tokens, is_binary)) => {
-                        received_count += 1;
-                        
-                        // Update the token count for the file with matching index and path
-                        if let Some(file) = self.file_list.iter_mut().find(|f| f.index == index && f.path == path) {
-                            file.tokens = tokens;
-                            file.is_binary = is_binary;
-                            needs_sort = true;
-                        }
-                    },
-                    Err(_) => break // No more results available right now
-                }
-            }
-            
-            // Resort the list if we received any results
-            if needs_sort {
-                self.sort_file_list();
-                // Request a repaint to update the UI immediately
-                ctx.request_repaint();
-            }
-            
-            // Update progress in status message
-            let completed = self.file_list.iter().filter(|f| f.tokens > 0).count();
-            if completed > 0 {
-                let percentage = (completed as f32 / total_files as f32 * 100.0) as usize;
-                self.status_message = format!("Counting tokens: {}% ({}/{})", percentage, completed, total_files);
-            }
-            
-            // Check if all files have been processed
-            if completed >= total_files {
-                self.is_loading_tokens = false;
-                self.status_message = String::from("Token counting completed");
-            }
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => hasher.update(&buffer[..read]),
+            Err(_) => return 0,
         }
     }
+    hasher.digest()
 }
 
-impl eframe::App for GitScrollApp {
-    /// Updates the application state and renders the UI
-    ///
-    /// # Arguments
-    /// * `ctx` - The egui context
-    /// * `_frame` - The eframe frame
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Apply custom styling based on dark mode setting
-        crate::ui::style::apply_style(ctx, self.ui_handler.is_dark_mode());
-        
-        // Check for results from background operations
-        self.check_background_operations(ctx);
-        
-        // Top panel for URL input and controls with adaptive layout
-        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            ui.add_space(8.0); // Padding at the top
+/// Counts tokens in a file, reads its size on disk and hashes its contents
+///
+/// A Git LFS pointer counts as a binary file of the size it declares, since
+/// its text says nothing about the content it stands for.
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `text_extensions` - Lowercased extensions expected to hold text
+/// * `tokenizer` - How lines are split into tokens
+///
+/// # Returns
+/// * `(usize, bool, usize, ContentHash)` - (token count or file size, is_binary, size in bytes, content hash)
+#[cfg(test)]
+fn count_file(path: &Path, text_extensions: &[String], tokenizer: TokenizerKind) -> (usize, bool, usize, ContentHash) {
+    let (tokens, is_binary, size_bytes, content_hash, _) = count_file_and_words(path, text_extensions, tokenizer, None, None);
+    (tokens, is_binary, size_bytes, content_hash)
+}
 
-            ui.horizontal(|ui| {
-                // Fixed widths for buttons and checkbox
-                let clone_button_width = 80.0;
-                let clear_button_width = 60.0;
-                let theme_button_width = 60.0;
-                let checkbox_width = 120.0; // Approximate width for "Keep Repository" checkbox
-                let spacing = 8.0;
-                let label_width = 60.0; // Approximate width for "Git URL:" label
+/// Counts, sizes and hashes a file like `count_file`, also tallying its words
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `text_extensions` - Lowercased extensions expected to hold text
+/// * `tokenizer` - How lines are split into tokens
+/// * `words` - Tally to add the words of a text file to, if any
+/// * `cancel` - Flag of the count, checked while hashing large files
+///
+/// # Returns
+/// * `(usize, bool, usize, ContentHash, Option<usize>)` - (token count or file size, is_binary, size in bytes,
+///   content hash, size of the real file if this is a Git LFS pointer)
+fn count_file_and_words(
+    path: &Path,
+    text_extensions: &[String],
+    tokenizer: TokenizerKind,
+    words: Option<&mut WordFrequencies>,
+    cancel: Option<&AtomicBool>,
+) -> (usize, bool, usize, ContentHash, Option<usize>) {
+    if let Some(real_size) = lfs_pointer_size(path) {
+        return (real_size, true, real_size, hash_file(path, cancel), Some(real_size));
+    }
+    let (tokens, is_binary) = count_tokens(path, text_extensions, tokenizer, words);
+    let size_bytes = std::fs::metadata(path).map_or(0, |metadata| metadata.len() as usize);
+    (tokens, is_binary, size_bytes, hash_file(path, cancel), None)
+}
 
-                // Git URL label - simple approach with consistent height
-                ui.add_sized(
-                    [label_width, 28.0], // Same height as other elements
-                    egui::Label::new(egui::RichText::new("Git URL:").strong())
-                );
+/// Counts a symlinked file as a link rather than as its target
+///
+/// The link gets no tokens, its own size, and a hash of its target path, so
+/// links to the same file are reported as duplicates of each other.
+///
+/// # Arguments
+/// * `path` - Path to the link
+///
+/// # Returns
+/// * `(usize, bool, usize, ContentHash)` - (0 tokens, not binary, link size in bytes, target hash)
+fn count_link(path: &Path) -> (usize, bool, usize, ContentHash) {
+    let size_bytes = std::fs::symlink_metadata(path).map_or(0, |metadata| metadata.len() as usize);
+    let target = std::fs::read_link(path).unwrap_or_default();
+    (0, false, size_bytes, xxhash_rust::xxh3::xxh3_64(target.to_string_lossy().as_bytes()))
+}
 
-                // Fixed widths for buttons and checkbox
-                let clone_button_width = 80.0;
-                let clear_button_width = 60.0;
-                let theme_button_width = 60.0;
-                let checkbox_width = 120.0; // Approximate width for "Keep Repository" checkbox
-                let spacing = 8.0;
+/// Default number of token counting threads, leaving one core for the UI
+///
+/// # Returns
+/// * `usize` - One less than the available parallelism, at least 1
+fn default_counting_threads() -> usize {
+    thread::available_parallelism()
+        .map_or(1, |threads| threads.get().saturating_sub(1))
+        .max(1)
+}
 
-                // Calculate total fixed width (label + buttons + checkbox + spacings)
-                let total_fixed_width = label_width
-                    + clone_button_width
-                    + checkbox_width
-                    + clear_button_width
-                    + theme_button_width
-                    + (spacing * 5.0); // Spaces between elements
+/// Runs token counting work on a dedicated pool with a capped number of threads
+///
+/// The global rayon pool uses every core, which can make the UI sluggish.
+/// If the pool cannot be created the work runs on the global pool instead.
+///
+/// # Arguments
+/// * `threads` - Number of worker threads
+/// * `work` - The parallel work to run
+///
+/// # Returns
+/// * `R` - The result of the work
+fn with_counting_pool<R: Send>(threads: usize, work: impl FnOnce() -> R + Send) -> R {
+    match rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.max(1))
+        .thread_name(|index| format!("token-counter-{}", index))
+        .build()
+    {
+        Ok(pool) => pool.install(work),
+        Err(_) => work(),
+    }
+}
 
-                // Set minimum URL input width
-                let min_url_width = 200.0;
+/// How the files of a repository are counted
+pub(crate) struct CountSettings {
+    /// Lowercased extensions expected to hold text
+    pub text_extensions: Vec<String>,
+    /// How lines are split into tokens
+    pub tokenizer: TokenizerKind,
+    /// Number of worker threads
+    pub threads: usize,
+}
 
-                // Calculate URL width directly - this ensures it grows immediately with window expansion
-                let url_width = (ui.available_width() - total_fixed_width).max(min_url_width);
+/// Counts the files of a parsed repository in parallel
+///
+/// Both the GUI's counting thread and the headless `analyze` count through
+/// here. Each result is handed to `on_counted` from the worker thread that
+/// produced it, in no particular order; the first error it returns stops the
+/// remaining work, e.g. when the GUI dropped the receiver of a count.
+///
+/// # Arguments
+/// * `files` - Indices and paths of the files to count
+/// * `link_only` - Files that are counted as links instead of being read
+/// * `settings` - Text extensions, tokenizer and thread count
+/// * `words` - Tally to add the words of text files to, if any
+/// * `cancel` - Flag of the count, checked while hashing large files
+/// * `on_counted` - Receives each counted file
+///
+/// # Returns
+/// * `Result<(), E>` - The error that stopped the count, if any
+pub(crate) fn count_repository_files<E: Send>(
+    files: &[(usize, PathBuf)],
+    link_only: &HashSet<PathBuf>,
+    settings: &CountSettings,
+    words: Option<&Mutex<WordFrequencies>>,
+    cancel: Option<&AtomicBool>,
+    on_counted: impl Fn(TokenResult) -> Result<(), E> + Sync,
+) -> Result<(), E> {
+    with_counting_pool(settings.threads, || {
+        files.par_iter().try_for_each(|(index, path)| {
+            let mut file_words = words.map(|_| WordFrequencies::default());
+            let is_link = link_only.contains(path);
+            let (tokens, is_binary, size_bytes, content_hash, lfs_size) = if is_link {
+                let (tokens, is_binary, size_bytes, content_hash) = count_link(path);
+                (tokens, is_binary, size_bytes, content_hash, None)
+            } else {
+                count_file_and_words(path, &settings.text_extensions, settings.tokenizer, file_words.as_mut(), cancel)
+            };
+            if let (Some(words), Some(file_words)) = (words, file_words) {
+                words.lock().unwrap().merge(file_words);
+            }
+            let metadata = if is_link { std::fs::symlink_metadata(path) } else { std::fs::metadata(path) };
+            let modified = metadata.and_then(|metadata| metadata.modified()).ok();
+            on_counted((*index, path.clone(), tokens, is_binary, size_bytes, content_hash, lfs_size, modified))
+        })
+    })
+}
 
-                // URL input with flexible width
-                let url_input_id = ui.make_persistent_id("git_url_input");
-                let response = ui.add_sized(
-                    [url_width, 28.0],
-                    egui::TextEdit::singleline(&mut self.git_url)
-                        .hint_text("Enter repository URL...")
-                        .id(url_input_id)
-                );
+/// Files with identical contents
+pub(crate) struct DuplicateSet {
+    /// Paths of the identical files, in list order
+    pub paths: Vec<PathBuf>,
+    /// Tokens in one copy (0 for binary files)
+    pub tokens: usize,
+    /// Size of one copy in bytes
+    pub size_bytes: usize,
+}
 
-                // Handle Enter key press when URL input is focused
-                if response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    self.handle_clone_button();
-                }
+impl DuplicateSet {
+    /// Tokens spent on the copies beyond the first
+    pub fn wasted_tokens(&self) -> usize {
+        self.tokens * (self.paths.len() - 1)
+    }
+    
+    /// Bytes spent on the copies beyond the first
+    pub fn wasted_bytes(&self) -> usize {
+        self.size_bytes * (self.paths.len() - 1)
+    }
+}
 
-                ui.add_space(spacing);
+/// Groups files with identical contents
+///
+/// Empty files and files that haven't been counted yet are skipped, since they
+/// would all share the same hash.
+///
+/// # Arguments
+/// * `files` - The files to check
+///
+/// # Returns
+/// * `Vec<DuplicateSet>` - Sets of two or more identical files, most wasted bytes first
+pub(crate) fn find_duplicates(files: &[FileInfo]) -> Vec<DuplicateSet> {
+    let mut groups: HashMap<(u64, usize), Vec<&FileInfo>> = HashMap::new();
+    for file in files.iter().filter(|f| f.size_bytes > 0) {
+        // Including the size makes hash collisions between different files even less likely
+        groups.entry((file.content_hash, file.size_bytes)).or_default().push(file);
+    }
+    
+    let mut duplicates: Vec<DuplicateSet> = groups.into_values()
+        .filter(|group| group.len() > 1)
+        .map(|group| DuplicateSet {
+            tokens: if group[0].is_binary { 0 } else { group[0].tokens },
+            size_bytes: group[0].size_bytes,
+            paths: group.into_iter().map(|f| f.path.clone()).collect(),
+        })
+        .collect();
+    duplicates.sort_by(|a, b| b.wasted_bytes().cmp(&a.wasted_bytes()).then_with(|| a.paths.cmp(&b.paths)));
+    duplicates
+}
 
-                // Clone or Cancel button based on state
-                if self.is_cloning {
-                    // Show Cancel button when cloning
-                    if ui.add(
-                        egui::Button::new(
-                            egui::RichText::new("Cancel")
-                                .strong()
-                        )
-                        .min_size(egui::vec2(clone_button_width, 28.0))
-                    ).clicked() {
-                        self.cancel_requested = true;
-                        self.status_message = String::from("Cancelling clone operation...");
-                    }
-                } else {
-                    // Show Clone button when not cloning
-                    if ui.add_enabled(
-                        !self.git_url.is_empty(),
-                        egui::Button::new(
-                            egui::RichText::new("Clone")
-                                .strong()
-                        )
-                        .min_size(egui::vec2(clone_button_width, 28.0))
-                    ).clicked() {
-                        self.handle_clone_button();
-                    }
-                }
+/// Finds the files in a list that are empty on disk
+///
+/// # Arguments
+/// * `files` - The counted files to check
+///
+/// # Returns
+/// * `Vec<&FileInfo>` - Files with zero bytes and zero tokens, in list order
+fn empty_files(files: &[FileInfo]) -> Vec<&FileInfo> {
+    files.iter()
+        .filter(|f| f.tokens == 0 && f.size_bytes == 0)
+        .collect()
+}
 
-                ui.add_space(spacing);
+/// Empty directories and files listed in the empty report window
+struct EmptyReport {
+    directories: Vec<PathBuf>, // Topmost empty directories of the parsed tree
+    files: Vec<PathBuf>,       // Empty files in the list, in list order
+}
 
-                // Keep Repository checkbox
-                ui.checkbox(&mut self.keep_repository, "Keep Repository");
+/// Directory names that usually hold vendored third-party code
+const VENDOR_DIRECTORY_NAMES: &[&str] = &["vendor", "third_party", "node_modules", "bower_components", "Pods"];
 
-                ui.add_space(spacing);
+/// A directory that likely holds generated or vendored files
+struct GeneratedDirectory {
+    path: PathBuf,        // Full path to the directory
+    reason: &'static str, // Why the directory was flagged
+    files: usize,         // Files in the list under the directory
+    tokens: usize,        // Tokens in those files
+}
 
-                // Clear button
-                if ui.add(
-                    egui::Button::new(egui::RichText::new("Clear").strong())
-                        .min_size(egui::vec2(clear_button_width, 28.0))
-                ).clicked() {
-                    self.clear_repository();
-                }
+impl GeneratedDirectory {
+    /// Gets the ignore pattern that leaves the directory out of the parse
+    ///
+    /// # Arguments
+    /// * `root` - Repository root the pattern is anchored at
+    ///
+    /// # Returns
+    /// * `String` - The relative path with `/` separators and a trailing `/`, e.g. `api/gen/`
+    fn ignore_pattern(&self, root: Option<&Path>) -> String {
+        let relative = root
+            .and_then(|root| self.path.strip_prefix(root).ok())
+            .unwrap_or(&self.path);
+        let components: Vec<String> = relative.components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        format!("{}/", components.join("/"))
+    }
+}
 
-                ui.add_space(spacing);
+/// Checks whether a file name follows a code generator's naming convention
+///
+/// # Arguments
+/// * `name` - The file name
+///
+/// # Returns
+/// * `bool` - True for names like `api.pb.go`, `api_pb2.py` or `schema_generated.rs`
+fn is_generated_file_name(name: &str) -> bool {
+    name.ends_with(".pb.go") || name.ends_with("_pb2.py") || name.contains("_generated.") || name.contains(".generated.")
+}
 
-                // Theme toggle button
-                if ui.add(
-                    egui::Button::new(
-                        if self.ui_handler.is_dark_mode() { "Light" } else { "Dark" }
-                    )
-                    .min_size(egui::vec2(theme_button_width, 28.0))
-                ).clicked() {
-                    self.toggle_dark_mode();
-                }
-            });
+/// Flags the directories that likely hold generated or vendored files
+///
+/// A directory is flagged if its name is a common vendor directory name, or
+/// if at least half of the files directly in it have generated names or look
+/// minified. Directories inside a flagged one aren't listed separately.
+///
+/// # Arguments
+/// * `files` - The counted files to check
+/// * `root` - Repository root, which is never flagged itself
+///
+/// # Returns
+/// * `Vec<GeneratedDirectory>` - The flagged directories, most tokens first
+fn find_generated_directories(files: &[FileInfo], root: Option<&Path>) -> Vec<GeneratedDirectory> {
+    let below_root = |directory: &Path| root.is_none_or(|root| directory != root && directory.starts_with(root));
+    let mut flagged: HashMap<PathBuf, &'static str> = HashMap::new();
+    
+    // Vendor directories, wherever they are in the tree
+    for file in files {
+        for directory in file.path.ancestors().skip(1).filter(|directory| below_root(directory)) {
+            let is_vendor = directory.file_name()
+                .is_some_and(|name| VENDOR_DIRECTORY_NAMES.contains(&name.to_string_lossy().as_ref()));
+            if is_vendor {
+                flagged.insert(directory.to_path_buf(), "vendored dependencies");
+            }
+        }
+    }
+    
+    // Directories whose own files are mostly generated
+    let mut direct_files: HashMap<&Path, (usize, usize)> = HashMap::new();
+    for file in files {
+        let Some(parent) = file.path.parent().filter(|parent| below_root(parent)) else {
+            continue;
+        };
+        let generated = file.path.file_name()
+            .is_some_and(|name| is_generated_file_name(&name.to_string_lossy())) || file.is_likely_generated();
+        let counts = direct_files.entry(parent).or_default();
+        counts.0 += 1;
+        counts.1 += usize::from(generated);
+    }
+    for (directory, (total, generated)) in direct_files {
+        if generated > 0 && generated * 2 >= total {
+            flagged.entry(directory.to_path_buf()).or_insert("generated or minified files");
+        }
+    }
+    
+    // Nested directories are covered by the outermost flagged one
+    let outermost: Vec<(PathBuf, &'static str)> = flagged.iter()
+        .filter(|(directory, _)| !directory.ancestors().skip(1).any(|ancestor| flagged.contains_key(ancestor)))
+        .map(|(directory, reason)| (directory.clone(), *reason))
+        .collect();
+    let mut directories: Vec<GeneratedDirectory> = outermost.into_iter()
+        .map(|(path, reason)| {
+            let inside: Vec<&FileInfo> = files.iter().filter(|f| f.path.starts_with(&path)).collect();
+            GeneratedDirectory {
+                files: inside.len(),
+                tokens: inside.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum(),
+                path,
+                reason,
+            }
+        })
+        .collect();
+    directories.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+    directories
+}
 
-            ui.add_space(8.0); // Padding at the bottom
+/// Shows a file or directory in the platform's file manager
+///
+/// Files are selected in their folder where the file manager supports it;
+/// otherwise the containing folder is opened.
+///
+/// # Arguments
+/// * `path` - Path to reveal
+fn reveal_in_file_manager(path: &Path) {
+    #[cfg(target_os = "windows")]
+    {
+        spawn_detached(std::process::Command::new("explorer")
+            .arg(format!("/select,{}", path.display())))
+            .ok();
+    }
+    #[cfg(target_os = "macos")]
+    {
+        spawn_detached(std::process::Command::new("open")
+            .arg("-R")
+            .arg(path))
+            .ok();
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let folder = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+        spawn_detached(std::process::Command::new("xdg-open")
+            .arg(folder))
+            .ok();
+    }
+}
+
+/// Starts a program without waiting for it to exit
+///
+/// A background thread waits for the child, so it doesn't linger as a
+/// zombie process on Unix once it exits.
+///
+/// # Arguments
+/// * `command` - The program and its arguments
+///
+/// # Returns
+/// * `std::io::Result<()>` - An error if the program couldn't be started
+fn spawn_detached(command: &mut std::process::Command) -> std::io::Result<()> {
+    let mut child = command.spawn()?;
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// Builds the command that opens a file in the configured editor
+///
+/// `{path}` and `{line}` in the template are replaced, e.g. `code -g {path}:{line}`
+/// or `vim +{line} {path}`. Templates for editors that can't jump to a line
+/// leave out `{line}` and open the file at its start; a template without
+/// `{path}` gets the path as its last argument.
+///
+/// # Arguments
+/// * `template` - The command template, split into arguments at whitespace
+/// * `path` - The file to open
+/// * `line` - The line to jump to, starting at 1
+///
+/// # Returns
+/// * `Option<Vec<String>>` - The program and its arguments, or `None` for an empty template
+fn editor_command(template: &str, path: &Path, line: usize) -> Option<Vec<String>> {
+    let path = path.to_string_lossy();
+    let line = line.max(1).to_string();
+    let mut args: Vec<String> = template.split_whitespace()
+        .map(|arg| arg.replace("{path}", &path).replace("{line}", &line))
+        .collect();
+    if args.is_empty() {
+        return None;
+    }
+    if !template.contains("{path}") {
+        args.push(path.to_string());
+    }
+    Some(args)
+}
+
+/// Number of file types shown in the statistics chart
+const CHART_EXTENSIONS: usize = 8;
+
+/// Totals for the files sharing an extension
+struct ExtensionTotal {
+    extension: String, // Lowercased extension, empty for files without one
+    files: usize,
+    tokens: usize,     // Tokens in text files
+    bytes: usize,      // Size on disk of all files
+    value: usize,      // The total being charted, tokens or bytes
+}
+
+/// Totals tokens and bytes per file extension
+///
+/// # Arguments
+/// * `files` - The files to aggregate
+/// * `by_bytes` - Whether to rank by bytes instead of tokens
+///
+/// # Returns
+/// * `Vec<ExtensionTotal>` - Totals per extension, largest first
+fn extension_totals(files: &[FileInfo], by_bytes: bool) -> Vec<ExtensionTotal> {
+    let mut totals: HashMap<String, ExtensionTotal> = HashMap::new();
+    for file in files {
+        let extension = file.path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let total = totals.entry(extension.clone()).or_insert(ExtensionTotal {
+            extension,
+            files: 0,
+            tokens: 0,
+            bytes: 0,
+            value: 0,
         });
+        total.files += 1;
+        total.bytes += file.size_bytes;
+        if !file.is_binary {
+            total.tokens += file.tokens;
+        }
+        total.value = if by_bytes { total.bytes } else { total.tokens };
+    }
+    
+    let mut totals: Vec<ExtensionTotal> = totals.into_values().collect();
+    totals.sort_by(|a, b| b.value.cmp(&a.value).then_with(|| a.extension.cmp(&b.extension)));
+    totals
+}
+
+/// Totals for the files under a top-level directory
+struct DirectoryTotal {
+    name: Option<String>, // Directory below the root, `None` for files in the root
+    files: usize,
+    tokens: usize,        // Tokens in text files
+    bytes: usize,         // Size on disk of all files
+}
+
+/// Totals files, tokens and bytes per top-level directory
+///
+/// # Arguments
+/// * `files` - The files to aggregate
+/// * `root` - Repository root the directories are taken relative to
+///
+/// # Returns
+/// * `Vec<DirectoryTotal>` - Totals per directory, most tokens first
+fn directory_totals(files: &[FileInfo], root: Option<&Path>) -> Vec<DirectoryTotal> {
+    let mut totals: HashMap<Option<String>, DirectoryTotal> = HashMap::new();
+    for file in files {
+        let name = file.top_level_dir(root);
+        let total = totals.entry(name.clone()).or_insert(DirectoryTotal {
+            name,
+            files: 0,
+            tokens: 0,
+            bytes: 0,
+        });
+        total.files += 1;
+        total.bytes += file.size_bytes;
+        if !file.is_binary {
+            total.tokens += file.tokens;
+        }
+    }
+    
+    let mut totals: Vec<DirectoryTotal> = totals.into_values().collect();
+    totals.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.name.cmp(&b.name)));
+    totals
+}
+
+/// A parsed repository, or an error
+type ParseResult = Result<ParsedRepository, String>;
+
+/// A re-parse of a watched repository after some of its files changed
+struct ChangesRefresh {
+    /// Repository that was parsed
+    repo_path: PathBuf,
+    /// Paths reported as changed, whose counts are dropped
+    changed: HashSet<PathBuf>,
+    /// The parsed repository, or an error
+    result: ParseResult,
+}
+
+/// A counted file: (index, path, tokens, is_binary, size_bytes, content_hash, lfs_size, modified)
+pub(crate) type TokenResult = (usize, PathBuf, usize, bool, usize, ContentHash, Option<usize>, Option<SystemTime>);
+
+/// Default number of files counted without asking first
+const DEFAULT_MAX_FILE_COUNT: usize = 50_000;
+
+/// Shortest time between progress updates in the status bar at Normal verbosity
+const PROGRESS_STATUS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Shortest time between repaints while tokens are being counted
+const COUNTING_REPAINT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often results of other background work are polled; when nothing is
+/// running, frames are only drawn on input
+const BACKGROUND_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of times the list is sorted while tokens are being counted, besides once at the end
+const COUNTING_SORT_STEPS: usize = 4;
+
+/// Maximum number of failures kept in the Issues panel; older ones are dropped first
+const MAX_ISSUES: usize = 20;
+
+/// An operation that can be run again from the Issues panel
+#[derive(Debug, Clone, PartialEq)]
+enum RetryAction {
+    /// Clone the URL again
+    Clone(String),
+    /// Parse the repository at the path again
+    Parse(PathBuf),
+}
+
+/// A failure listed in the Issues panel until it is dismissed
+struct Issue {
+    /// When the failure happened
+    time: chrono::DateTime<chrono::Local>,
+    /// The error message, as shown in the error dialog
+    message: String,
+    /// How to run the failed operation again, if it can be
+    retry: Option<RetryAction>,
+}
+
+/// A parsed repository with more files than the limit, waiting for the user to confirm counting it
+struct LargeRepository {
+    /// The parsed repository
+    repository: ParsedRepository,
+    /// Number of files in the structure
+    file_count: usize,
+    /// Ignore patterns typed into the dialog, comma separated
+    ignore_patterns: String,
+}
+
+/// Settings for a clone started from the clone button
+struct CloneRequest {
+    /// The Git URL to clone
+    git_url: String,
+    /// Branch to check out, or `None` for the remote's default branch
+    branch: Option<String>,
+    /// SSH authentication settings, used for SSH URLs
+    ssh_config: SshConfig,
+    /// Whether HTTP redirects to other hosts are followed at every stage
+    follow_redirects: bool,
+    /// Remote name and which branches are fetched
+    fetch_config: FetchConfig,
+    /// Whether only files with uncommitted changes in a local source are analyzed
+    changes_only: bool,
+    /// Attempts made before giving up on transient network failures
+    max_attempts: usize,
+    /// Parser for the cloned directory, with the configured ignore patterns
+    parser: DirectoryParser,
+}
+
+/// Where a clone is written
+enum CloneDestination {
+    /// A new temporary directory
+    Temporary(tempfile::TempDir),
+    /// A directory chosen in the settings, which must not exist or be empty
+    Chosen(PathBuf),
+    /// A chosen directory already holding a clone, which is updated instead
+    Existing(PathBuf),
+}
+
+impl CloneDestination {
+    /// Gets the directory the repository is cloned into
+    fn path(&self) -> &Path {
+        match self {
+            Self::Temporary(dir) => dir.path(),
+            Self::Chosen(path) | Self::Existing(path) => path,
+        }
+    }
+    
+    /// Checks that a chosen directory can be cloned into
+    ///
+    /// # Arguments
+    /// * `path` - The chosen directory
+    ///
+    /// # Returns
+    /// * `Result<(), String>` - An error message if the directory is in use
+    fn check_chosen(path: &Path) -> Result<(), String> {
+        if !path.exists() {
+            return Ok(());
+        }
+        if !path.is_dir() {
+            return Err(format!("{} exists and is not a directory", path.display()));
+        }
+        match std::fs::read_dir(path) {
+            Ok(mut entries) => match entries.next() {
+                None => Ok(()),
+                Some(_) => Err(format!("{} is not empty; choose another directory or remove it first", path.display())),
+            },
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// Copies the files with uncommitted changes in a local repository into its clone
+///
+/// Cloning only transfers commits, so modified and untracked files are
+/// brought over separately for the working-tree changes mode.
+///
+/// # Arguments
+/// * `source` - The URL or path the repository was cloned from
+/// * `clone_path` - The working tree of the clone
+///
+/// # Returns
+/// * `Result<usize, String>` - The number of copied files, or why nothing could be copied
+fn copy_working_tree_changes(source: &str, clone_path: &Path) -> Result<usize, String> {
+    let source_path = PathBuf::from(source.strip_prefix("file://").unwrap_or(source));
+    if !source_path.is_dir() {
+        return Err("not a local repository".to_string());
+    }
+    
+    let changed = GitHandler::new(true).changed_files(&source_path)?;
+    for relative_path in &changed {
+        let target = clone_path.join(relative_path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::copy(source_path.join(relative_path), &target)
+            .map_err(|e| format!("Failed to copy {}: {}", relative_path.display(), e))?;
+    }
+    Ok(changed.len())
+}
+
+/// Limits a parsed tree to the files with uncommitted changes
+///
+/// The tree is left untouched when the changes can't be read or there are
+/// none, so everything is analyzed instead.
+///
+/// # Arguments
+/// * `root` - The parsed repository
+/// * `repo_path` - Path to the repository
+///
+/// # Returns
+/// * `Result<usize, String>` - The number of changed files kept, or why all files were kept
+fn retain_working_tree_changes(root: &mut DirectoryEntry, repo_path: &Path) -> Result<usize, String> {
+    let changed = GitHandler::new(true).changed_files(repo_path)?;
+    if changed.is_empty() {
+        return Err("no uncommitted changes".to_string());
+    }
+    
+    let keep: HashSet<PathBuf> = changed.iter().map(|path| repo_path.join(path)).collect();
+    root.retain_files(&keep);
+    Ok(keep.len())
+}
+
+/// Clones a repository and parses it
+///
+/// Runs on the background thread spawned by the clone button. On success a
+/// temporary directory is persisted so the clone outlives this function; it is
+/// removed later by `clear_repository` unless the repository is kept. On
+/// failure or cancellation a temporary directory is dropped and cleaned up
+/// immediately, and a partial clone in a chosen directory is removed.
+///
+/// # Arguments
+/// * `request` - What to clone and how to parse it
+/// * `destination` - The directory to clone into
+/// * `cancel_flag` - Flag checked during transfer to abort the clone
+/// * `clone_sender` - Channel for clone progress and the clone result
+/// * `parse_sender` - Channel for the directory parse result
+fn clone_and_parse(
+    request: &CloneRequest,
+    destination: CloneDestination,
+    cancel_flag: &AtomicBool,
+    clone_sender: &mpsc::Sender<CloneProgress>,
+    parse_sender: &mpsc::Sender<ParseResult>,
+) {
+    // Clone the repository with progress tracking, retrying transient failures
+    let mut attempt = 1;
+    let repo_result = loop {
+        let result = match &destination {
+            CloneDestination::Existing(path) => update_attempt(request, path, cancel_flag, clone_sender),
+            _ => clone_attempt(request, destination.path(), cancel_flag, clone_sender),
+        };
+        let retry = match &result {
+            Err(e) => attempt < request.max_attempts && is_transient_clone_error(e) && !cancel_flag.load(Ordering::SeqCst),
+            Ok(_) => false,
+        };
+        if !retry {
+            break result;
+        }
         
-        // Controls panel removed - consolidated into central panel
-        
-        // Optimized bottom panel with horizontal layout for status and stats
-        egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
-            ui.add_space(8.0);
+        // Start the next clone attempt from an empty directory; an update just fetches again
+        attempt += 1;
+        let _ = clone_sender.send(CloneProgress::Retrying(attempt, request.max_attempts));
+        if !matches!(destination, CloneDestination::Existing(_)) {
+            let _ = std::fs::remove_dir_all(destination.path());
+            let _ = std::fs::create_dir_all(destination.path());
+        }
+        if !sleep_unless_cancelled(clone_retry_delay(attempt - 1), cancel_flag) {
+            break result;
+        }
+    };
+    
+    // Don't leave a partial clone behind in a chosen directory
+    if let CloneDestination::Chosen(path) = &destination {
+        if repo_result.is_err() || cancel_flag.load(Ordering::SeqCst) {
+            let _ = std::fs::remove_dir_all(path);
+        }
+    }
+    
+    // Check if cancellation was requested
+    if cancel_flag.load(Ordering::SeqCst) {
+        let _ = clone_sender.send(CloneProgress::Completed(Err("Clone operation cancelled by user".to_string())));
+        return;
+    }
+    
+    // Send the final result
+    match repo_result {
+        Ok(repo) => {
+            // Take ownership of a temporary directory so it is not deleted when this thread ends
+            if let CloneDestination::Temporary(temp_dir) = destination {
+                let _ = temp_dir.into_path();
+            }
             
-            ui.horizontal(|ui| {
-                // Status message on the left
-                let status_width = ui.available_width() * 0.6;
-                ui.horizontal(|ui| {
-                    ui.set_width(status_width);
-                    self.ui_handler.render_status_bar(ui, &self.status_message, self.is_loading_tokens);
-                });
-                
-                // Stats on the right (if repository is loaded)
-                if self.directory_structure.is_some() {
-                    ui.separator();
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        let total_files = self.file_list.len();
-                        let text_files = self.file_list.iter().filter(|f| !f.is_binary).count();
-                        let binary_files = self.file_list.iter().filter(|f| f.is_binary).count();
-                        let total_tokens = self.file_list.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum::<usize>();
-                        let avg_tokens = if text_files > 0 { total_tokens / text_files } else { 0 };
-                        
-                        ui.label(format!("Avg: {} tokens/file", avg_tokens));
-                        ui.add_space(8.0);
-                        ui.label(egui::RichText::new(format!("Tokens: {}", total_tokens)).strong());
-                        ui.add_space(8.0);
-                        ui.label(egui::RichText::new(format!("Files: {} ({} text, {} bin)",
-                            total_files, text_files, binary_files)).strong());
-                    });
+            let repo_path = repo.path().parent().unwrap_or(repo.path()).to_path_buf();
+            if request.changes_only {
+                if let Err(e) = copy_working_tree_changes(&request.git_url, &repo_path) {
+                    let _ = clone_sender.send(CloneProgress::ChangesOnly(Err(e)));
                 }
-            });
+            }
+            let _ = clone_sender.send(CloneProgress::Completed(Ok(repo_path.clone())));
             
-            ui.add_space(8.0);
+            // Parse the directory structure
+            let mut parse_result = request.parser.parse_repository(&repo_path);
+            if let (true, Ok(parsed)) = (request.changes_only, &mut parse_result) {
+                let _ = clone_sender.send(CloneProgress::ChangesOnly(retain_working_tree_changes(&mut parsed.root_entry, &repo_path)));
+            }
+            let _ = parse_sender.send(parse_result);
+        },
+        Err(e) => {
+            // Missing SSH credentials get a dedicated message rather than a generic auth error
+            let error_message = if e.message().contains(SSH_UNAVAILABLE_MESSAGE) {
+                format!("{}. Start an SSH agent or set a private key path in Settings.", SSH_UNAVAILABLE_MESSAGE)
+            } else if attempt > 1 {
+                format!("{} (after {} attempts)", e, attempt)
+            } else {
+                e.to_string()
+            };
+            let _ = clone_sender.send(CloneProgress::Completed(Err(error_message)));
+        }
+    }
+}
+
+/// Runs a single clone attempt
+///
+/// Callbacks are set up afresh for every attempt, since the SSH credential
+/// callback remembers which methods it already tried.
+///
+/// # Arguments
+/// * `request` - What to clone
+/// * `path` - The empty directory to clone into
+/// * `cancel_flag` - Flag checked during transfer to abort the clone
+/// * `clone_sender` - Channel for clone progress
+///
+/// # Returns
+/// * `Result<git2::Repository, git2::Error>` - The cloned repository or the git2 error
+fn clone_attempt(
+    request: &CloneRequest,
+    path: &Path,
+    cancel_flag: &AtomicBool,
+    clone_sender: &mpsc::Sender<CloneProgress>,
+) -> Result<git2::Repository, git2::Error> {
+    use git2::RemoteCallbacks;
+
+    // Set up callbacks to track progress
+    let callbacks = || {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.transfer_progress(|stats| {
+            // Check if cancellation was requested
+            if cancel_flag.load(Ordering::SeqCst) {
+                // Return false to abort the transfer
+                return false;
+            }
+            
+            let _ = clone_sender.send(CloneProgress::Progress(TransferStats {
+                received_objects: stats.received_objects(),
+                total_objects: stats.total_objects(),
+                received_bytes: stats.received_bytes(),
+            }));
+            true
         });
         
-        // Main central panel
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if self.directory_structure.is_none() {
-                // Empty state when no repository is loaded
-                self.ui_handler.render_empty_state(ui, &mut self.git_url);
-            } else {
-                // Enhanced header section with title, sorting, filtering, and export options
-                ui.horizontal(|ui| {
-                    ui.heading("Repository Files");
-                    
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        // Add export button
-                        if ui.button("Export to CSV").clicked() {
-                            self.export_to_csv();
-                        }
-                        
-                        ui.add_space(8.0);
-                        
-                        // Add sort direction toggle
-                        let direction_text = match self.sort_direction {
-                            SortDirection::Ascending => "↑ Asc",
-                            SortDirection::Descending => "↓ Desc",
-                        };
-                        if ui.button(direction_text).clicked() {
-                            self.sort_direction = match self.sort_direction {
-                                SortDirection::Ascending => SortDirection::Descending,
-                                SortDirection::Descending => SortDirection::Ascending,
-                            };
-                            self.sort_file_list();
-                        }
-                        
-                        ui.add_space(4.0);
-                        
-                        // Add sort column selector
-                        egui::ComboBox::from_id_source("sort_column")
-                            .selected_text(match self.sort_column {
-                                SortColumn::Index => "Sort: Number",
-                                SortColumn::Name => "Sort: Name",
-                                SortColumn::Tokens => "Sort: Tokens",
-                            })
-                            .show_ui(ui, |ui| {
-                                if ui.selectable_value(&mut self.sort_column, SortColumn::Index, "Number").clicked() {
-                                    self.sort_file_list();
-                                }
-                                if ui.selectable_value(&mut self.sort_column, SortColumn::Name, "Name").clicked() {
-                                    self.sort_file_list();
-                                }
-                                if ui.selectable_value(&mut self.sort_column, SortColumn::Tokens, "Tokens").clicked() {
-                                    self.sort_file_list();
-                                }
-                            });
-                    });
-                });
-                
+        // SSH URLs need credentials from the agent or a key file
+        if GitHandler::is_ssh_url(&request.git_url) {
+            callbacks.credentials(request.ssh_config.credentials_callback());
+        }
+        callbacks
+    };
+    
+    request.fetch_config.clone_repository(&request.git_url, path, request.branch.as_deref(), request.follow_redirects, callbacks)
+}
+
+/// Runs a single update of an existing clone
+///
+/// Fetches from the configured remote and fast-forwards the checked-out
+/// branch to its upstream. Local commits that would need a merge fail the
+/// update rather than being touched, and local changes block a checkout the
+/// same way switching branches does.
+///
+/// # Arguments
+/// * `request` - Supplies the remote name, SSH settings and redirect policy
+/// * `path` - The working tree of the existing clone
+/// * `cancel_flag` - Flag checked during transfer to abort the fetch
+/// * `clone_sender` - Channel for fetch progress
+///
+/// # Returns
+/// * `Result<git2::Repository, git2::Error>` - The updated repository or the git2 error
+fn update_attempt(
+    request: &CloneRequest,
+    path: &Path,
+    cancel_flag: &AtomicBool,
+    clone_sender: &mpsc::Sender<CloneProgress>,
+) -> Result<git2::Repository, git2::Error> {
+    let repo = git2::Repository::open(path)?;
+    {
+        let mut remote = repo.find_remote(&request.fetch_config.remote_name)?;
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.transfer_progress(|stats| {
+            if cancel_flag.load(Ordering::SeqCst) {
+                return false;
+            }
+            let _ = clone_sender.send(CloneProgress::Progress(TransferStats {
+                received_objects: stats.received_objects(),
+                total_objects: stats.total_objects(),
+                received_bytes: stats.received_bytes(),
+            }));
+            true
+        });
+        if remote.url().is_some_and(GitHandler::is_ssh_url) {
+            callbacks.credentials(request.ssh_config.credentials_callback());
+        }
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        fetch_options.follow_redirects(GitHandler::redirect_policy(request.follow_redirects));
+        remote.fetch::<&str>(&[], Some(&mut fetch_options), None)?;
+    }
+    fast_forward(&repo)?;
+    Ok(repo)
+}
+
+/// Fast-forwards the checked-out branch to its upstream
+///
+/// # Arguments
+/// * `repo` - The repository, after fetching
+///
+/// # Returns
+/// * `Result<(), git2::Error>` - An error if the branch has diverged or the checkout fails
+fn fast_forward(repo: &git2::Repository) -> Result<(), git2::Error> {
+    // A detached HEAD or a branch without upstream has nothing to fast-forward
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Ok(());
+    }
+    let mut branch = git2::Branch::wrap(head);
+    let Ok(upstream) = branch.upstream() else {
+        return Ok(());
+    };
+    let target = upstream.get().peel_to_commit()?;
+    let (analysis, _) = repo.merge_analysis(&[&repo.find_annotated_commit(target.id())?])?;
+    if analysis.is_fast_forward() {
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.safe();
+        repo.checkout_tree(target.as_object(), Some(&mut checkout))?;
+        branch.get_mut().set_target(target.id(), "git_scroll: fast-forward")?;
+    } else if !analysis.is_up_to_date() {
+        return Err(git2::Error::from_str("the branch has local commits and can't be fast-forwarded; choose another directory"));
+    }
+    Ok(())
+}
+
+/// Delay before the first clone retry; each further retry waits twice as long
+const CLONE_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Gets the delay before a clone retry
+///
+/// # Arguments
+/// * `retry` - The retry number, starting at 1
+///
+/// # Returns
+/// * `Duration` - The exponential backoff delay
+fn clone_retry_delay(retry: usize) -> Duration {
+    CLONE_RETRY_BASE_DELAY * 2u32.saturating_pow(retry.saturating_sub(1) as u32)
+}
+
+/// Checks whether a clone failure is worth retrying
+///
+/// Network errors, timeouts and server errors are transient; authentication,
+/// missing repositories, certificate problems and cancellation are not.
+///
+/// # Arguments
+/// * `error` - The git2 error of the failed attempt
+///
+/// # Returns
+/// * `bool` - True if another attempt may succeed
+fn is_transient_clone_error(error: &git2::Error) -> bool {
+    use git2::{ErrorClass, ErrorCode};
+    
+    if matches!(error.code(), ErrorCode::Auth | ErrorCode::NotFound | ErrorCode::Certificate | ErrorCode::User) {
+        return false;
+    }
+    
+    let message = error.message().to_lowercase();
+    let timed_out = ["timed out", "timeout", "connection reset", "temporarily unavailable"]
+        .iter()
+        .any(|hint| message.contains(hint));
+    match error.class() {
+        ErrorClass::Net => true,
+        // Only server errors (5xx) are transient; 401 or 404 won't change on retry
+        ErrorClass::Http => timed_out || message.contains("status code: 5"),
+        _ => timed_out,
+    }
+}
+
+/// Sleeps for a duration, waking early if the clone is cancelled
+///
+/// # Arguments
+/// * `duration` - How long to sleep
+/// * `cancel_flag` - Flag checked while sleeping
+///
+/// # Returns
+/// * `bool` - False if the clone was cancelled
+fn sleep_unless_cancelled(duration: Duration, cancel_flag: &AtomicBool) -> bool {
+    let step = Duration::from_millis(50);
+    let mut slept = Duration::ZERO;
+    while slept < duration {
+        if cancel_flag.load(Ordering::SeqCst) {
+            return false;
+        }
+        thread::sleep(step);
+        slept += step;
+    }
+    !cancel_flag.load(Ordering::SeqCst)
+}
+
+/// Default number of leading lines shown in the file list tooltip
+const TOOLTIP_PREVIEW_LINES: usize = 8;
+
+/// Widest the file list tooltip grows before wrapping long lines
+const TOOLTIP_MAX_WIDTH: f32 = 480.0;
+
+/// Takes the leading lines of a file for its tooltip
+///
+/// # Arguments
+/// * `content` - The loaded file content
+/// * `lines` - Maximum number of lines to keep
+///
+/// # Returns
+/// * `Option<String>` - The lines, or `None` if there is nothing to show
+fn tooltip_head(content: &str, lines: usize) -> Option<String> {
+    let head: Vec<&str> = content.lines().take(lines).collect();
+    if head.iter().all(|line| line.trim().is_empty()) {
+        return None;
+    }
+    Some(head.join("\n"))
+}
+
+/// Enum for sortable columns
+#[derive(PartialEq, Clone, Copy)]
+pub enum SortColumn {
+    Index,
+    Name,
+    Tokens,
+    Size,    // Bytes on disk
+    Density, // Tokens per byte
+    Depth,   // Directories below the repository root
+}
+
+impl SortColumn {
+    /// Checks whether the order depends on the token counts
+    ///
+    /// # Returns
+    /// * `bool` - True for the columns that change as files are counted
+    fn depends_on_counts(self) -> bool {
+        matches!(self, SortColumn::Tokens | SortColumn::Size | SortColumn::Density)
+    }
+}
+
+/// Checks whether a running count has received enough new results to sort again
+///
+/// # Arguments
+/// * `unsorted` - Results received since the list was last sorted
+/// * `total` - Files the count started with
+///
+/// # Returns
+/// True once a `COUNTING_SORT_STEPS`th of the count is unsorted, so the
+/// number of sorts doesn't grow with the number of files
+fn sort_due(unsorted: usize, total: usize) -> bool {
+    unsorted > 0 && unsorted * COUNTING_SORT_STEPS >= total
+}
+
+/// A column of the file table
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+enum FileColumn {
+    Index,
+    Name,
+    Value,    // Tokens, bytes or both, per the metric basis
+    Size,     // Bytes on disk
+    Density,  // Tokens per byte
+    Modified, // Last modification time on disk
+    Depth,    // Directories below the repository root
+}
+
+impl FileColumn {
+    /// Gets the column header
+    ///
+    /// # Arguments
+    /// * `basis` - What the value column measures
+    fn header(&self, basis: MetricBasis) -> &'static str {
+        match self {
+            Self::Index => "Number",
+            Self::Name => "File Name",
+            Self::Value => basis.column_header(),
+            Self::Size => "Size",
+            Self::Density => "Tokens/Byte",
+            Self::Modified => "Modified",
+            Self::Depth => "Depth",
+        }
+    }
+    
+    /// Gets the sort order the column's header switches to
+    ///
+    /// # Returns
+    /// The sort column, or `None` for columns that can't be sorted by
+    fn sort_column(&self) -> Option<SortColumn> {
+        match self {
+            Self::Index => Some(SortColumn::Index),
+            Self::Name => Some(SortColumn::Name),
+            Self::Value => Some(SortColumn::Tokens),
+            Self::Size => Some(SortColumn::Size),
+            Self::Density => Some(SortColumn::Density),
+            Self::Depth => Some(SortColumn::Depth),
+            // Sorting would read the metadata of every file
+            Self::Modified => None,
+        }
+    }
+}
+
+/// A column of the file table with its layout
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct TableColumn {
+    kind: FileColumn,
+    width: f32,
+    visible: bool,
+}
+
+/// Gets every column of the file table in the default order
+///
+/// # Returns
+/// * `Vec<TableColumn>` - The columns, with Index, Name and Value shown
+fn default_table_columns() -> Vec<TableColumn> {
+    [
+        (FileColumn::Index, 60.0, true),
+        (FileColumn::Name, 400.0, true),
+        (FileColumn::Value, 100.0, true),
+        (FileColumn::Size, 90.0, false),
+        (FileColumn::Density, 90.0, false),
+        (FileColumn::Modified, 130.0, false),
+        (FileColumn::Depth, 60.0, false),
+    ]
+    .into_iter()
+    .map(|(kind, width, visible)| TableColumn { kind, width, visible })
+    .collect()
+}
+
+/// Restores saved table columns, adding any column missing from them
+///
+/// # Arguments
+/// * `saved` - The columns as they were saved
+///
+/// # Returns
+/// * `Vec<TableColumn>` - Every column once, in the saved order followed by
+///   the missing ones with their default layout
+fn restore_table_columns(saved: Vec<TableColumn>) -> Vec<TableColumn> {
+    let mut columns: Vec<TableColumn> = Vec::new();
+    for column in saved.into_iter().chain(default_table_columns()) {
+        if columns.iter().all(|other| other.kind != column.kind) {
+            columns.push(column);
+        }
+    }
+    columns
+}
+
+/// Enum for sort direction
+#[derive(PartialEq, Clone, Copy)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// Object and byte counts reported while a clone transfers
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TransferStats {
+    received_objects: usize,
+    total_objects: usize,
+    received_bytes: usize,
+}
+
+impl TransferStats {
+    /// Gets the fraction of objects received, for the progress bar
+    ///
+    /// # Returns
+    /// * `f32` - Progress between 0.0 and 1.0
+    fn fraction(&self) -> f32 {
+        if self.total_objects > 0 {
+            (self.received_objects as f32 / self.total_objects as f32).min(1.0)
+        } else {
+            0.0
+        }
+    }
+    
+    /// Describes the transfer for the status bar
+    ///
+    /// # Returns
+    /// * `String` - e.g. "12,340 / 45,000 objects (23.4 MiB)"
+    fn message(&self) -> String {
+        let size = if self.received_bytes >= 1024 * 1024 {
+            format!("{:.1} MiB", self.received_bytes as f64 / (1024.0 * 1024.0))
+        } else {
+            format!("{:.1} KiB", self.received_bytes as f64 / 1024.0)
+        };
+        format!(
+            "{} / {} objects ({})",
+            format_thousands(self.received_objects),
+            format_thousands(self.total_objects),
+            size
+        )
+    }
+}
+
+/// Renders a statistic as selectable text with a button that copies its exact value
+///
+/// # Arguments
+/// * `ui` - The egui UI to render to
+/// * `text` - The statistic as shown, e.g. "Total Tokens: 1.4M"
+/// * `exact` - The value copied to the clipboard, e.g. "1423875"
+///
+/// # Returns
+/// * `egui::Response` - The response of the text, for adding hover text
+fn copyable_stat(ui: &mut egui::Ui, text: impl Into<egui::WidgetText>, exact: impl ToString) -> egui::Response {
+    let exact = exact.to_string();
+    ui.horizontal(|ui| {
+        let response = ui.add(egui::Label::new(text).selectable(true));
+        if ui.small_button("📋").on_hover_text(format!("Copy {}", exact)).clicked() {
+            ui.ctx().copy_text(exact);
+        }
+        response
+    }).inner
+}
+
+/// Formats a number with commas between groups of three digits
+///
+/// # Arguments
+/// * `value` - The number to format
+///
+/// # Returns
+/// * `String` - The grouped number (e.g., "45,000")
+fn format_thousands(value: usize) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Progress information for cloning operations
+enum CloneProgress {
+    Progress(TransferStats),
+    Retrying(usize, usize), // (attempt about to start, maximum attempts)
+    ChangesOnly(Result<usize, String>), // Changed files analyzed, or why all files are
+    Completed(Result<PathBuf, String>),
+}
+
+/// Default time a row stays highlighted after another panel navigates to it, in seconds
+const ROW_HIGHLIGHT_SECONDS: f64 = 1.5;
+
+/// Storage key of whether transitions and the row highlight animate
+const ANIMATIONS_KEY: &str = "animations";
+
+/// Storage key of how long a row navigated to stays highlighted
+const HIGHLIGHT_SECONDS_KEY: &str = "highlight_seconds";
+
+/// Storage key of the chosen theme mode
+const THEME_MODE_KEY: &str = "theme_mode";
+
+/// Storage key of the file table columns with their order, widths and visibility
+const TABLE_COLUMNS_KEY: &str = "table_columns";
+
+/// Storage key of the custom extension presets; the built-in ones are never saved
+const EXTENSION_PRESETS_KEY: &str = "extension_presets";
+
+/// Storage key of the pinned files of every repository
+const PINNED_FILES_KEY: &str = "pinned_files";
+
+/// How long file system events are batched before a watched repository refreshes
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// A file system watcher on the active tab's repository
+struct RepositoryWatcher {
+    /// The watched repository directory
+    path: PathBuf,
+    /// The underlying watcher; watching stops when it is dropped
+    _debouncer: Debouncer<RecommendedWatcher>,
+    /// Receives batches of changed paths
+    receiver: mpsc::Receiver<Vec<PathBuf>>,
+}
+
+impl RepositoryWatcher {
+    /// Starts watching a repository directory recursively
+    ///
+    /// # Arguments
+    /// * `path` - The repository directory
+    /// * `ctx` - The egui context, repainted when changes arrive
+    ///
+    /// # Returns
+    /// * `Result<Self, String>` - The watcher or an error message
+    fn new(path: &Path, ctx: egui::Context) -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel();
+        let mut debouncer = new_debouncer(WATCH_DEBOUNCE, move |result: DebounceEventResult| {
+            if let Ok(events) = result {
+                let _ = sender.send(events.into_iter().map(|event| event.path).collect());
+                ctx.request_repaint();
+            }
+        }).map_err(|e| e.to_string())?;
+        debouncer.watcher()
+            .watch(path, RecursiveMode::Recursive)
+            .map_err(|e| e.to_string())?;
+        
+        Ok(Self {
+            path: path.to_path_buf(),
+            _debouncer: debouncer,
+            receiver,
+        })
+    }
+}
+
+/// Branches of the active repository, read once per repository
+struct BranchList {
+    /// Repository the branches belong to
+    repo_path: PathBuf,
+    /// Local and remote branch names, without remote prefixes
+    names: Vec<String>,
+    /// Checked out branch, `None` for a detached HEAD
+    current: Option<String>,
+}
+
+/// Repository data for a single tab
+///
+/// The active tab's data lives directly on `GitScrollApp`; a session only holds
+/// its data while the tab is inactive, and swaps it back in when switched to.
+struct RepoSession {
+    /// Title shown on the tab
+    title: String,
+    repository_path: Option<PathBuf>,
+    repository_source: Option<String>,
+    directory_structure: Option<DirectoryEntry>,
+    directory_statistics: Option<DirectoryStatistics>,
+    file_list: Vec<FileInfo>,
+    token_counts: HashMap<PathBuf, (usize, bool, usize, ContentHash)>,
+    lfs_pointers: HashMap<PathBuf, usize>,
+    modified_times: HashMap<PathBuf, SystemTime>,
+    link_only: HashSet<PathBuf>,
+    pinned_paths: HashSet<PathBuf>,
+    current_page: usize,
+    is_loading_tokens: bool,
+    counting_progress: (usize, usize),
+    sorted_progress: usize,
+    counting_cancel: Arc<AtomicBool>,
+    token_receiver: mpsc::Receiver<TokenResult>,
+    common_words: Option<Vec<(String, usize)>>,
+    word_receiver: mpsc::Receiver<Vec<(String, usize)>>,
+    statistics_receiver: mpsc::Receiver<DirectoryStatistics>,
+}
+
+impl RepoSession {
+    /// Creates an empty session with the given tab title
+    fn new(title: String) -> Self {
+        let (_token_sender, token_receiver) = mpsc::channel();
+        Self {
+            title,
+            repository_path: None,
+            repository_source: None,
+            directory_structure: None,
+            directory_statistics: None,
+            file_list: Vec::new(),
+            token_counts: HashMap::new(),
+            lfs_pointers: HashMap::new(),
+            modified_times: HashMap::new(),
+            link_only: HashSet::new(),
+            pinned_paths: HashSet::new(),
+            current_page: 0,
+            is_loading_tokens: false,
+            counting_progress: (0, 0),
+            sorted_progress: 0,
+            counting_cancel: Arc::new(AtomicBool::new(false)),
+            token_receiver,
+            common_words: None,
+            word_receiver: mpsc::channel().1,
+            statistics_receiver: mpsc::channel().1,
+        }
+    }
+}
+
+/// Picks the page of the file list to show
+///
+/// # Arguments
+/// * `current_page` - The page shown so far
+/// * `target` - Position of a row that must be visible, if any
+/// * `len` - Number of rows in the list
+/// * `items_per_page` - Rows per page
+///
+/// # Returns
+/// The page holding the target, or else the current page clamped to the last page
+fn visible_page(current_page: usize, target: Option<usize>, len: usize, items_per_page: usize) -> usize {
+    let items_per_page = items_per_page.max(1);
+    match target {
+        Some(position) => position / items_per_page,
+        None => current_page.min(len.saturating_sub(1) / items_per_page),
+    }
+}
+
+/// Checks whether a file is one of the given paths or lies in one of them
+///
+/// # Arguments
+/// * `path` - The file to check
+/// * `subset` - Paths of files and folders
+///
+/// # Returns
+/// True if the file or one of its parent folders is in the subset
+fn is_in_subset(path: &Path, subset: &HashSet<PathBuf>) -> bool {
+    path.ancestors().any(|ancestor| subset.contains(ancestor))
+}
+
+/// Builds a Markdown summary of a repository for pasting into a PR or chat
+///
+/// # Arguments
+/// * `title` - Repository name
+/// * `metadata` - Branch and commit count, if the Git history could be read
+/// * `statistics` - Directory statistics, used for the size and languages
+/// * `files` - The file list, used for the file and token totals
+/// * `generated_at` - Timestamp to include in the summary
+///
+/// # Returns
+/// * `String` - The summary as Markdown
+fn repository_summary(
+    title: &str,
+    metadata: Option<&RepositoryMetadata>,
+    statistics: Option<&DirectoryStatistics>,
+    files: &[FileInfo],
+    generated_at: &str,
+) -> String {
+    let text_files = files.iter().filter(|f| !f.is_binary).count();
+    let total_tokens = files.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum::<usize>();
+    let total_size = statistics.map_or_else(
+        || files.iter().map(|f| f.size_bytes).sum::<usize>(),
+        |s| s.total_size_bytes as usize,
+    );
+    
+    let mut summary = format!("## {}\n\n", title);
+    if let Some(metadata) = metadata {
+        summary.push_str(&format!("- Branch: {}\n", metadata.branch));
+        summary.push_str(&format!("- Commits: {}\n", metadata.commit_count));
+    }
+    summary.push_str(&format!("- Files: {} ({} text, {} binary)\n", files.len(), text_files, files.len() - text_files));
+    summary.push_str(&format!("- Tokens (text files): {}\n", total_tokens));
+    summary.push_str(&format!("- Size: {}\n", format_file_size(total_size)));
+    
+    if let Some(statistics) = statistics {
+        let mut file_types = statistics.file_types.iter().collect::<Vec<_>>();
+        file_types.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        if !file_types.is_empty() {
+            let languages = file_types.iter()
+                .take(5)
+                .map(|(extension, count)| format!(".{} ({})", extension, count))
+                .collect::<Vec<_>>()
+                .join(", ");
+            summary.push_str(&format!("- Top languages: {}\n", languages));
+        }
+    }
+    
+    summary.push_str(&format!("\n_Generated by Git Scroll on {}_\n", generated_at));
+    summary
+}
+
+/// Default deepest level the file list indents to
+const MAX_TREE_INDENT: usize = 6;
+
+/// Gets how deep a file sits below the repository root
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `root` - Repository root; paths outside it are measured from their first component
+///
+/// # Returns
+/// * `usize` - Number of directories between the root and the file
+fn tree_depth(path: &Path, root: Option<&Path>) -> usize {
+    let relative = root
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    relative.components()
+        .filter(|component| matches!(component, std::path::Component::Normal(_)))
+        .count()
+        .saturating_sub(1)
+}
+
+/// Finds the next file whose path contains a query, wrapping around the list
+///
+/// # Arguments
+/// * `files` - The file list, in display order
+/// * `root` - Repository root, stripped from paths before matching
+/// * `query` - Text to look for, matched case-insensitively
+/// * `start` - Position to search from, included in the search
+/// * `backwards` - Whether to search towards the top of the list
+///
+/// # Returns
+/// * `Option<usize>` - Position of the matching file, or `None` if nothing matches
+fn find_match(files: &[FileInfo], root: Option<&Path>, query: &str, start: usize, backwards: bool) -> Option<usize> {
+    if files.is_empty() || query.is_empty() {
+        return None;
+    }
+    
+    let query = query.to_lowercase();
+    let count = files.len();
+    let start = start % count;
+    (0..count)
+        .map(|step| if backwards { (start + count - step) % count } else { (start + step) % count })
+        .find(|&position| {
+            let path = &files[position].path;
+            let relative_path = root.and_then(|root| path.strip_prefix(root).ok()).unwrap_or(path);
+            relative_path.to_string_lossy().to_lowercase().contains(&query)
+        })
+}
+
+/// Formats a file's contents for pasting into an LLM prompt
+///
+/// # Arguments
+/// * `relative_path` - Path of the file within the repository, used as the header
+/// * `contents` - The file's text
+/// * `tokens` - The file's token count, appended as a footer
+///
+/// # Returns
+/// * `String` - The contents between a `// path` header and a token count footer
+fn prompt_snippet(relative_path: &Path, contents: &str, tokens: usize) -> String {
+    let mut snippet = format!("// {}\n{}", relative_path.display(), contents);
+    if !snippet.ends_with('\n') {
+        snippet.push('\n');
+    }
+    snippet.push_str(&format!("// {} tokens\n", tokens));
+    snippet
+}
+
+/// Derives a tab title from a repository URL
+///
+/// # Arguments
+/// * `url` - The Git URL
+///
+/// # Returns
+/// * `String` - The last path segment without a `.git` suffix
+fn repository_title(url: &str) -> String {
+    let name = url.trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(url);
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    if name.is_empty() {
+        String::from("Repository")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Main application state for Git Scroll
+pub struct GitScrollApp {
+    // Input state
+    git_url: String,
+    pasted_url: Option<String>, // Valid URL pasted outside the URL field, offered as a hint
+    existing_clone: Option<PathBuf>, // Chosen clone directory already holding a repository, awaiting update or another directory
+    keep_repository: bool,
+    watch_repository: bool, // Refresh a kept repository when its files change
+    follow_redirects: bool, // Follow HTTP redirects to other hosts at every stage of a clone
+    clone_directory: String, // Directory kept repositories are cloned into, empty for a temporary one
+    clone_retries: usize,   // Retries after transient network failures while cloning
+    counting_threads: usize, // Worker threads used to count tokens
+    editor_command: String, // Opens a file at a line, with {path} and {line} placeholders; empty for none
+    max_file_count: usize,  // Ask before counting more files than this, 0 for no limit
+    clone_branch: String,   // Empty to clone the remote's default branch
+    clone_remote_name: String, // Empty for the default remote name
+    fetch_all_branches: bool, // Fetch every branch even when a branch to clone is named
+    changes_only: bool,     // Analyze only files with uncommitted changes in a local repository
+    ssh_key_path: String,   // Empty to use the default key locations
+    ssh_passphrase: String, // Empty for keys without a passphrase
+    
+    // Application state
+    status_message: String,
+    status_kind: StatusKind,
+    status_verbosity: StatusVerbosity, // How often progress updates replace the status message
+    last_progress_status: Option<Instant>, // When a progress update was last shown
+    event_log: EventLog,    // Finished operations and errors, for the log panel
+    error_dialog: Option<String>, // Error shown in a dialog until dismissed
+    issues: Vec<Issue>,     // Recent failures, oldest first, kept until dismissed
+    show_issues: bool,      // Whether the issues panel is shown
+    clone_url: String,      // URL of the running or last clone, for retrying it
+    large_repository: Option<LargeRepository>, // Parsed repository over the file limit, until confirmed
+    show_event_log: bool,   // Whether the log panel is shown
+    is_cloning: bool,
+    is_refreshing: bool,    // Re-parsing the active tab's repository in the background
+    cancel_requested: bool, // Flag to cancel cloning operation
+    clone_cancel: Arc<AtomicBool>, // Checked by the running clone's thread; each clone gets its own
+    
+    // Repository data for the active tab
+    repository_path: Option<PathBuf>,
+    directory_structure: Option<DirectoryEntry>,
+    directory_statistics: Option<DirectoryStatistics>,
+    
+    // Repository tabs
+    sessions: Vec<RepoSession>,
+    active_session: usize,
+    show_comparison: bool,
+    show_duplicates: bool,  // Whether the duplicate files window is open
+    duplicates: Option<Vec<DuplicateSet>>, // Sets of identical files, found again after the file list or counts change
+    show_empty_report: bool, // Whether the empty directories and files window is open
+    empty_report: Option<EmptyReport>, // Found again after the directory structure or file list changes
+    show_generated_dirs: bool, // Whether the likely generated and vendored directories window is open
+    generated_dirs: Option<Vec<GeneratedDirectory>>, // Flagged directories, found again after the file list changes
+    show_common_words: bool, // Whether the common words window is open
+    repository_watcher: Option<RepositoryWatcher>, // Watches the active tab's repository
+    
+    // Module handlers
+    git_handler: GitHandler,
+    directory_parser: DirectoryParser,
+    ui_handler: UiHandler,
+    
+    // UI state
+    show_stats_panel: bool,
+    chart_by_bytes: bool,    // Whether the extension chart shows bytes instead of tokens
+    show_settings: bool,
+    show_about: bool,        // Whether the about window is open
+    about_commit_id: Option<Option<String>>, // HEAD commit shown in the about window, read once each time it opens
+    filter_pattern: String,
+    show_advanced_filters: bool,
+    filter_extension: String,
+    extension_presets: Vec<ExtensionPreset>, // Built-in presets followed by custom ones
+    active_preset: usize,    // Index into extension_presets; 0 includes every file
+    new_preset_name: String, // Name typed for a custom preset
+    new_preset_extensions: String, // Extensions typed for a custom preset
+    filter_token_min: usize,
+    filter_token_max: usize,
+    filter_hide_below_tokens: usize, // Hide text files with fewer tokens (0 = off)
+    filter_hide_below_bytes: usize,  // Hide binary files smaller than this (0 = off)
+    hidden_small_files: usize,       // Files currently hidden by the two thresholds above
+    exclude_binary_above_mb: usize,  // Exclude binary files larger than this many MB (0 = off)
+    excluded_large_files: (usize, usize), // Files excluded by the size limit above, with their total bytes
+    
+    // File list state
+    file_list: Vec<FileInfo>,
+    token_counts: HashMap<PathBuf, (usize, bool, usize, ContentHash)>, // Counted (tokens, is_binary, size_bytes, content_hash) for every file, filtered or not
+    lfs_pointers: HashMap<PathBuf, usize>, // Git LFS pointer files with the sizes of their real files
+    modified_times: HashMap<PathBuf, SystemTime>, // Modification times of the counted files
+    link_only: HashSet<PathBuf>,           // Symlinked files counted as links, found when the repository is parsed
+    pinned_paths: HashSet<PathBuf>,        // Files pinned to the top of the list, kept across refreshes and filters
+    repository_source: Option<String>,     // URL or path the active repository was cloned from, keying its saved pins
+    saved_pins: HashMap<String, Vec<PathBuf>>, // Pinned files of every repository by source, relative to its root
+    sort_column: SortColumn,
+    sort_direction: SortDirection,
+    is_loading_tokens: bool,
+    counting_progress: (usize, usize),         // Files counted and to count in the running count
+    sorted_progress: usize,                    // Files counted when the list was last sorted
+    counting_cancel: Arc<AtomicBool>,          // Set when the running count is replaced or cleared
+    count_on_load: bool,                       // Whether every file is counted after parsing, or only on request
+    tally_words: bool,                         // Whether counting also tallies the most common words
+    common_words: Option<Vec<(String, usize)>>, // Most common words, once counting has finished
+    
+    // Table UI state
+    table_columns: Vec<TableColumn>, // Every file table column in display order, shown or not
+    compact_counts: bool,      // Whether token counts are shown as 1.4M, 14.2k
+    metric_basis: MetricBasis, // What the value column, totals and exports measure
+    animations: bool,          // Whether transitions and the row highlight animate
+    highlight_seconds: f64,    // How long a row navigated to stays highlighted
+    tooltip_lines: usize,      // Leading lines of a file shown in its tooltip, 0 for none
+    preview_max_kb: usize,     // Most of a file read for the preview pane and tooltips, in KB
+    tooltip_wrap: bool,        // Whether long tooltip lines wrap instead of being cut off
+    max_tree_indent: usize,    // Deepest level file names are indented to
+    text_extensions: String,   // Comma separated extensions expected to hold text
+    tokenizer: TokenizerKind,  // How text files are split into tokens
+    current_page: usize,     // Current page for pagination
+    
+    // File preview state
+    preview_path: Option<PathBuf>,                            // File shown in the preview pane
+    preview_cursor_line: usize,                               // Line of the cursor in the preview, starting at 1
+    scroll_to_index: Option<usize>,                           // File index to bring into view in the table
+    highlighted_row: Option<(usize, f64)>,                    // File index flashed after scrolling, with the start time
+    preview_cache: FileContentCache,                          // Loaded previews, shared with the file list tooltips
+    branch_list: Option<BranchList>,         // Branches of the active repository, reloaded when it changes
+    
+    // Find-as-you-type state
+    find_open: bool,             // Whether the find bar is shown
+    find_query: String,          // Text matched against file paths
+    find_current: Option<usize>, // File index of the current match
+    
+    // Background processing channels
+    clone_receiver: mpsc::Receiver<CloneProgress>,
+    parse_receiver: mpsc::Receiver<ParseResult>,
+    token_receiver: mpsc::Receiver<TokenResult>,
+    word_receiver: mpsc::Receiver<Vec<(String, usize)>>,
+    statistics_receiver: mpsc::Receiver<DirectoryStatistics>, // Replaced to drop stale statistics
+    changes_receiver: mpsc::Receiver<ChangesRefresh>, // Re-parse after watched files changed, replaced by the next one
+    pending_changes: HashSet<PathBuf>, // Changed paths of the running re-parse, carried into a newer one
+}
+
+impl GitScrollApp {
+    /// Creates a new instance of the GitScrollApp
+    ///
+    /// # Arguments
+    /// * `storage` - Settings saved by an earlier run, or `None` to start from the defaults
+    ///
+    /// Returns a new GitScrollApp with default values, overridden by any saved settings
+    pub fn new(storage: Option<&dyn eframe::Storage>) -> Self {
+        // Create channels for background processing
+        let (_clone_sender, clone_receiver) = mpsc::channel::<CloneProgress>();
+        let (_parse_sender, parse_receiver) = mpsc::channel();
+        let (_token_sender, token_receiver) = mpsc::channel();
+        
+        // Initialize with default values
+        let mut app = Self {
+            git_url: String::new(),
+            pasted_url: None,
+            existing_clone: None,
+            keep_repository: false,
+            watch_repository: false,
+            follow_redirects: false,
+            clone_directory: String::new(),
+            clone_retries: 2,
+            counting_threads: default_counting_threads(),
+            editor_command: String::new(),
+            max_file_count: DEFAULT_MAX_FILE_COUNT,
+            clone_branch: String::new(),
+            clone_remote_name: String::new(),
+            fetch_all_branches: false,
+            changes_only: false,
+            ssh_key_path: String::new(),
+            ssh_passphrase: String::new(),
+            status_message: String::from("Ready"),
+            status_kind: StatusKind::Info,
+            status_verbosity: StatusVerbosity::default(),
+            last_progress_status: None,
+            event_log: EventLog::new(EVENT_LOG_CAPACITY),
+            error_dialog: None,
+            issues: Vec::new(),
+            show_issues: false,
+            clone_url: String::new(),
+            large_repository: None,
+            show_event_log: false,
+            is_cloning: false,
+            is_refreshing: false,
+            cancel_requested: false,
+            clone_cancel: Arc::new(AtomicBool::new(false)),
+            repository_path: None,
+            directory_structure: None,
+            directory_statistics: None,
+            
+            // Start with a single empty tab
+            sessions: vec![RepoSession::new(String::from("New Tab"))],
+            active_session: 0,
+            show_comparison: false,
+            show_duplicates: false,
+            show_empty_report: false,
+            show_generated_dirs: false,
+            generated_dirs: None,
+            duplicates: None,
+            empty_report: None,
+            show_common_words: false,
+            repository_watcher: None,
+            
+            // Initialize module handlers
+            git_handler: GitHandler::new(false),
+            directory_parser: DirectoryParser::new(),
+            ui_handler: UiHandler::new(),
+            
+            // UI state
+            show_stats_panel: false,
+            chart_by_bytes: false,
+            show_settings: false,
+            show_about: false,
+            about_commit_id: None,
+            filter_pattern: String::new(),
+            show_advanced_filters: false,
+            filter_extension: String::new(),
+            extension_presets: builtin_extension_presets(),
+            active_preset: 0,
+            new_preset_name: String::new(),
+            new_preset_extensions: String::new(),
+            filter_token_min: 0,
+            filter_token_max: 0,
+            filter_hide_below_tokens: 0,
+            filter_hide_below_bytes: 0,
+            hidden_small_files: 0,
+            exclude_binary_above_mb: 0,
+            excluded_large_files: (0, 0),
+            
+            // File list state
+            file_list: Vec::new(),
+            token_counts: HashMap::new(),
+            lfs_pointers: HashMap::new(),
+            modified_times: HashMap::new(),
+            link_only: HashSet::new(),
+            pinned_paths: HashSet::new(),
+            repository_source: None,
+            saved_pins: HashMap::new(),
+            sort_column: SortColumn::Index,
+            sort_direction: SortDirection::Ascending,
+            is_loading_tokens: false,
+            counting_progress: (0, 0),
+            sorted_progress: 0,
+            counting_cancel: Arc::new(AtomicBool::new(false)),
+            
+            // Table UI state
+            table_columns: default_table_columns(),
+            compact_counts: false,
+            metric_basis: MetricBasis::default(),
+            animations: true,
+            highlight_seconds: ROW_HIGHLIGHT_SECONDS,
+            tooltip_lines: TOOLTIP_PREVIEW_LINES,
+            preview_max_kb: PREVIEW_MAX_BYTES / 1024,
+            tooltip_wrap: true,
+            max_tree_indent: MAX_TREE_INDENT,
+            text_extensions: DEFAULT_TEXT_EXTENSIONS.to_string(),
+            tokenizer: TokenizerKind::default(),
+            count_on_load: true,
+            tally_words: false,
+            common_words: None,
+            current_page: 0,                     // Start at first page
+            
+            // File preview state
+            preview_path: None,
+            preview_cursor_line: 1,
+            scroll_to_index: None,
+            highlighted_row: None,
+            find_open: false,
+            find_query: String::new(),
+            find_current: None,
+            branch_list: None,
+            preview_cache: FileContentCache::new(PREVIEW_CACHE_ENTRIES),
+            
+            // Background processing channels
+            clone_receiver,
+            parse_receiver,
+            token_receiver,
+            word_receiver: mpsc::channel().1,
+            statistics_receiver: mpsc::channel().1,
+            changes_receiver: mpsc::channel().1,
+            pending_changes: HashSet::new(),
+        };
+        
+        // Restore the settings saved when the app last closed
+        if let Some(storage) = storage {
+            if let Some(animations) = eframe::get_value(storage, ANIMATIONS_KEY) {
+                app.animations = animations;
+            }
+            if let Some(highlight_seconds) = eframe::get_value(storage, HIGHLIGHT_SECONDS_KEY) {
+                app.highlight_seconds = highlight_seconds;
+            }
+            if let Some(theme_mode) = eframe::get_value(storage, THEME_MODE_KEY) {
+                app.ui_handler.set_theme_mode(theme_mode);
+            }
+            if let Some(table_columns) = eframe::get_value(storage, TABLE_COLUMNS_KEY) {
+                app.table_columns = restore_table_columns(table_columns);
+            }
+            if let Some(presets) = eframe::get_value::<Vec<ExtensionPreset>>(storage, EXTENSION_PRESETS_KEY) {
+                app.extension_presets.extend(presets);
+            }
+            if let Some(saved_pins) = eframe::get_value(storage, PINNED_FILES_KEY) {
+                app.saved_pins = saved_pins;
+            }
+        }
+        app
+    }
+    
+    /// Applies the settings from a project file
+    ///
+    /// Settings missing from the file keep their current values. If the file
+    /// asks for it, cloning starts right away.
+    ///
+    /// # Arguments
+    /// * `config` - The loaded project configuration
+    pub fn apply_config(&mut self, config: &ProjectConfig) {
+        if let Some(url) = &config.url {
+            self.git_url = url.clone();
+        }
+        if let Some(branch) = &config.branch {
+            self.clone_branch = branch.clone();
+        }
+        if let Some(keep_repository) = config.keep_repository {
+            self.keep_repository = keep_repository;
+        }
+        if let Some(follow_redirects) = config.follow_redirects {
+            self.follow_redirects = follow_redirects;
+        }
+        if let Some(remote_name) = &config.remote_name {
+            self.clone_remote_name = remote_name.clone();
+        }
+        if let Some(fetch_all_branches) = config.fetch_all_branches {
+            self.fetch_all_branches = fetch_all_branches;
+        }
+        if let Some(ssh_key_path) = &config.ssh_key_path {
+            self.ssh_key_path = ssh_key_path.display().to_string();
+        }
+        if let Some(max_file_count) = config.max_file_count {
+            self.max_file_count = max_file_count;
+        }
+        if let Some(ignore_preset) = config.ignore_preset {
+            self.directory_parser.set_ignore_preset(ignore_preset);
+        }
+        for pattern in &config.ignore_patterns {
+            self.directory_parser.add_ignore_pattern(pattern.clone());
+        }
+        if let Some(text_extensions) = &config.text_extensions {
+            self.text_extensions = text_extensions.join(", ");
+        }
+        if let Some(tokenizer) = config.tokenizer {
+            self.tokenizer = tokenizer;
+        }
+        
+        for preset in &config.extension_presets {
+            self.save_extension_preset(&preset.name, &preset.extensions.join(", "));
+        }
+        
+        let filters = &config.filters;
+        self.filter_pattern = filters.pattern.clone();
+        self.filter_extension = filters.extension.clone();
+        self.active_preset = self.extension_presets.iter()
+            .position(|preset| preset.name.eq_ignore_ascii_case(&filters.preset))
+            .unwrap_or(0);
+        self.filter_token_min = filters.min_tokens;
+        self.filter_token_max = filters.max_tokens;
+        self.filter_hide_below_tokens = filters.hide_below_tokens;
+        self.filter_hide_below_bytes = filters.hide_below_bytes;
+        self.exclude_binary_above_mb = filters.exclude_binary_above_mb;
+        
+        if config.auto_clone {
+            self.handle_clone_button();
+        }
+    }
+    
+    /// Formats a token count for display, following the compact counts setting
+    ///
+    /// # Arguments
+    /// * `count` - The count to format
+    ///
+    /// # Returns
+    /// * `String` - The compact or exact count
+    fn display_count(&self, count: usize) -> String {
+        if self.compact_counts {
+            format_count(count)
+        } else {
+            count.to_string()
+        }
+    }
+    
+    /// Sets the status bar message along with its kind
+    ///
+    /// Messages other than progress updates are also recorded in the event log.
+    ///
+    /// # Arguments
+    /// * `message` - The status message to display
+    /// * `kind` - The kind of status, used to color the indicator
+    fn set_status(&mut self, message: impl Into<String>, kind: StatusKind) {
+        self.status_message = message.into();
+        self.status_kind = kind;
+        if kind != StatusKind::Loading {
+            self.event_log.push(kind, self.status_message.clone());
+        }
+    }
+    
+    /// Shows a progress update in the status bar, as often as the verbosity allows
+    ///
+    /// Quiet leaves the message of the phase that started; Normal skips
+    /// updates that follow the last shown one too closely, so the message
+    /// doesn't flicker.
+    ///
+    /// # Arguments
+    /// * `message` - The progress message
+    fn set_progress_status(&mut self, message: impl Into<String>) {
+        let throttled = self.last_progress_status
+            .is_some_and(|last| last.elapsed() < PROGRESS_STATUS_INTERVAL);
+        match self.status_verbosity {
+            StatusVerbosity::Quiet => return,
+            StatusVerbosity::Normal if throttled => return,
+            _ => {}
+        }
+        self.last_progress_status = Some(Instant::now());
+        self.set_status(message, StatusKind::Loading);
+    }
+    
+    /// Builds the counting settings from the settings panel inputs
+    ///
+    /// # Returns
+    /// * `CountSettings` - The parsed text extensions, the tokenizer and the number of counting threads
+    fn count_settings(&self) -> CountSettings {
+        CountSettings {
+            text_extensions: parse_extension_list(&self.text_extensions),
+            tokenizer: self.tokenizer,
+            threads: self.counting_threads,
+        }
+    }
+    
+    /// Builds the fetch settings from the settings panel inputs
+    ///
+    /// # Returns
+    /// * `FetchConfig` - The remote name, or the default one if empty, and whether all branches are fetched
+    fn fetch_config(&self) -> FetchConfig {
+        let remote_name = self.clone_remote_name.trim();
+        FetchConfig {
+            remote_name: if remote_name.is_empty() { DEFAULT_REMOTE_NAME.to_string() } else { remote_name.to_string() },
+            all_branches: self.fetch_all_branches,
+        }
+    }
+    
+    /// Builds the SSH settings from the settings panel inputs
+    ///
+    /// # Returns
+    /// * `SshConfig` - The SSH settings, with empty inputs treated as unset
+    fn ssh_config(&self) -> SshConfig {
+        SshConfig {
+            private_key_path: if self.ssh_key_path.trim().is_empty() {
+                None
+            } else {
+                Some(PathBuf::from(self.ssh_key_path.trim()))
+            },
+            passphrase: if self.ssh_passphrase.is_empty() {
+                None
+            } else {
+                Some(self.ssh_passphrase.clone())
+            },
+        }
+    }
+    
+    /// Validates the Git URL format
+    /// 
+    /// # Arguments
+    /// * `url` - The Git URL to validate
+    /// 
+    /// # Returns
+    /// * `bool` - True if the URL is valid, false otherwise
+    fn validate_git_url(&self, url: &str) -> bool {
+        GitHandler::validate_url(url)
+    }
+    
+    /// Offers text pasted outside the URL field as the repository URL
+    ///
+    /// Only a valid URL that differs from the current one is offered, and not
+    /// while a clone is running.
+    ///
+    /// # Arguments
+    /// * `text` - The pasted text
+    fn offer_pasted_url(&mut self, text: &str) {
+        let url = GitHandler::normalize_url(text);
+        if !self.is_cloning && url != GitHandler::normalize_url(&self.git_url) && self.validate_git_url(&url) {
+            self.pasted_url = Some(url);
+        }
+    }
+    
+    /// Handles the clone button click
+    ///
+    /// Initiates the repository cloning process if the URL is valid
+    fn handle_clone_button(&mut self) {
+        if self.is_cloning || self.is_refreshing {
+            return; // Already cloning, or a refresh would receive the clone's parse result
+        }
+        
+        // Pasted URLs often carry a trailing newline, spaces or slash
+        let normalized = GitHandler::normalize_url(&self.git_url);
+        if normalized != self.git_url {
+            self.git_url = normalized;
+        }
+        self.pasted_url = None;
+        
+        if !self.validate_git_url(&self.git_url) {
+            self.set_status("Invalid Git URL format", StatusKind::Error);
+            return;
+        }
+        
+        // A kept repository can go to a chosen directory, in a folder named after it
+        let title = repository_title(&self.git_url);
+        let chosen_path = Some(self.clone_directory.trim())
+            .filter(|directory| self.keep_repository && !directory.is_empty())
+            .map(|directory| PathBuf::from(directory).join(&title));
+        if let Some(path) = &chosen_path {
+            // An earlier clone there can be updated instead; ask rather than fail
+            if GitHandler::is_repository(path) {
+                self.set_status(format!("{} already contains a repository", path.display()), StatusKind::Info);
+                self.existing_clone = chosen_path;
+                return;
+            }
+            if let Err(e) = CloneDestination::check_chosen(path) {
+                self.set_status(format!("Cannot clone: {}", e), StatusKind::Error);
+                return;
+            }
+        }
+        
+        self.start_clone(title, chosen_path.map(CloneDestination::Chosen));
+    }
+    
+    /// Starts cloning the current URL, or updating an existing clone, in the background
+    ///
+    /// # Arguments
+    /// * `title` - Title of the tab the repository is shown in
+    /// * `destination` - Where to clone to, or `None` for a new temporary directory
+    fn start_clone(&mut self, title: String, destination: Option<CloneDestination>) {
+        // Clone into a new tab if the current one already holds a repository
+        if self.repository_path.is_some() || self.directory_structure.is_some() {
+            self.open_session(title);
+        } else {
+            self.sessions[self.active_session].title = title;
+        }
+        
+        // Update state
+        self.is_cloning = true;
+        self.clone_url = self.git_url.clone();
+        self.cancel_requested = false; // Reset cancel flag
+        self.set_status("Cloning repository...", StatusKind::Loading);
+        self.ui_handler.set_loading(true);
+        
+        // Create channels for this operation
+        let (clone_sender, clone_receiver) = mpsc::channel::<CloneProgress>();
+        let (parse_sender, parse_receiver) = mpsc::channel();
+        self.clone_receiver = clone_receiver;
+        self.parse_receiver = parse_receiver;
+        
+        // Update git handler with keep_repository preference
+        let git_handler = GitHandler::new(self.keep_repository);
+        
+        // Collect the clone settings for the background thread
+        let request = CloneRequest {
+            git_url: self.git_url.clone(),
+            branch: Some(self.clone_branch.trim().to_string()).filter(|branch| !branch.is_empty()),
+            ssh_config: self.ssh_config(),
+            follow_redirects: self.follow_redirects,
+            fetch_config: self.fetch_config(),
+            changes_only: self.changes_only,
+            max_attempts: self.clone_retries + 1,
+            parser: self.directory_parser.clone(),
+        };
+        
+        // Create a temporary directory for the repository unless one was chosen
+        let destination = match destination {
+            Some(destination) => destination,
+            None => match tempfile::Builder::new()
+                .prefix("git_scroll_")
+                .tempdir() {
+                    Ok(dir) => CloneDestination::Temporary(dir),
+                    Err(e) => {
+                        self.set_status(format!("Failed to create temporary directory: {}", e), StatusKind::Error);
+                        self.is_cloning = false;
+                        self.ui_handler.set_loading(false);
+                        return;
+                    }
+                },
+        };
+        
+        if let CloneDestination::Existing(path) = &destination {
+            self.set_status("Updating existing clone...", StatusKind::Loading);
+            self.event_log.push(StatusKind::Loading, format!("Updating the existing clone in {}", path.display()));
+        } else {
+            self.event_log.push(StatusKind::Loading, format!("Cloning {} into {}", request.git_url, destination.path().display()));
+        }
+        
+        // Create a cancel flag that can be checked from the background thread
+        self.clone_cancel = Arc::new(AtomicBool::new(false));
+        let cancel_flag = self.clone_cancel.clone();
+        
+        // Spawn a background thread to perform the cloning and parsing
+        thread::spawn(move || {
+            clone_and_parse(&request, destination, &cancel_flag, &clone_sender, &parse_sender);
+        });
+    }
+    
+    // Square-related methods removed (handle_zoom, handle_layout_change, handle_theme_change)
+    
+    /// Handles filter pattern change
+    ///
+    /// # Arguments
+    /// * `pattern` - The new filter pattern
+    fn handle_filter_change(&mut self, pattern: String) {
+        if self.filter_pattern != pattern {
+            self.filter_pattern = pattern;
+            self.apply_filters();
+        }
+    }
+    
+    /// Renders the statistics panel
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render to
+    fn render_stats_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Repository Statistics");
+        ui.add_space(10.0);
+        
+        if !self.file_list.is_empty() {
+            let total_files = self.file_list.len();
+            let text_files = self.file_list.iter().filter(|f| !f.is_binary).count();
+            let binary_files = self.file_list.iter().filter(|f| f.is_binary).count();
+            let total_tokens = self.file_list.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum::<usize>();
+            let avg_tokens = if text_files > 0 { total_tokens / text_files } else { 0 };
+            
+            // Each value can be selected or copied exactly, e.g. for a report
+            copyable_stat(ui, format!("Total Files: {}", total_files), total_files);
+            copyable_stat(ui, format!("Text Files: {}", text_files), text_files);
+            copyable_stat(ui, format!("Binary Files: {}", binary_files), binary_files);
+            copyable_stat(ui, format!("Total Tokens (text files): {}", self.display_count(total_tokens)), total_tokens)
+                .on_hover_text(total_tokens.to_string());
+            copyable_stat(ui, format!("Average Tokens per Text File: {}", self.display_count(avg_tokens)), avg_tokens);
+            
+            // Add binary file size statistics
+            if binary_files > 0 {
+                let total_binary_size = self.file_list.iter()
+                    .filter(|f| f.is_binary)
+                    .map(|f| f.tokens)
+                    .sum::<usize>();
+                
+                let avg_binary_size = if binary_files > 0 { total_binary_size / binary_files } else { 0 };
+                
+                ui.add_space(5.0);
+                copyable_stat(ui, format!("Average Binary Size: {}", format_file_size(avg_binary_size)), avg_binary_size);
+                copyable_stat(ui, format!("Total Binary Size: {}", format_file_size(total_binary_size)), total_binary_size);
+            }
+            
+            let (excluded_files, excluded_bytes) = self.excluded_large_files;
+            if excluded_files > 0 {
+                ui.add_space(5.0);
+                copyable_stat(ui, format!("Excluded Large Files: {} ({})", excluded_files, format_file_size(excluded_bytes)), excluded_files)
+                    .on_hover_text(format!("Binary files over {} MB are not listed", self.exclude_binary_above_mb));
+            }
+            
+            // Token density, where a low ratio points at generated or minified code
+            if let Some(ratio) = aggregate_tokens_per_byte(&self.file_list) {
+                ui.add_space(5.0);
+                copyable_stat(ui, format!("Tokens per Byte (text files): {:.3}", ratio), format!("{:.3}", ratio));
+                let generated: Vec<&FileInfo> = self.file_list.iter()
+                    .filter(|f| f.is_likely_generated())
+                    .collect();
+                if !generated.is_empty() {
+                    copyable_stat(ui, format!("Likely Generated/Minified: {}", generated.len()), generated.len())
+                        .on_hover_text(generated.iter()
+                            .take(10)
+                            .map(|f| f.path.file_name().unwrap_or_default().to_string_lossy())
+                            .collect::<Vec<_>>()
+                            .join("\n"));
+                }
+            }
+            
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+            
+            // Add token count legend (for text files only)
+            ui.label("Token Count Legend (Text Files):");
+            ui.horizontal(|ui| {
+                // Calculate max tokens for color scaling (text files only)
+                let max_tokens = self.file_list.iter()
+                    .filter(|f| !f.is_binary)
+                    .map(|f| f.tokens)
+                    .max()
+                    .unwrap_or(1);
+                
+                let low_color = crate::ui::style::token_count_color(0, max_tokens, self.ui_handler.is_dark_mode());
+                let mid_color = crate::ui::style::token_count_color(max_tokens / 2, max_tokens, self.ui_handler.is_dark_mode());
+                let high_color = crate::ui::style::token_count_color(max_tokens, max_tokens, self.ui_handler.is_dark_mode());
+                
+                ui.label(egui::RichText::new("Low").color(low_color));
+                ui.label(" → ");
+                ui.label(egui::RichText::new("Medium").color(mid_color));
+                ui.label(" → ");
+                ui.label(egui::RichText::new("High").color(high_color));
+            });
+            
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+            self.render_extension_chart(ui);
+            
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+            self.render_directory_summary(ui);
+            
+            ui.add_space(10.0);
+            ui.separator();
+            ui.add_space(10.0);
+            if self.metric_basis == MetricBasis::Bytes {
+                ui.heading("Largest Files by Size");
+            } else {
+                ui.heading("Top Text Files by Token Count");
+            }
+            ui.add_space(5.0);
+            
+            // Get top files by the chosen basis; binary files only count when measuring bytes
+            let mut top_files = self.file_list.iter()
+                .filter(|f| !f.is_binary || self.metric_basis == MetricBasis::Bytes)
+                .cloned()
+                .collect::<Vec<_>>();
+            top_files.sort_by_key(|f| std::cmp::Reverse(self.metric_basis.value(f)));
+            
+            // Display top files; clicking one brings it into view in the table
+            for file in top_files.iter().take(10) {
+                let file_name = file.path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                let value = self.metric_basis.format(file, |tokens| format!("{} tokens", self.display_count(tokens)));
+                ui.horizontal(|ui| {
+                    if ui.link(file_name).on_hover_text("Show in file list").clicked() {
+                        self.scroll_to_index = Some(file.index);
+                    }
+                    ui.label(value);
+                });
+            }
+        } else {
+            ui.label("No statistics available");
+        }
+    }
+    
+    /// Renders a bar chart of tokens or bytes for the largest file types
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render to
+    fn render_extension_chart(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.heading("By File Type");
+            ui.radio_value(&mut self.chart_by_bytes, false, "Tokens");
+            ui.radio_value(&mut self.chart_by_bytes, true, "Bytes");
+        });
+        ui.add_space(5.0);
+        
+        let totals = extension_totals(&self.file_list, self.chart_by_bytes);
+        let max_total = totals.iter().map(|t| t.value).max().unwrap_or(0).max(1);
+        let dark_mode = self.ui_handler.is_dark_mode();
+        let label_width = 70.0;
+        let bar_height = 18.0;
+        
+        for total in totals.iter().take(CHART_EXTENSIONS) {
+            let (rect, response) = ui.allocate_exact_size(
+                egui::vec2(ui.available_width().max(label_width + 50.0), bar_height),
+                egui::Sense::hover(),
+            );
+            let painter = ui.painter();
+            let name = if total.extension.is_empty() { "(none)".to_string() } else { format!(".{}", total.extension) };
+            painter.text(
+                rect.left_center(),
+                egui::Align2::LEFT_CENTER,
+                &name,
+                egui::FontId::monospace(12.0),
+                ui.visuals().text_color(),
+            );
+            
+            // Bars are scaled to the largest type
+            let bar_width = (rect.width() - label_width) * total.value as f32 / max_total as f32;
+            let bar_rect = egui::Rect::from_min_size(
+                rect.min + egui::vec2(label_width, 2.0),
+                egui::vec2(bar_width.max(1.0), bar_height - 4.0),
+            );
+            painter.rect_filled(bar_rect, CornerRadius::same(2), crate::ui::style::extension_color(&total.extension, dark_mode));
+            
+            response.on_hover_text(format!(
+                "{}: {} files, {} tokens, {}",
+                name, total.files, total.tokens, format_file_size(total.bytes)
+            ));
+        }
+    }
+    
+    /// Renders the files, tokens and size under each top-level directory
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render to
+    fn render_directory_summary(&self, ui: &mut egui::Ui) {
+        ui.heading("By Top-Level Directory");
+        ui.add_space(5.0);
+        
+        egui::Grid::new("directory_summary")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("Directory").strong());
+                ui.label(egui::RichText::new("Files").strong());
+                ui.label(egui::RichText::new("Tokens").strong());
+                ui.label(egui::RichText::new("Size").strong());
+                ui.end_row();
+                
+                for total in directory_totals(&self.file_list, self.repository_path.as_deref()) {
+                    let name = total.name.map_or("(root)".to_string(), |name| format!("{}/", name));
+                    ui.label(egui::RichText::new(name).family(egui::FontFamily::Monospace));
+                    ui.label(total.files.to_string());
+                    ui.label(self.display_count(total.tokens));
+                    ui.label(format_file_size(total.bytes));
+                    ui.end_row();
+                }
+            });
+    }
+    
+    /// Renders the settings panel
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render to
+    fn render_settings_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("File List Settings");
+        ui.add_space(10.0);
+        
+        // Sort settings
+        ui.label("Sort By:");
+        ui.horizontal(|ui| {
+            if ui.radio_value(&mut self.sort_column, SortColumn::Index, "Number").clicked() {
+                self.change_sort();
+            }
+            if ui.radio_value(&mut self.sort_column, SortColumn::Name, "Name").clicked() {
+                self.change_sort();
+            }
+            if ui.radio_value(&mut self.sort_column, SortColumn::Tokens, "Tokens").clicked() {
+                self.change_sort();
+            }
+            if ui.radio_value(&mut self.sort_column, SortColumn::Size, "Size").clicked() {
+                self.change_sort();
+            }
+            if ui.radio_value(&mut self.sort_column, SortColumn::Density, "Tokens/Byte").clicked() {
+                self.change_sort();
+            }
+            if ui.radio_value(&mut self.sort_column, SortColumn::Depth, "Depth").clicked() {
+                self.change_sort();
+            }
+        });
+        
+        ui.add_space(5.0);
+        
+        // Sort direction
+        ui.label("Sort Direction:");
+        ui.horizontal(|ui| {
+            if ui.radio_value(&mut self.sort_direction, SortDirection::Ascending, "Ascending").clicked() {
+                self.change_sort();
+            }
+            if ui.radio_value(&mut self.sort_direction, SortDirection::Descending, "Descending").clicked() {
+                self.change_sort();
+            }
+        });
+        
+        ui.add_space(5.0);
+        
+        self.render_column_settings(ui);
+        
+        ui.add_space(5.0);
+        
+        ui.horizontal(|ui| {
+            ui.label("Exclude binaries over:");
+            if ui.add(egui::Slider::new(&mut self.exclude_binary_above_mb, 0..=500).suffix(" MB"))
+                .on_hover_text("Large media files are left out of the list and totals; 0 keeps them")
+                .changed()
+            {
+                self.apply_filters();
+            }
+        });
+        ui.checkbox(&mut self.compact_counts, "Compact token counts (1.4M, 14.2k)")
+            .on_hover_text("Exact counts stay available in tooltips and exports");
+        ui.horizontal(|ui| {
+            ui.label("Value column:");
+            for basis in [MetricBasis::Hybrid, MetricBasis::Tokens, MetricBasis::Bytes] {
+                if ui.radio_value(&mut self.metric_basis, basis, basis.label()).changed() {
+                    self.change_sort();
+                }
+            }
+        }).response.on_hover_text("Also used for the totals, the statistics and CSV exports");
+        ui.checkbox(&mut self.count_on_load, "Count tokens on load")
+            .on_hover_text("When off, files show — until counted with Count Selected or Count Folder");
+        ui.checkbox(&mut self.tally_words, "Tally common words while counting")
+            .on_hover_text("Fills the Common Words window; applied on the next Refresh");
+        
+        ui.add_space(5.0);
+        
+        // Animations can be distracting or slow on low-end hardware
+        ui.checkbox(&mut self.animations, "Animations")
+            .on_hover_text("When off, panels, scrolling and highlights change instantly");
+        ui.horizontal(|ui| {
+            ui.label("Status updates:");
+            for verbosity in [StatusVerbosity::Quiet, StatusVerbosity::Normal, StatusVerbosity::Verbose] {
+                ui.radio_value(&mut self.status_verbosity, verbosity, verbosity.label());
+            }
+        }).response.on_hover_text("Quiet shows only when cloning or counting starts and ends; Verbose shows every batch of counted files");
+        ui.horizontal(|ui| {
+            ui.label("Row highlight:");
+            ui.add(
+                egui::Slider::new(&mut self.highlight_seconds, 0.0..=5.0)
+                    .suffix(" s")
+                    .step_by(0.1)
+            ).on_hover_text("How long a row stays highlighted after jumping to it; 0 disables it");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Tooltip preview:");
+            ui.add(egui::Slider::new(&mut self.tooltip_lines, 0..=40).suffix(" lines"))
+                .on_hover_text("Leading lines of a text file shown when hovering it; 0 shows only the path");
+            ui.checkbox(&mut self.tooltip_wrap, "Wrap");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Preview read limit:");
+            if ui.add(egui::Slider::new(&mut self.preview_max_kb, 4..=1024).suffix(" KB").logarithmic(true))
+                .on_hover_text("Most of a text file read for the preview and tooltips; binary files stop after their first bytes")
+                .changed()
+            {
+                self.preview_cache.set_max_bytes(self.preview_max_kb * 1024);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Indent depth limit:");
+            ui.add(egui::Slider::new(&mut self.max_tree_indent, 0..=16))
+                .on_hover_text("Files deeper than this are indented no further; 0 turns indentation off");
+        });
+        
+        ui.add_space(10.0);
+        
+        // Extensions that are counted as text; other files are sniffed for text content
+        ui.label("Text File Extensions:");
+        ui.add(
+            egui::TextEdit::multiline(&mut self.text_extensions)
+                .desired_rows(3)
+                .hint_text("rs, py, js, ...")
+        );
+        ui.horizontal(|ui| {
+            if ui.button("Reset to Defaults").clicked() {
+                self.text_extensions = DEFAULT_TEXT_EXTENSIONS.to_string();
+            }
+            ui.label(egui::RichText::new("Applied on the next Refresh").weak());
+        });
+        
+        ui.add_space(5.0);
+        
+        // Word counts undercount code, where `foo(bar)` is several LLM tokens
+        ui.label("Token Counting:");
+        ui.horizontal(|ui| {
+            for tokenizer in [TokenizerKind::Words, TokenizerKind::Code] {
+                ui.radio_value(&mut self.tokenizer, tokenizer, tokenizer.label());
+            }
+            if ui.add_enabled(!self.token_counts.is_empty() || self.is_loading_tokens, egui::Button::new("Recount Tokens"))
+                .on_hover_text("Count the files again with the chosen tokenizer and text extensions, without parsing again")
+                .clicked()
+            {
+                self.recount_tokens();
+            }
+        });
+        let max_threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        ui.horizontal(|ui| {
+            ui.label("Counting threads:");
+            ui.add(egui::DragValue::new(&mut self.counting_threads).range(1..=max_threads))
+                .on_hover_text("Fewer threads keep the interface responsive while counting");
+        });
+        ui.horizontal(|ui| {
+            ui.label("Ask before counting more than:");
+            ui.add(egui::DragValue::new(&mut self.max_file_count).range(0..=10_000_000).speed(1000))
+                .on_hover_text("0 counts every repository without asking");
+            ui.label("files");
+        });
+        
+        ui.add_space(10.0);
+        
+        // The toolbar button switches between Light and Dark; System follows the OS
+        // and High Contrast is a dark theme with heavier borders
+        ui.label("Theme:");
+        ui.horizontal(|ui| {
+            let mut theme_mode = self.ui_handler.theme_mode();
+            for mode in [ThemeMode::Light, ThemeMode::Dark, ThemeMode::System, ThemeMode::HighContrast] {
+                if ui.radio_value(&mut theme_mode, mode, mode.label()).changed() {
+                    self.ui_handler.set_theme_mode(theme_mode);
+                }
+            }
+        });
+        
+        ui.add_space(5.0);
+        
+        // Editors that can't jump to a line leave out {line} and open at the start
+        ui.horizontal(|ui| {
+            ui.label("Editor command:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.editor_command)
+                    .hint_text("code -g {path}:{line}")
+            ).on_hover_text("Used by Open in Editor; {path} is the file and {line} the line, e.g. vim +{line} {path}");
+        });
+        
+        ui.add_space(10.0);
+        
+        // Filter pattern
+        ui.label("Filter Pattern:");
+        let mut pattern = self.filter_pattern.clone();
+        if ui.text_edit_singleline(&mut pattern).changed() {
+            self.handle_filter_change(pattern);
+        }
+        
+        if ui.button("Apply Filter").clicked() {
+            self.apply_filters();
+        }
+        
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+        
+        // Watching only applies to kept repositories, which may be edited locally
+        ui.heading("Repository");
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Branch:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.clone_branch)
+                    .hint_text("Default branch")
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Remote name:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.clone_remote_name)
+                    .hint_text(DEFAULT_REMOTE_NAME)
+            );
+        });
+        ui.checkbox(&mut self.fetch_all_branches, "Fetch all branches")
+            .on_hover_text("Makes every branch available in the branch selector; otherwise only the branch named above, or the default branch, is fetched. Clones take longer and use more space");
+        ui.checkbox(&mut self.changes_only, "Analyze working-tree changes only")
+            .on_hover_text("For a local repository, counts only modified and untracked files, e.g. to estimate the tokens of a PR; all files are analyzed when there are no changes");
+        ui.add_enabled_ui(self.keep_repository, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Clone to:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.clone_directory)
+                        .hint_text("Temporary directory")
+                );
+                if ui.button("Browse...").clicked() {
+                    let start = Some(Path::new(self.clone_directory.trim())).filter(|path| !path.as_os_str().is_empty());
+                    if let Some(folder) = dialogs::pick_folder("Clone Repositories Into", start) {
+                        self.clone_directory = folder.display().to_string();
+                    }
+                }
+            });
+        }).response.on_disabled_hover_text("Enable Keep Repository to choose where repositories are cloned");
+        ui.add_enabled(
+            self.keep_repository,
+            egui::Checkbox::new(&mut self.watch_repository, "Watch for changes"),
+        ).on_disabled_hover_text("Enable Keep Repository to watch for changes");
+        ui.horizontal(|ui| {
+            ui.label("Symlinked files:");
+            let mut symlink_mode = self.directory_parser.symlink_mode();
+            for mode in [SymlinkMode::CountOnce, SymlinkMode::FollowAll, SymlinkMode::CountAsLinks] {
+                if ui.radio_value(&mut symlink_mode, mode, mode.label()).changed() {
+                    self.directory_parser.set_symlink_mode(symlink_mode);
+                }
+            }
+        }).response.on_hover_text("Applied on the next Refresh");
+        ui.horizontal(|ui| {
+            ui.label("Default ignores:");
+            let mut ignore_preset = self.directory_parser.ignore_preset().unwrap_or_default();
+            for preset in IgnorePreset::ALL {
+                if ui.radio_value(&mut ignore_preset, preset, preset.label()).changed() {
+                    self.directory_parser.set_ignore_preset(ignore_preset);
+                }
+            }
+        }).response.on_hover_text(
+            "Minimal ignores only .git; Strict also ignores build output, vendored dependencies, lockfiles and images. Applied on the next clone or Refresh"
+        );
+        let mut git_excludes = self.directory_parser.uses_git_excludes();
+        if ui.checkbox(&mut git_excludes, "Honor .git/info/exclude and the global gitignore")
+            .on_hover_text("Leaves out the files git status ignores through them; applied on the next Refresh")
+            .changed()
+        {
+            self.directory_parser.set_git_excludes(git_excludes);
+        }
+        let mut keep_ignored_only = self.directory_parser.keeps_ignored_only_directories();
+        if ui.checkbox(&mut keep_ignored_only, "Keep directories with only ignored content")
+            .on_hover_text("Applied on the next Refresh")
+            .changed()
+        {
+            self.directory_parser.set_keep_ignored_only_directories(keep_ignored_only);
+        }
+        ui.horizontal(|ui| {
+            ui.label("Retries on network errors:");
+            ui.add(egui::DragValue::new(&mut self.clone_retries).range(0..=10));
+        });
+        ui.checkbox(&mut self.follow_redirects, "Follow redirects to other hosts")
+            .on_hover_text("By default only a redirect on the first request is followed");
+        
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
+        
+        // SSH authentication for git@ and ssh:// URLs
+        ui.heading("SSH Authentication");
+        ui.add_space(5.0);
+        ui.label("The SSH agent is tried first, then the private key.");
+        ui.horizontal(|ui| {
+            ui.label("Private Key:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.ssh_key_path)
+                    .hint_text("~/.ssh/id_ed25519 or ~/.ssh/id_rsa")
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Passphrase:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.ssh_passphrase)
+                    .password(true)
+                    .hint_text("Optional")
+            );
+        });
+    }
+}
+
+impl GitScrollApp {
+    /// Shows an error dialog with the given message until it is dismissed
+    ///
+    /// The error is also listed in the Issues panel, which keeps it after the
+    /// dialog is closed.
+    ///
+    /// # Arguments
+    /// * `error_message` - The error message to display
+    /// * `retry` - How to run the failed operation again, if it can be
+    fn show_error_dialog(&mut self, error_message: &str, retry: Option<RetryAction>) {
+        self.error_dialog = Some(error_message.to_string());
+        self.issues.push(Issue {
+            time: chrono::Local::now(),
+            message: error_message.to_string(),
+            retry,
+        });
+        if self.issues.len() > MAX_ISSUES {
+            self.issues.remove(0);
+        }
+    }
+    
+    /// Runs a failed operation again
+    ///
+    /// A parse is retried in the tab of its repository, or in a new tab if
+    /// that was closed; a clone starts over from its URL.
+    ///
+    /// # Arguments
+    /// * `action` - The operation to run again
+    fn retry(&mut self, action: RetryAction) {
+        if self.is_cloning || self.is_refreshing {
+            return;
+        }
+        match action {
+            RetryAction::Clone(url) => {
+                self.git_url = url;
+                self.handle_clone_button();
+            }
+            RetryAction::Parse(path) => {
+                if self.repository_path.as_ref() != Some(&path) {
+                    let title = path.file_name().map_or_else(
+                        || path.to_string_lossy().to_string(),
+                        |name| name.to_string_lossy().to_string(),
+                    );
+                    if self.repository_path.is_some() || self.directory_structure.is_some() {
+                        self.open_session(title);
+                    } else {
+                        self.sessions[self.active_session].title = title;
+                    }
+                    self.repository_path = Some(path);
+                }
+                self.refresh_repository();
+            }
+        }
+    }
+    
+    /// Renders the error dialog, if an error is waiting to be dismissed
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_error_dialog(&mut self, ctx: &egui::Context) {
+        let Some(error_message) = &self.error_dialog else {
+            return;
+        };
+        
+        let mut dismissed = false;
+        egui::Window::new("Error")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(error_message);
+                if ui.button("OK").clicked() {
+                    dismissed = true;
+                }
+            });
+        
+        if dismissed {
+            self.error_dialog = None;
+        }
+    }
+
+    /// Renders the choice offered when the clone directory already holds a repository
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_existing_clone_dialog(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.existing_clone.clone() else {
+            return;
+        };
+        
+        let remote_name = self.fetch_config().remote_name;
+        let remote_url = self.git_handler.remote_url(&path, &remote_name);
+        let mut choice = None;
+        egui::Window::new("Repository Already Exists")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("{} already contains a repository.", path.display()));
+                match &remote_url {
+                    Some(url) if GitHandler::normalize_url(url) != GitHandler::normalize_url(&self.git_url) => {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("Its {} remote is {}, not the URL being cloned.", remote_name, url));
+                    },
+                    Some(_) => {},
+                    None => {
+                        ui.colored_label(ui.visuals().warn_fg_color, format!("It has no {} remote to update from.", remote_name));
+                    },
+                }
+                ui.label("Update it by fetching and fast-forwarding its branch, or clone into another directory.");
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(remote_url.is_some(), egui::Button::new("Update Existing")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Choose Another Directory...").clicked() {
+                        choice = Some(false);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.existing_clone = None;
+                        self.set_status("Clone cancelled", StatusKind::Info);
+                    }
+                });
+            });
+        
+        match choice {
+            Some(true) => {
+                self.existing_clone = None;
+                self.start_clone(repository_title(&self.git_url), Some(CloneDestination::Existing(path)));
+            },
+            Some(false) => {
+                self.existing_clone = None;
+                if let Some(folder) = dialogs::pick_folder("Choose Clone Directory", path.parent()) {
+                    self.clone_directory = folder.display().to_string();
+                    self.handle_clone_button();
+                }
+            },
+            None => {},
+        }
+    }
+    
+    /// Computes the directory statistics on a background thread
+    ///
+    /// Reading every file's metadata can take a while on a large repository.
+    /// The previous statistics stay until the new ones arrive, and results of
+    /// an earlier computation still running are dropped with its receiver.
+    ///
+    /// # Arguments
+    /// * `root_entry` - The parsed directory structure
+    fn compute_statistics(&mut self, root_entry: &DirectoryEntry) {
+        let (statistics_sender, statistics_receiver) = mpsc::channel();
+        self.statistics_receiver = statistics_receiver;
+        let directory_parser = self.directory_parser.clone();
+        let root_entry = root_entry.clone();
+        thread::spawn(move || {
+            let _ = statistics_sender.send(directory_parser.get_statistics(&root_entry));
+        });
+    }
+    
+    /// Shows a parsed repository and starts counting its tokens
+    ///
+    /// # Arguments
+    /// * `parsed` - The parsed repository
+    fn load_parsed_repository(&mut self, parsed: ParsedRepository) {
+        let ParsedRepository { root_entry, warnings, link_only } = parsed;
+        self.link_only = link_only;
+        
+        // Set the directory structure; its statistics follow from the background
+        self.compute_statistics(&root_entry);
+        self.directory_structure = Some(root_entry.clone());
+        
+        // Populate file list; without counting on load, every file starts uncounted
+        let count_only = (!self.count_on_load).then(HashSet::new);
+        self.populate_file_list(&root_entry, count_only.as_ref());
+        
+        // Entries that couldn't be read were left out; say which in the log
+        for warning in &warnings {
+            self.event_log.push(StatusKind::Error, warning.clone());
+        }
+        
+        // Update state
+        if !warnings.is_empty() {
+            self.set_status(
+                format!("Repository parsed, skipped {} unreadable entries (see Log)", warnings.len()),
+                StatusKind::Info,
+            );
+        } else if self.is_refreshing {
+            self.set_status("Repository refreshed, counting tokens...", StatusKind::Loading);
+        } else {
+            self.set_status("Repository parsed successfully", StatusKind::Success);
+        }
+        self.is_cloning = false;
+        self.is_refreshing = false;
+        self.ui_handler.set_loading(false);
+    }
+    
+    /// Checks whether counting a parsed repository should wait for confirmation
+    ///
+    /// A refresh of a repository that was already counted past the limit
+    /// doesn't ask again, however many of its files the filters hide.
+    ///
+    /// # Arguments
+    /// * `file_count` - Number of files in the parsed repository
+    ///
+    /// # Returns
+    /// * `bool` - True if the file count is over the limit
+    fn needs_file_count_confirmation(&self, file_count: usize) -> bool {
+        let loaded_count = self.directory_structure.as_ref().map_or(0, |root| root.descendant_file_count());
+        self.max_file_count > 0 && file_count > self.max_file_count && loaded_count <= self.max_file_count
+    }
+    
+    /// Renders the confirmation for a repository with more files than the limit
+    ///
+    /// The repository can be counted anyway, parsed again with extra ignore
+    /// patterns, or closed.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_large_repository_dialog(&mut self, ctx: &egui::Context) {
+        let Some(large) = &mut self.large_repository else {
+            return;
+        };
+        
+        let mut count_all = false;
+        let mut reparse = false;
+        let mut cancel = false;
+        egui::Window::new("Large Repository")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "This repository has {} files, more than the limit of {}. Counting tokens for all of them may take a long time.",
+                    format_thousands(large.file_count),
+                    format_thousands(self.max_file_count),
+                ));
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.label("Ignore patterns:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut large.ignore_patterns)
+                            .hint_text("node_modules, dist, vendor/**")
+                    );
+                });
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    count_all = ui.button("Count All Files").clicked();
+                    reparse = ui.add_enabled(
+                        !large.ignore_patterns.trim().is_empty(),
+                        egui::Button::new("Ignore and Parse Again"),
+                    ).clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        
+        if count_all {
+            self.confirm_large_repository();
+        } else if reparse {
+            self.reparse_large_repository();
+        } else if cancel {
+            self.event_log.push(StatusKind::Info, "Closed a repository over the file limit without counting it");
+            self.clear_repository();
+        }
+    }
+    
+    /// Counts the tokens of the repository waiting for confirmation
+    fn confirm_large_repository(&mut self) {
+        if let Some(large) = self.large_repository.take() {
+            self.event_log.push(StatusKind::Info, format!("Counting {} files over the limit of {}", large.file_count, self.max_file_count));
+            self.load_parsed_repository(large.repository);
+        }
+    }
+    
+    /// Adds the ignore patterns typed into the confirmation and parses the repository again
+    fn reparse_large_repository(&mut self) {
+        let Some(large) = self.large_repository.take() else {
+            return;
+        };
+        for pattern in large.ignore_patterns.split(',').map(str::trim).filter(|pattern| !pattern.is_empty()) {
+            self.directory_parser.add_ignore_pattern(pattern.to_string());
+        }
+        self.refresh_repository();
+    }
+    
+    /// Populates the file list from the directory structure
+    /// Uses parallel processing with rayon for better performance
+    ///
+    /// # Arguments
+    /// * `root_entry` - The parsed directory structure
+    /// * `count_only` - Files and folders to count tokens for, or `None` to count every file;
+    ///   the others stay uncounted
+    fn populate_file_list(&mut self, root_entry: &DirectoryEntry, count_only: Option<&HashSet<PathBuf>>) {
+        // Keep the selection of files that are still present, e.g. after a refresh
+        let selected_paths: HashSet<PathBuf> = self.file_list.iter()
+            .filter(|f| f.selected)
+            .map(|f| f.path.clone())
+            .collect();
+        self.file_list.clear();
+        self.generated_dirs = None;
+        self.duplicates = None;
+        self.empty_report = None;
+        self.token_counts.clear();
+        self.lfs_pointers.clear();
+        self.modified_times.clear();
+        let files = self.directory_parser.get_all_files(root_entry);
+        
+        if files.is_empty() {
+            return; // No files to process
+        }
+        
+        // Create a new channel for this operation
+        let (token_sender, cancel) = self.start_counting();
+        let (word_sender, word_receiver) = mpsc::channel();
+        self.word_receiver = word_receiver;
+        self.common_words = None;
+        
+        // Create a placeholder for each file with 0 tokens initially
+        self.file_list = files
+            .iter()
+            .enumerate()
+            .map(|(index, path)| FileInfo {
+                index,
+                path: path.clone(),
+                tokens: 0, // Will be updated asynchronously
+                selected: selected_paths.contains(path),
+                is_binary: false, // Will be updated asynchronously
+                size_bytes: 0,    // Will be updated asynchronously
+                content_hash: 0,  // Will be updated asynchronously
+                lfs_size: None,   // Will be updated asynchronously
+                pinned: self.pinned_paths.contains(path),
+                modified: None,   // Will be updated asynchronously
+            })
+            .collect();
+        
+        // Indices match `apply_filters`, so they're taken before leaving files out
+        let files_to_process: Vec<(usize, PathBuf)> = files.into_iter()
+            .enumerate()
+            .filter(|(_, path)| count_only.is_none_or(|subset| is_in_subset(path, subset)))
+            .collect();
+        if files_to_process.is_empty() {
+            self.is_loading_tokens = false;
+            self.sort_file_list();
+            return;
+        }
+        self.is_loading_tokens = true;
+        self.counting_progress = (0, files_to_process.len());
+        self.sorted_progress = 0;
+        
+        // Process files in parallel using rayon
+        let link_only = self.link_only.clone();
+        let settings = self.count_settings();
+        let words = self.tally_words.then(|| Mutex::new(WordFrequencies::default()));
+        thread::spawn(move || {
+            // Sending fails once the receiver is replaced or dropped, which stops the work
+            let counted = count_repository_files(&files_to_process, &link_only, &settings, words.as_ref(), Some(&cancel), |result| {
+                token_sender.send(result)
+            });
+            
+            // Cancelled counts leave the tally incomplete, so it isn't sent
+            if let (Ok(()), Some(words)) = (counted, words) {
+                let _ = word_sender.send(words.into_inner().unwrap().top(COMMON_WORDS_SHOWN));
+            }
+        });
+        
+        // Initial sort (will be updated as tokens are counted)
+        self.sort_file_list();
+    }
+
+    /// Renders the checklist of file table columns with buttons to reorder them
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render to
+    fn render_column_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Columns:");
+            if ui.small_button("Reset").clicked() {
+                self.table_columns = default_table_columns();
+            }
+        });
+        
+        // Swapped after the loop; the index is of the lower of the two columns
+        let mut swap = None;
+        let column_count = self.table_columns.len();
+        for (position, column) in self.table_columns.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(position > 0, egui::Button::new("⏶").small()).clicked() {
+                    swap = Some(position);
+                }
+                if ui.add_enabled(position + 1 < column_count, egui::Button::new("⏷").small()).clicked() {
+                    swap = Some(position + 1);
+                }
+                
+                // The name column holds the preview and context menu, so it stays
+                let header = column.kind.header(self.metric_basis);
+                let checkbox = ui.add_enabled(column.kind != FileColumn::Name, egui::Checkbox::new(&mut column.visible, header));
+                match column.kind {
+                    FileColumn::Density => { checkbox.on_hover_text("Useful for spotting minified or generated files"); }
+                    FileColumn::Modified => { checkbox.on_hover_text("Read when the file is counted"); }
+                    FileColumn::Depth => { checkbox.on_hover_text("Directories between the repository root and the file"); }
+                    _ => {}
+                }
+            });
+        }
+        if let Some(position) = swap {
+            self.table_columns.swap(position - 1, position);
+        }
+    }
+    
+    /// Sorts the file list after the sort settings or the filters changed
+    ///
+    /// The page turns to keep the focused file in view: the current find
+    /// match, or else the previewed one. Without a focused file the list
+    /// starts over at the first page, since the old page would show unrelated
+    /// files.
+    fn change_sort(&mut self) {
+        self.sort_file_list();
+        
+        let focused = self.find_current
+            .or_else(|| {
+                let preview_path = self.preview_path.as_ref()?;
+                self.file_list.iter().find(|f| &f.path == preview_path).map(|f| f.index)
+            })
+            .filter(|index| self.file_list.iter().any(|f| f.index == *index));
+        match focused {
+            Some(index) => self.scroll_to_index = Some(index),
+            None => self.current_page = 0,
+        }
+    }
+    
+    /// Sorts the file list based on current sort settings
+    fn sort_file_list(&mut self) {
+        match self.sort_column {
+            SortColumn::Index => {
+                self.file_list.sort_by(|a, b| {
+                    match self.sort_direction {
+                        SortDirection::Ascending => a.index.cmp(&b.index),
+                        SortDirection::Descending => b.index.cmp(&a.index),
+                    }
+                });
+            }
+            SortColumn::Name => {
+                self.file_list.sort_by(|a, b| {
+                    let a_name = a.path.file_name().unwrap_or_default().to_string_lossy();
+                    let b_name = b.path.file_name().unwrap_or_default().to_string_lossy();
+                    match self.sort_direction {
+                        SortDirection::Ascending => a_name.cmp(&b_name),
+                        SortDirection::Descending => b_name.cmp(&a_name),
+                    }
+                });
+            }
+            SortColumn::Tokens => {
+                // Files without a value sort as the smallest
+                let basis = self.metric_basis;
+                self.file_list.sort_by(|a, b| {
+                    match self.sort_direction {
+                        SortDirection::Ascending => basis.value(a).cmp(&basis.value(b)),
+                        SortDirection::Descending => basis.value(b).cmp(&basis.value(a)),
+                    }
+                });
+            }
+            SortColumn::Size => {
+                self.file_list.sort_by(|a, b| {
+                    match self.sort_direction {
+                        SortDirection::Ascending => a.size_bytes.cmp(&b.size_bytes),
+                        SortDirection::Descending => b.size_bytes.cmp(&a.size_bytes),
+                    }
+                });
+            }
+            SortColumn::Density => {
+                // Files without a ratio (binary or empty) sort last in either direction
+                self.file_list.sort_by(|a, b| match (a.tokens_per_byte(), b.tokens_per_byte()) {
+                    (Some(a_ratio), Some(b_ratio)) => match self.sort_direction {
+                        SortDirection::Ascending => a_ratio.total_cmp(&b_ratio),
+                        SortDirection::Descending => b_ratio.total_cmp(&a_ratio),
+                    },
+                    (a_ratio, b_ratio) => b_ratio.is_some().cmp(&a_ratio.is_some()),
+                });
+            }
+            SortColumn::Depth => {
+                // Files at the same depth stay grouped by directory
+                let root = self.repository_path.as_deref();
+                self.file_list.sort_by(|a, b| {
+                    let (a_depth, b_depth) = (a.depth(root), b.depth(root));
+                    match self.sort_direction {
+                        SortDirection::Ascending => a_depth.cmp(&b_depth),
+                        SortDirection::Descending => b_depth.cmp(&a_depth),
+                    }
+                    .then_with(|| a.path.cmp(&b.path))
+                });
+            }
+        }
+        
+        // The sort is stable, so each group keeps the order above
+        self.file_list.sort_by_key(|f| !f.pinned);
+    }
+
+    /// Checks for results from background operations
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn check_background_operations(&mut self, ctx: &egui::Context) {
+        // Check if cancel was requested and update the cancel flag
+        if self.cancel_requested {
+            self.clone_cancel.store(true, Ordering::SeqCst);
+        }
+        
+        // Check for clone progress and results; every queued message is read, so
+        // the clone is always completed before its parse result below is handled
+        while let Ok(clone_msg) = self.clone_receiver.try_recv() {
+            match clone_msg {
+                CloneProgress::Progress(stats) => {
+                    // Update progress in UI
+                    self.ui_handler.set_progress(stats.fraction());
+                    self.set_progress_status(format!("Cloning repository... {}", stats.message()));
+                    ctx.request_repaint(); // Force UI update to show progress
+                },
+                CloneProgress::Retrying(attempt, max_attempts) => {
+                    self.ui_handler.set_progress(0.0);
+                    self.set_status(format!("Retrying clone ({}/{})...", attempt, max_attempts), StatusKind::Loading);
+                    self.event_log.push(StatusKind::Loading, format!("Clone failed, retrying ({}/{})", attempt, max_attempts));
+                    ctx.request_repaint();
+                },
+                CloneProgress::ChangesOnly(result) => {
+                    match result {
+                        Ok(count) => self.event_log.push(StatusKind::Info, format!("Analyzing {} changed files from the working tree", count)),
+                        Err(e) => self.event_log.push(StatusKind::Info, format!("Analyzing all files: {}", e)),
+                    }
+                },
+                CloneProgress::Completed(result) => {
+                    match result {
+                        Ok(repo_path) => {
+                            let branch_count = self.git_handler.list_branches(&repo_path).map_or(0, |names| names.len());
+                            self.event_log.push(StatusKind::Info, format!(
+                                "Fetched {} {}", branch_count, if branch_count == 1 { "branch" } else { "branches" }
+                            ));
+                            self.repository_path = Some(repo_path);
+                            self.repository_source = Some(self.clone_url.clone());
+                            self.restore_pins();
+                            self.set_status(
+                                format!("Repository cloned successfully ({} branches fetched), parsing directory...", branch_count),
+                                StatusKind::Loading,
+                            );
+                            
+                            // Parsing reports no progress, so a spinner replaces the finished
+                            // transfer bar and the app doesn't look idle until the files are listed
+                            self.ui_handler.set_progress(0.0);
+                            ctx.request_repaint();
+                            self.cancel_requested = false; // Reset cancel flag
+                        },
+                        Err(e) => {
+                            let error_message = format!("Failed to clone repository: {}", e);
+                            self.set_status(error_message.clone(), StatusKind::Error);
+                            self.is_cloning = false;
+                            self.ui_handler.set_loading(false);
+                            self.cancel_requested = false; // Reset cancel flag
+                            
+                            // Show error dialog for critical errors
+                            self.show_error_dialog(&error_message, Some(RetryAction::Clone(self.clone_url.clone())));
+                        }
+                    }
+                }
+            }
+        }
+        
+        // Check for parse results
+        if let Ok(parse_result) = self.parse_receiver.try_recv() {
+            match parse_result {
+                Ok(parsed) => {
+                    // Ask before counting a repository far larger than expected
+                    let file_count = parsed.root_entry.descendant_file_count();
+                    if self.needs_file_count_confirmation(file_count) {
+                        self.set_status(
+                            format!("Repository has {} files, more than the limit of {}", file_count, self.max_file_count),
+                            StatusKind::Info,
+                        );
+                        self.large_repository = Some(LargeRepository {
+                            repository: parsed,
+                            file_count,
+                            ignore_patterns: String::new(),
+                        });
+                        self.is_cloning = false;
+                        self.is_refreshing = false;
+                        self.ui_handler.set_loading(false);
+                    } else {
+                        self.load_parsed_repository(parsed);
+                    }
+                },
+                Err(e) => {
+                    // Failed to parse directory
+                    let error_message = format!("Failed to parse repository: {}", e);
+                    self.set_status(error_message.clone(), StatusKind::Error);
+                    
+                    // Clean up the repository if not keeping it; a failed refresh keeps the loaded one
+                    let cleaned_up = !self.is_refreshing && !self.keep_repository && self.repository_path.is_some();
+                    if cleaned_up {
+                        let _ = self.git_handler.cleanup(self.repository_path.as_ref().unwrap());
+                    }
+                    
+                    // A removed clone has to be cloned again before it can be parsed
+                    let retry = match &self.repository_path {
+                        Some(path) if !cleaned_up => Some(RetryAction::Parse(path.clone())),
+                        _ if self.is_cloning => Some(RetryAction::Clone(self.clone_url.clone())),
+                        _ => None,
+                    };
+                    
+                    self.is_cloning = false;
+                    self.is_refreshing = false;
+                    self.ui_handler.set_loading(false);
+                    
+                    // Show error dialog for critical errors
+                    self.show_error_dialog(&error_message, retry);
+                }
+            }
+        }
+        
+        // Refresh files changed in a watched repository
+        self.update_repository_watcher(ctx);
+        if let Some(watcher) = &self.repository_watcher {
+            let mut changed: Vec<PathBuf> = watcher.receiver.try_iter().flatten().collect();
+            changed.retain(|path| !self.directory_parser.is_ignored(&watcher.path, path));
+            if !changed.is_empty() {
+                self.refresh_changed_files(changed, ctx);
+            }
+        }
+        if let Ok(refresh) = self.changes_receiver.try_recv() {
+            self.apply_changed_files(refresh);
+        }
+        
+        // Background threads don't wake the UI themselves, so keep polling
+        // while one is running; a finished thread has dropped its sender
+        let mut waiting = self.is_cloning || self.is_refreshing;
+        match self.statistics_receiver.try_recv() {
+            Ok(statistics) => {
+                self.directory_statistics = Some(statistics);
+                ctx.request_repaint();
+            },
+            Err(mpsc::TryRecvError::Empty) => waiting = true,
+            Err(mpsc::TryRecvError::Disconnected) => {},
+        }
+        
+        match self.word_receiver.try_recv() {
+            Ok(words) => {
+                self.common_words = Some(words);
+                ctx.request_repaint();
+            },
+            Err(mpsc::TryRecvError::Empty) => waiting = true,
+            Err(mpsc::TryRecvError::Disconnected) => {},
+        }
+        
+        if waiting {
+            ctx.request_repaint_after(BACKGROUND_POLL_INTERVAL);
+        }
+        
+        if self.is_loading_tokens {
+            let (batch, all_received) = self.receive_token_results();
+            
+            // Poll again shortly instead of repainting every frame; the
+            // results that arrive in between are handled as one batch
+            if all_received {
+                ctx.request_repaint();
+            } else {
+                ctx.request_repaint_after(COUNTING_REPAINT_INTERVAL);
+            }
+            
+            // Update progress in status message
+            let (completed, total_files) = self.counting_progress;
+            if batch > 0 && !all_received {
+                let percentage = (completed as f32 / total_files.max(1) as f32 * 100.0) as usize;
+                let mut message = format!("Counting tokens: {}% ({}/{})", percentage, completed, total_files);
+                if self.status_verbosity == StatusVerbosity::Verbose {
+                    message.push_str(&format!(", {} in this batch", batch));
+                }
+                self.set_progress_status(message);
+            }
+            
+            // Check if all files have been processed
+            if all_received {
+                self.is_loading_tokens = false;
+                self.set_status("Token counting completed", StatusKind::Success);
+                
+                // Filters depend on token counts, so apply them once counting is done
+                if self.has_active_filters() {
+                    self.apply_filters();
+                }
+            }
+        }
+    }
+}
+
+impl eframe::App for GitScrollApp {
+    /// Updates the application state and renders the UI
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    /// * `_frame` - The eframe frame
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Apply custom styling based on dark mode setting, following the OS if chosen
+        self.ui_handler.resolve_theme(ctx.system_theme());
+        crate::ui::style::apply_style(ctx, self.ui_handler.is_dark_mode(), self.ui_handler.is_high_contrast());
+        if !self.animations {
+            // Collapsing headers, windows and scrolling snap to their final state
+            ctx.style_mut(|style| {
+                style.animation_time = 0.0;
+                style.scroll_animation = egui::style::ScrollAnimation::none();
+            });
+        }
+        
+        // Check for results from background operations
+        self.check_background_operations(ctx);
+        
+        // Top panel for URL input and controls with adaptive layout
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            ui.add_space(8.0); // Padding at the top
+
+            ui.horizontal(|ui| {
+                // Fixed widths for buttons and checkbox
+                let clone_button_width = 80.0;
+                let folder_button_width = 28.0;
+                let clear_button_width = 60.0;
+                let theme_button_width = 60.0;
+                let checkbox_width = 120.0; // Approximate width for "Keep Repository" checkbox
+                let spacing = 8.0;
+                let label_width = 60.0; // Approximate width for "Git URL:" label
+
+                // Git URL label - simple approach with consistent height
+                ui.add_sized(
+                    [label_width, 28.0], // Same height as other elements
+                    egui::Label::new(egui::RichText::new("Git URL:").strong())
+                );
+
+                // Fixed widths for buttons and checkbox
+                let clone_button_width = 80.0;
+                let clear_button_width = 60.0;
+                let theme_button_width = 60.0;
+                let settings_button_width = 70.0;
+                let refresh_button_width = 70.0;
+                let checkbox_width = 120.0; // Approximate width for "Keep Repository" checkbox
+                let spacing = 8.0;
+
+                // Calculate total fixed width (label + buttons + checkbox + spacings)
+                let total_fixed_width = label_width
+                    + folder_button_width
+                    + clone_button_width
+                    + checkbox_width
+                    + clear_button_width
+                    + theme_button_width
+                    + settings_button_width
+                    + refresh_button_width
+                    + (spacing * 8.0); // Spaces between elements
+
+                // Set minimum URL input width
+                let min_url_width = 200.0;
+
+                // Calculate URL width directly - this ensures it grows immediately with window expansion
+                let url_width = (ui.available_width() - total_fixed_width).max(min_url_width);
+
+                // URL input with flexible width
+                let url_input_id = ui.make_persistent_id("git_url_input");
+                let response = ui.add_sized(
+                    [url_width, 28.0],
+                    egui::TextEdit::singleline(&mut self.git_url)
+                        .hint_text("Enter repository URL...")
+                        .id(url_input_id)
+                );
+
+                // Handle Enter key press when URL input is focused
+                if response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.handle_clone_button();
+                }
+                
+                // Text pasted into the field is trimmed; a URL pasted elsewhere is offered instead
+                if let Some(pasted) = ui.input(|i| i.events.iter().rev().find_map(|event| match event {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                })) {
+                    if response.changed() {
+                        self.git_url = self.git_url.trim().to_string();
+                    } else if !ui.ctx().wants_keyboard_input() {
+                        self.offer_pasted_url(&pasted);
+                    }
+                }
+
+                ui.add_space(spacing);
+
+                // Local repositories are cloned from their folder like any other URL
+                if ui.add_enabled(
+                    !self.is_cloning,
+                    egui::Button::new("📂").min_size(egui::vec2(folder_button_width, 28.0))
+                ).on_hover_text("Choose a local repository folder").clicked() {
+                    if let Some(folder) = dialogs::pick_folder("Open Local Repository", None) {
+                        self.git_url = folder.display().to_string();
+                    }
+                }
+
+                ui.add_space(spacing);
+
+                // Clone or Cancel button based on state
+                if self.is_cloning {
+                    // Show Cancel button when cloning
+                    if ui.add(
+                        egui::Button::new(
+                            egui::RichText::new("Cancel")
+                                .strong()
+                        )
+                        .min_size(egui::vec2(clone_button_width, 28.0))
+                    ).clicked() {
+                        self.cancel_requested = true;
+                        self.set_status("Cancelling clone operation...", StatusKind::Loading);
+                    }
+                } else {
+                    // Show Clone button when not cloning
+                    if ui.add_enabled(
+                        !self.git_url.is_empty() && !self.is_refreshing,
+                        egui::Button::new(
+                            egui::RichText::new("Clone")
+                                .strong()
+                        )
+                        .min_size(egui::vec2(clone_button_width, 28.0))
+                    ).clicked() {
+                        self.handle_clone_button();
+                    }
+                }
+
+                ui.add_space(spacing);
+
+                // Keep Repository checkbox
+                ui.checkbox(&mut self.keep_repository, "Keep Repository");
+
+                ui.add_space(spacing);
+
+                // Clear button, locked while a clone or refresh would still deliver results
+                if ui.add_enabled(
+                    !self.is_cloning && !self.is_refreshing,
+                    egui::Button::new(egui::RichText::new("Clear").strong())
+                        .min_size(egui::vec2(clear_button_width, 28.0))
+                ).on_disabled_hover_text("Cancel or wait for the running operation first").clicked() {
+                    self.clear_repository();
+                }
+
+                ui.add_space(spacing);
+
+                // Refresh button re-parses the repository from disk
+                if ui.add_enabled(
+                    self.repository_path.is_some() && !self.is_cloning && !self.is_refreshing,
+                    egui::Button::new("Refresh")
+                        .min_size(egui::vec2(refresh_button_width, 28.0))
+                ).clicked() {
+                    self.refresh_repository();
+                }
+
+                ui.add_space(spacing);
+
+                // Theme toggle button
+                if ui.add(
+                    egui::Button::new(
+                        if self.ui_handler.is_dark_mode() { "Light" } else { "Dark" }
+                    )
+                    .min_size(egui::vec2(theme_button_width, 28.0))
+                ).clicked() {
+                    self.toggle_dark_mode();
+                }
+
+                ui.add_space(spacing);
+
+                // Settings window toggle
+                let settings_button = ui.add(
+                    egui::Button::new("Settings")
+                        .selected(self.show_settings)
+                        .min_size(egui::vec2(settings_button_width, 28.0))
+                );
+                if settings_button.clicked() {
+                    self.show_settings = !self.show_settings;
+                }
+            });
+            
+            if let Some(pasted_url) = self.pasted_url.clone() {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!("Use pasted URL? {}", pasted_url)).weak());
+                    if ui.small_button("Use").clicked() {
+                        self.git_url = pasted_url;
+                        self.pasted_url = None;
+                    }
+                    if ui.small_button("✕").on_hover_text("Dismiss").clicked() {
+                        self.pasted_url = None;
+                    }
+                });
+            }
+
+            ui.add_space(8.0); // Padding at the bottom
+        });
+        
+        // Tab bar for switching between repositories
+        egui::TopBottomPanel::top("tab_bar").show(ctx, |ui| {
+            self.render_tab_bar(ui);
+        });
+        
+        if self.show_comparison && self.sessions.len() > 1 {
+            self.render_comparison_window(ctx);
+        }
+        
+        if self.show_duplicates && !self.file_list.is_empty() {
+            self.render_duplicates_window(ctx);
+        }
+        
+        if self.show_empty_report && !self.file_list.is_empty() {
+            self.render_empty_report_window(ctx);
+        }
+        
+        if self.show_common_words && !self.file_list.is_empty() {
+            self.render_common_words_window(ctx);
+        }
+        
+        if self.show_generated_dirs && !self.file_list.is_empty() {
+            self.render_generated_dirs_window(ctx);
+        }
+        
+        // Statistics window
+        if self.show_stats_panel && !self.file_list.is_empty() {
+            let mut open = true;
+            egui::Window::new("Statistics")
+                .open(&mut open)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        self.render_stats_panel(ui);
+                    });
+                });
+            self.show_stats_panel = open;
+        }
+        
+        // Settings window
+        if self.show_settings {
+            let mut open = true;
+            egui::Window::new("Settings")
+                .open(&mut open)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    self.render_settings_panel(ui);
+                });
+            self.show_settings = open;
+        }
+        
+        if self.show_about {
+            self.render_about_window(ctx);
+        } else {
+            self.about_commit_id = None;
+        }
+        
+        // Controls panel removed - consolidated into central panel
+        
+        // Optimized bottom panel with horizontal layout for status and stats
+        egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
+            ui.add_space(8.0);
+            
+            ui.horizontal(|ui| {
+                // Past status messages are kept in the event log
+                ui.toggle_value(&mut self.show_event_log, format!("Log ({})", self.event_log.len()))
+                    .on_hover_text("Show what happened during this session");
+                ui.toggle_value(&mut self.show_issues, format!("Issues ({})", self.issues.len()))
+                    .on_hover_text("Show recent failures and retry them");
+                ui.toggle_value(&mut self.show_about, "About")
+                    .on_hover_text("Show version information");
+                
+                // Status message on the left
+                let status_width = ui.available_width() * 0.6;
+                ui.horizontal(|ui| {
+                    ui.set_width(status_width);
+                    self.ui_handler.render_status_bar(ui, &self.status_message, self.status_kind, self.is_loading_tokens);
+                });
+                
+                // Stats on the right (if repository is loaded)
+                if self.directory_structure.is_some() {
+                    ui.separator();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let total_files = self.file_list.len();
+                        let text_files = self.file_list.iter().filter(|f| !f.is_binary).count();
+                        let binary_files = self.file_list.iter().filter(|f| f.is_binary).count();
+                        let total_tokens = self.file_list.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum::<usize>();
+                        let avg_tokens = if text_files > 0 { total_tokens / text_files } else { 0 };
+                        
+                        if self.metric_basis == MetricBasis::Bytes {
+                            let total_size = self.file_list.iter().map(|f| f.size_bytes).sum::<usize>();
+                            ui.label(format!("Avg: {}/file", format_file_size(total_size / total_files.max(1))));
+                            ui.add_space(8.0);
+                            ui.label(egui::RichText::new(format!("Size: {}", format_file_size(total_size))).strong());
+                        } else {
+                            ui.label(format!("Avg: {} tokens/file", self.display_count(avg_tokens)));
+                            ui.add_space(8.0);
+                            ui.label(egui::RichText::new(format!("Tokens: {}", self.display_count(total_tokens))).strong())
+                                .on_hover_text(format!("{} tokens", total_tokens));
+                        }
+                        ui.add_space(8.0);
+                        ui.label(egui::RichText::new(format!("Files: {} ({} text, {} bin)",
+                            total_files, text_files, binary_files)).strong());
+                    });
+                }
+            });
+            
+            ui.add_space(8.0);
+        });
+        
+        if self.show_event_log {
+            self.render_event_log_panel(ctx);
+        }
+        if self.show_issues {
+            self.render_issues_panel(ctx);
+        }
+        
+        self.render_error_dialog(ctx);
+        self.render_existing_clone_dialog(ctx);
+        self.render_large_repository_dialog(ctx);
+        
+        // File preview pane for the clicked file
+        self.render_preview_panel(ctx);
+        
+        // Main central panel
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.directory_structure.is_none() {
+                // Empty state when no repository is loaded
+                self.ui_handler.render_empty_state(ui, &mut self.git_url);
+            } else {
+                // Enhanced header section with title, sorting, filtering, and export options
+                ui.horizontal(|ui| {
+                    ui.heading("Repository Files");
+                    self.render_branch_selector(ui);
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        // Add export buttons
+                        ui.menu_button("File Tree", |ui| {
+                            if ui.button("Export to TXT").clicked() {
+                                self.export_file_tree();
+                                ui.close_menu();
+                            }
+                            if ui.button("Copy to Clipboard").clicked() {
+                                if let Some(root_entry) = &self.directory_structure {
+                                    ui.ctx().copy_text(self.directory_parser.tree_text(root_entry, &self.sessions[self.active_session].title));
+                                    self.set_status("File tree copied to clipboard", StatusKind::Success);
+                                }
+                                ui.close_menu();
+                            }
+                        });
+                        let has_selection = self.file_list.iter().any(|f| f.selected);
+                        ui.menu_button("Export List", |ui| {
+                            for format in ExportFormat::ALL {
+                                if ui.button(format.label()).clicked() {
+                                    self.export_file_list(format, false);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                        ui.menu_button("Export Selected", |ui| {
+                            for format in ExportFormat::ALL {
+                                if ui.button(format.label()).clicked() {
+                                    self.export_file_list(format, true);
+                                    ui.close_menu();
+                                }
+                            }
+                        }).response.on_hover_text(if has_selection {
+                            "Export only the checked files"
+                        } else {
+                            "No files are checked, so every file is exported"
+                        });
+                        if ui.add_enabled(has_selection && !self.is_loading_tokens, egui::Button::new("Count Selected"))
+                            .on_hover_text("Count tokens for the selected files only")
+                            .on_disabled_hover_text("Select files with their checkboxes once counting has finished")
+                            .clicked()
+                        {
+                            self.count_selected_files();
+                        }
+                        
+                        ui.add_space(8.0);
+                        
+                        // Add sort direction toggle
+                        let direction_text = match self.sort_direction {
+                            SortDirection::Ascending => "↑ Asc",
+                            SortDirection::Descending => "↓ Desc",
+                        };
+                        if ui.button(direction_text).clicked() {
+                            self.sort_direction = match self.sort_direction {
+                                SortDirection::Ascending => SortDirection::Descending,
+                                SortDirection::Descending => SortDirection::Ascending,
+                            };
+                            self.change_sort();
+                        }
+                        
+                        ui.add_space(4.0);
+                        
+                        // Add sort column selector
+                        egui::ComboBox::from_id_source("sort_column")
+                            .selected_text(match self.sort_column {
+                                SortColumn::Index => "Sort: Number",
+                                SortColumn::Name => "Sort: Name",
+                                SortColumn::Tokens => "Sort: Tokens",
+                                SortColumn::Size => "Sort: Size",
+                                SortColumn::Density => "Sort: Tokens/Byte",
+                                SortColumn::Depth => "Sort: Depth",
+                            })
+                            .show_ui(ui, |ui| {
+                                if ui.selectable_value(&mut self.sort_column, SortColumn::Index, "Number").clicked() {
+                                    self.change_sort();
+                                }
+                                if ui.selectable_value(&mut self.sort_column, SortColumn::Name, "Name").clicked() {
+                                    self.change_sort();
+                                }
+                                if ui.selectable_value(&mut self.sort_column, SortColumn::Tokens, "Tokens").clicked() {
+                                    self.change_sort();
+                                }
+                                if ui.selectable_value(&mut self.sort_column, SortColumn::Size, "Size").clicked() {
+                                    self.change_sort();
+                                }
+                                if ui.selectable_value(&mut self.sort_column, SortColumn::Density, "Tokens/Byte").clicked() {
+                                    self.change_sort();
+                                }
+                                if ui.selectable_value(&mut self.sort_column, SortColumn::Depth, "Depth").clicked() {
+                                    self.change_sort();
+                                }
+                            });
+                    });
+                });
+                
+                ui.add_space(8.0);
+                
+                // Search and filter bar with improved layout
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("Filter:").strong());
+                    
+                    // Calculate available width for the filter input
+                    let available_width = ui.available_width() - 150.0; // Space for button
+                    
+                    // Add filter input with dynamic width
+                    let mut filter_text = self.filter_pattern.clone();
+                    if ui.add_sized(
+                        [available_width.max(200.0), 28.0],
+                        egui::TextEdit::singleline(&mut filter_text)
+                            .hint_text("Filter files...")
+                    ).changed() {
+                        self.handle_filter_change(filter_text);
+                    }
+                    
+                    ui.add_space(8.0);
+                    
+                    // Add advanced filter options
+                    if ui.button(if self.show_advanced_filters { "Hide Advanced" } else { "Advanced Filters" }).clicked() {
+                        self.show_advanced_filters = !self.show_advanced_filters;
+                    }
+                });
+                
+                // Enhanced advanced filters section with better layout and visual feedback
+                if self.show_advanced_filters {
+                    egui::Frame::group(ui.style())
+                        .fill(if self.ui_handler.is_dark_mode() {
+                            egui::Color32::from_rgb(45, 45, 48)
+                        } else {
+                            egui::Color32::from_rgb(240, 240, 245)
+                        })
+                        .inner_margin(egui::vec2(10.0, 8.0))
+                        .show(ui, |ui| {
+                            ui.heading("Advanced Filters");
+                            ui.add_space(4.0);
+                            
+                            // Extension filter
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("File Extension:").strong());
+                                ui.add_space(4.0);
+                                
+                                let mut extension = self.filter_extension.clone();
+                                if ui.add_sized(
+                                    [120.0, 24.0],
+                                    egui::TextEdit::singleline(&mut extension)
+                                        .hint_text("e.g., rs, js, py")
+                                ).changed() {
+                                    self.filter_extension = extension;
+                                    self.apply_filters();
+                                }
+                                
+                                ui.add_space(4.0);
+                                if ui.button("Clear").clicked() {
+                                    self.filter_extension = String::new();
+                                    self.apply_filters();
+                                }
+                            });
+                            
+                            ui.add_space(8.0);
+                            
+                            // Extension presets, e.g. code or docs only
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("Preset:").strong());
+                                ui.add_space(4.0);
+                                
+                                let mut active_preset = self.active_preset;
+                                egui::ComboBox::from_id_salt("extension_preset")
+                                    .selected_text(&self.extension_presets[active_preset].name)
+                                    .show_ui(ui, |ui| {
+                                        for (index, preset) in self.extension_presets.iter().enumerate() {
+                                            let extensions = if preset.extensions.is_empty() {
+                                                "Every file".to_string()
+                                            } else {
+                                                preset.extensions.join(", ")
+                                            };
+                                            ui.selectable_value(&mut active_preset, index, &preset.name)
+                                                .on_hover_text(extensions);
+                                        }
+                                    });
+                                if active_preset != self.active_preset {
+                                    self.active_preset = active_preset;
+                                    self.apply_filters();
+                                }
+                                
+                                if self.active_preset >= BUILTIN_PRESET_COUNT && ui.button("Delete").clicked() {
+                                    self.delete_extension_preset(self.active_preset);
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Save preset:");
+                                ui.add_sized(
+                                    [100.0, 24.0],
+                                    egui::TextEdit::singleline(&mut self.new_preset_name)
+                                        .hint_text("Name")
+                                );
+                                ui.add_sized(
+                                    [160.0, 24.0],
+                                    egui::TextEdit::singleline(&mut self.new_preset_extensions)
+                                        .hint_text("e.g., rs, toml")
+                                );
+                                let can_save = !self.new_preset_name.trim().is_empty()
+                                    && !parse_extension_list(&self.new_preset_extensions).is_empty();
+                                if ui.add_enabled(can_save, egui::Button::new("Save")).clicked() {
+                                    let name = self.new_preset_name.trim().to_string();
+                                    let extensions = std::mem::take(&mut self.new_preset_extensions);
+                                    self.save_extension_preset(&name, &extensions);
+                                    self.new_preset_name.clear();
+                                }
+                            });
+                            
+                            ui.add_space(8.0);
+                            
+                            // Token range filters
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("Token Range:").strong());
+                                ui.add_space(4.0);
+                                
+                                ui.label("Min:");
+                                let mut min_tokens = self.filter_token_min.to_string();
+                                if ui.add_sized(
+                                    [80.0, 24.0],
+                                    egui::TextEdit::singleline(&mut min_tokens)
+                                        .hint_text("0")
+                                ).changed() {
+                                    if let Ok(value) = min_tokens.parse::<usize>() {
+                                        self.filter_token_min = value;
+                                        self.apply_filters();
+                                    }
+                                }
+                                
+                                ui.add_space(8.0);
+                                
+                                ui.label("Max:");
+                                let mut max_tokens = self.filter_token_max.to_string();
+                                if ui.add_sized(
+                                    [80.0, 24.0],
+                                    egui::TextEdit::singleline(&mut max_tokens)
+                                        .hint_text("∞")
+                                ).changed() {
+                                    if let Ok(value) = max_tokens.parse::<usize>() {
+                                        self.filter_token_max = value;
+                                        self.apply_filters();
+                                    }
+                                }
+                                
+                                ui.add_space(4.0);
+                                if ui.button("Reset Range").clicked() {
+                                    self.filter_token_min = 0;
+                                    self.filter_token_max = 0;
+                                    self.apply_filters();
+                                }
+                            });
+                            
+                            ui.add_space(4.0);
+                            
+                            ui.add_space(8.0);
+                            
+                            // Small file thresholds
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("Hide Small Files:").strong());
+                                ui.add_space(4.0);
+                                
+                                ui.label("Text below");
+                                let mut hide_tokens = self.filter_hide_below_tokens.to_string();
+                                if ui.add_sized(
+                                    [60.0, 24.0],
+                                    egui::TextEdit::singleline(&mut hide_tokens)
+                                        .hint_text("0")
+                                ).changed() {
+                                    if let Ok(value) = hide_tokens.parse::<usize>() {
+                                        self.filter_hide_below_tokens = value;
+                                        self.apply_filters();
+                                    }
+                                }
+                                ui.label("tokens,");
+                                
+                                ui.label("binary below");
+                                let mut hide_bytes = self.filter_hide_below_bytes.to_string();
+                                if ui.add_sized(
+                                    [80.0, 24.0],
+                                    egui::TextEdit::singleline(&mut hide_bytes)
+                                        .hint_text("0")
+                                ).changed() {
+                                    if let Ok(value) = hide_bytes.parse::<usize>() {
+                                        self.filter_hide_below_bytes = value;
+                                        self.apply_filters();
+                                    }
+                                }
+                                ui.label("bytes");
+                                
+                                if self.hidden_small_files > 0 {
+                                    ui.add_space(8.0);
+                                    ui.label(egui::RichText::new(format!("{} files hidden", self.hidden_small_files)).weak());
+                                }
+                            });
+                            
+                            ui.add_space(4.0);
+                            
+                            // Show active filters summary
+                            if self.has_active_filters() {
+                                ui.add_space(4.0);
+                                ui.separator();
+                                ui.add_space(4.0);
+                                
+                                ui.horizontal(|ui| {
+                                    ui.label(egui::RichText::new("Active Filters:").strong());
+                                    
+                                    let mut filter_text = Vec::new();
+                                    
+                                    if !self.filter_pattern.is_empty() {
+                                        filter_text.push(format!("Pattern: {}", self.filter_pattern));
+                                    }
+                                    
+                                    if !self.filter_extension.is_empty() {
+                                        filter_text.push(format!("Extension: {}", self.filter_extension));
+                                    }
+                                    
+                                    if self.active_preset != 0 {
+                                        filter_text.push(format!("Preset: {}", self.extension_presets[self.active_preset].name));
+                                    }
+                                    
+                                    if self.filter_token_min > 0 {
+                                        filter_text.push(format!("Min Tokens: {}", self.filter_token_min));
+                                    }
+                                    
+                                    if self.filter_token_max > 0 {
+                                        filter_text.push(format!("Max Tokens: {}", self.filter_token_max));
+                                    }
+                                    
+                                    if self.filter_hide_below_tokens > 0 || self.filter_hide_below_bytes > 0 {
+                                        filter_text.push(format!("Small Files Hidden: {}", self.hidden_small_files));
+                                    }
+                                    
+                                    if self.exclude_binary_above_mb > 0 {
+                                        filter_text.push(format!("Large Binaries Excluded: {}", self.excluded_large_files.0));
+                                    }
+                                    
+                                    ui.label(filter_text.join(" | "));
+                                    
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        if ui.button("Clear All Filters").clicked() {
+                                            self.filter_pattern = String::new();
+                                            self.filter_extension = String::new();
+                                            self.active_preset = 0;
+                                            self.filter_token_min = 0;
+                                            self.filter_token_max = 0;
+                                            self.filter_hide_below_tokens = 0;
+                                            self.filter_hide_below_bytes = 0;
+                                            self.exclude_binary_above_mb = 0;
+                                            self.apply_filters();
+                                        }
+                                    });
+                                });
+                            }
+                        });
+                }
+                
+                // Pressing `/` outside a text field jumps between matches without filtering
+                if !ui.ctx().wants_keyboard_input() && ui.input(|i| i.key_pressed(egui::Key::Slash)) {
+                    // Drop the typed slash so it doesn't land in the find field
+                    ui.input_mut(|i| i.events.retain(|event| !matches!(event, egui::Event::Text(text) if text == "/")));
+                    self.find_open = true;
+                    ui.memory_mut(|mem| mem.request_focus(egui::Id::new("find_field")));
+                }
+                if self.find_open {
+                    self.render_find_bar(ui);
+                }
+                
                 ui.add_space(8.0);
                 
-                // Search and filter bar with improved layout
-                ui.horizontal(|ui| {
-                    ui.label(egui::RichText::new("Filter:").strong());
-                    
-                    // Calculate available width for the filter input
-                    let available_width = ui.available_width() - 150.0; // Space for button
-                    
-                    // Add filter input with dynamic width
-                    let mut filter_text = self.filter_pattern.clone();
-                    if ui.add_sized(
-                        [available_width.max(200.0), 28.0],
-                        egui::TextEdit::singleline(&mut filter_text)
-                            .hint_text("Filter files...")
-                    ).changed() {
-                        self.handle_filter_change(filter_text);
-                    }
+                // Calculate the max value for color scaling (text files only, unless measuring bytes)
+                let max_tokens = self.file_list.iter()
+                    .filter(|f| !f.is_binary || self.metric_basis == MetricBasis::Bytes)
+                    .filter_map(|f| self.metric_basis.value(f))
+                    .max()
+                    .unwrap_or(1);
+                
+                // Get row colors for striping
+                let (even_row_color, odd_row_color) =
+                    crate::ui::style::row_colors(self.ui_handler.is_dark_mode());
+                
+                // Get header color
+                let header_color = crate::ui::style::header_color(self.ui_handler.is_dark_mode());
+                
+                // Calculate pagination with dynamic items per page based on available height
+                let row_height = 24.0;
+                let header_height = 30.0;
+                let footer_height = 40.0; // Space for pagination controls
+                let available_height = ui.available_height() - header_height - footer_height;
+                
+                // Calculate items per page based on available height, with a minimum of 10 items
+                let items_per_page = (available_height / row_height).max(10.0) as usize;
+                let total_pages = (self.file_list.len() + items_per_page - 1) / items_per_page;
+                
+                // Turn to the page holding a row requested by another panel; it is
+                // scrolled into view when rendered, then the request is cleared
+                let scroll_target = self.scroll_to_index.take()
+                    .and_then(|index| self.file_list.iter().position(|f| f.index == index));
+                self.current_page = visible_page(self.current_page, scroll_target, self.file_list.len(), items_per_page);
+                
+                let start_idx = self.current_page * items_per_page;
+                let end_idx = (start_idx + items_per_page).min(self.file_list.len());
+                let visible_items = end_idx - start_idx;
+                
+                // File list table with virtual scrolling for better performance
+                let row_height = 24.0; // Estimated height of each row
+                let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+                if let Some(position) = scroll_target {
+                    // Rows outside the viewport aren't rendered, so jump close to the target first
+                    let row_with_spacing = row_height + ui.spacing().item_spacing.y;
+                    scroll_area = scroll_area.vertical_scroll_offset((position - start_idx) as f32 * row_with_spacing);
+                }
+                scroll_area
+                    .show_rows(ui, row_height, visible_items, |ui, row_range| {
+                    // Table header with custom styling
+                    let header_frame = egui::Frame::default()
+                        .fill(header_color)
+                        .inner_margin(Margin::symmetric(8, 4));
                     
-                    ui.add_space(8.0);
+                    header_frame.show(ui, |ui| {
+                        egui::Grid::new("file_list_header")
+                            .num_columns(3)
+                            .spacing([8.0, 4.0])
+                            .show(ui, |ui| {
+                                // Positions of the shown columns, so resizing adjusts the right widths
+                                let shown: Vec<usize> = (0..self.table_columns.len())
+                                    .filter(|&position| self.table_columns[position].visible)
+                                    .collect();
+                                
+                                for (i, &position) in shown.iter().enumerate() {
+                                    let column = self.table_columns[position];
+                                    let text = column.kind.header(self.metric_basis);
+                                    let is_sorted = column.kind.sort_column() == Some(self.sort_column);
+                                    let sort_indicator = if is_sorted {
+                                        if self.sort_direction == SortDirection::Ascending { "↑" } else { "↓" }
+                                    } else { "" };
+                                    
+                                    // Make headers clickable for sorting
+                                    let header_button = ui.add_sized(
+                                        [column.width, 30.0],
+                                        egui::Button::new(
+                                            egui::RichText::new(format!("{} {}", text, sort_indicator)).strong()
+                                        ).fill(header_color)
+                                    );
+                                    
+                                    if let Some(col) = column.kind.sort_column().filter(|_| header_button.clicked()) {
+                                        if self.sort_column == col {
+                                            // Toggle direction if already sorting by this column
+                                            self.sort_direction = match self.sort_direction {
+                                                SortDirection::Ascending => SortDirection::Descending,
+                                                SortDirection::Descending => SortDirection::Ascending,
+                                            };
+                                        } else {
+                                            // Set new sort column
+                                            self.sort_column = col;
+                                        }
+                                        self.change_sort();
+                                    }
+                                    
+                                    // Add tooltip to explain sorting
+                                    if header_button.hovered() && column.kind.sort_column().is_some() {
+                                        egui::show_tooltip(ui.ctx(), LayerId::background(), egui::Id::new("sort_tooltip").with(i), |ui| {
+                                            ui.label(format!("Click to sort by {}", text));
+                                        });
+                                    }
+                                    
+                                    // Add resize handle between columns
+                                    if i < shown.len() - 1 { // Only between columns
+                                        // Create a visible draggable area for resizing
+                                        let resize_id = ui.id().with(("resize", i));
+                                        
+                                        // Make the resize handle more visible
+                                        let resize_width = 8.0;
+                                        // Import std::ops::Add for Pos2
+                                        use std::ops::Add;
+                                        
+                                        let resize_rect = egui::Rect::from_min_size(
+                                            ui.cursor().min + egui::vec2(-resize_width/2.0, 0.0),
+                                            egui::vec2(resize_width, ui.available_height().min(30.0))
+                                        );
+                                        
+                                        // Draw a visible handle
+                                        if ui.is_rect_visible(resize_rect) {
+                                            // Get the response from interact to determine hover/active state
+                                            let resize_response = ui.interact(
+                                                resize_rect,
+                                                resize_id,
+                                                egui::Sense::drag()
+                                            );
+                                            
+                                            // Determine stroke based on hover/active state
+                                            let stroke = if resize_response.hovered() || resize_response.dragged() {
+                                                egui::Stroke::new(2.0, egui::Color32::from_rgb(100, 150, 255))
+                                            } else {
+                                                egui::Stroke::new(1.0, egui::Color32::from_gray(160))
+                                            };
+                                            
+                                            ui.painter().line_segment(
+                                                [
+                                                    resize_rect.center_top(),
+                                                    resize_rect.center_bottom(),
+                                                ],
+                                                stroke
+                                            );
+                                        }
+                                        
+                                        let resize_response = ui.interact(
+                                            resize_rect,
+                                            resize_id,
+                                            egui::Sense::drag()
+                                        );
+                                        
+                                        if resize_response.dragged() {
+                                            let delta = ui.input(|i| i.pointer.delta().x);
+                                            
+                                            // Adjust both columns to maintain total width
+                                            let (left, right) = (shown[i], shown[i + 1]);
+                                            self.table_columns[left].width += delta;
+                                            self.table_columns[right].width -= delta;
+                                            
+                                            // Ensure minimum widths
+                                            self.table_columns[left].width = self.table_columns[left].width.max(50.0);
+                                            self.table_columns[right].width = self.table_columns[right].width.max(50.0);
+                                            
+                                            // Request repaint for smooth resizing
+                                            ui.ctx().request_repaint();
+                                        }
+                                        
+                                        // Show resize cursor on hover
+                                        if resize_response.hovered() {
+                                            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+                                        }
+                                    }
+                                }
+                                
+                                ui.end_row();
+                            });
+                    });
                     
-                    // Add advanced filter options
-                    if ui.button(if self.show_advanced_filters { "Hide Advanced" } else { "Advanced Filters" }).clicked() {
-                        self.show_advanced_filters = !self.show_advanced_filters;
-                    }
+                    // Table body
+                    let shown_columns: Vec<TableColumn> = self.table_columns.iter()
+                        .filter(|column| column.visible)
+                        .copied()
+                        .collect();
+                    egui::Grid::new("file_list_grid")
+                        .num_columns(3)
+                        .spacing([8.0, 4.0])
+                        .striped(true) // Use built-in striping
+                        .show(ui, |ui| {
+                            // Only render visible rows for the current page
+                            for relative_idx in row_range {
+                                let absolute_idx = start_idx + relative_idx;
+                                if absolute_idx >= self.file_list.len() {
+                                    break;
+                                }
+                                let i = absolute_idx; // For compatibility with existing code
+                                let row_response = ui.horizontal(|ui| {
+                                    // Add checkbox for selection
+                                    let mut selected = self.file_list[absolute_idx].selected;
+                                    if ui.checkbox(&mut selected, "").changed() {
+                                        self.file_list[absolute_idx].selected = selected;
+                                        
+                                        // Handle shift-click for multi-selection
+                                        if ui.input(|i| i.modifiers.shift) && selected {
+                                            if let Some(last_selected) = self.file_list.iter().rposition(|f| f.selected && f.index != self.file_list[absolute_idx].index) {
+                                                let clicked_idx = i;
+                                                let range = if clicked_idx < last_selected {
+                                                    clicked_idx..=last_selected
+                                                } else {
+                                                    last_selected..=clicked_idx
+                                                };
+                                                for idx in range {
+                                                    if idx < self.file_list.len() {
+                                                        self.file_list[idx].selected = true;
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    
+                                    for column in &shown_columns {
+                                        let width = column.width;
+                                        match column.kind {
+                                            FileColumn::Index => {
+                                                ui.add_sized([width, 20.0], egui::Label::new(self.file_list[absolute_idx].index.to_string()));
+                                            }
+                                            FileColumn::Name => {
+                                                // File path column with tree structure and color coding
+                                                let path_str = self.file_list[absolute_idx].path.to_string_lossy();
+                                                
+                                                // Calculate the file's depth below the repository root, so the
+                                                // clone's own location doesn't push everything to the right
+                                                let depth = tree_depth(&self.file_list[absolute_idx].path, self.repository_path.as_deref());
+                                                
+                                                // Get file extension for color coding
+                                                let extension = self.file_list[absolute_idx].path.extension()
+                                                    .and_then(|e| e.to_str())
+                                                    .unwrap_or("");
+                                                
+                                                // Determine file type color based on extension
+                                                let file_color = crate::ui::style::extension_color(extension, self.ui_handler.is_dark_mode());
+                                                
+                                                // Get just the file name for display
+                                                let file_name = self.file_list[absolute_idx].path.file_name()
+                                                    .map(|n| n.to_string_lossy().to_string())
+                                                    .unwrap_or_default();
+                                                
+                                                // Create indentation based on depth, capped so deep files stay visible
+                                                let indent = "  ".repeat(depth.min(self.max_tree_indent));
+                                                
+                                                // Add tree structure character
+                                                let tree_prefix = if depth > 0 { "└─ " } else { "" };
+                                                
+                                                // Combine for display, marking pointers to content that isn't in the clone
+                                                let lfs_marker = if self.file_list[absolute_idx].lfs_size.is_some() { " [LFS]" } else { "" };
+                                                let pin_marker = if self.file_list[absolute_idx].pinned { "📌 " } else { "" };
+                                                let display_path = format!("{}{}{}{}{}", indent, tree_prefix, pin_marker, file_name, lfs_marker);
+                                                
+                                                // Create the label with the file path, clickable to open the preview
+                                                let path_label = ui.add_sized(
+                                                    [width, 20.0],
+                                                    egui::Label::new(
+                                                        egui::RichText::new(display_path)
+                                                            .family(egui::FontFamily::Monospace)
+                                                            .color(file_color)
+                                                    ).sense(egui::Sense::click())
+                                                );
+                                                
+                                                if path_label.clicked() {
+                                                    self.preview_path = Some(self.file_list[absolute_idx].path.clone());
+                                                    self.preview_cursor_line = 1;
+                                                }
+                                                
+                                                // Show full path on hover with extension info
+                                                if path_label.hovered() {
+                                                    egui::show_tooltip(ui.ctx(), LayerId::background(), egui::Id::new("path_tooltip").with(i), |ui| {
+                                                        let extension = self.file_list[absolute_idx].path.extension()
+                                                            .map_or("".to_string(), |e| format!(" ({})", e.to_string_lossy()));
+                                                        ui.label(format!("{}{}", path_str, extension));
+                                                        if let Some(real_size) = self.file_list[absolute_idx].lfs_size {
+                                                            ui.label(format!("Git LFS pointer to {}; the content wasn't fetched", format_file_size(real_size)));
+                                                        }
+                                                        
+                                                        // Peek at the first lines, sharing loads with the preview pane
+                                                        let file_path = &self.file_list[absolute_idx].path;
+                                                        if self.file_list[absolute_idx].is_binary || self.tooltip_lines == 0 {
+                                                            // Nothing worth peeking at
+                                                        } else if self.preview_cache.is_loading(file_path) {
+                                                            ui.label(egui::RichText::new("Loading...").small().weak());
+                                                        } else if let Some(FilePreview::Text { content, .. }) = self.preview_cache.get_or_load(ui.ctx(), file_path) {
+                                                            if let Some(head) = tooltip_head(&content, self.tooltip_lines) {
+                                                                ui.separator();
+                                                                
+                                                                // Egui measures the laid out text, so the tooltip fits
+                                                                // multi-line and multibyte content; long lines either
+                                                                // wrap or are cut at the maximum width
+                                                                ui.set_max_width(TOOLTIP_MAX_WIDTH);
+                                                                let text = egui::RichText::new(head).monospace().small();
+                                                                if self.tooltip_wrap {
+                                                                    ui.add(egui::Label::new(text).wrap());
+                                                                } else {
+                                                                    ui.add(egui::Label::new(text).truncate());
+                                                                }
+                                                            }
+                                                        }
+                                                    });
+                                                }
+                                                
+                                                // Context menu on right-click
+                                                path_label.context_menu(|ui| {
+                                                    ui.set_min_width(150.0);
+                                                    
+                                                    if ui.button("Open File").clicked() {
+                                                        #[cfg(target_os = "windows")]
+                                                        {
+                                                            spawn_detached(std::process::Command::new("cmd")
+                                                                .args(&["/c", "start", "", self.file_list[absolute_idx].path.to_string_lossy().as_ref()]))
+                                                                .ok();
+                                                        }
+                                                        #[cfg(not(target_os = "windows"))]
+                                                        {
+                                                            spawn_detached(std::process::Command::new("xdg-open")
+                                                                .arg(self.file_list[absolute_idx].path.to_string_lossy().as_ref()))
+                                                                .ok();
+                                                        }
+                                                        ui.close_menu();
+                                                    }
+                                                    
+                                                    if ui.add_enabled(!self.editor_command.trim().is_empty(), egui::Button::new("Open in Editor"))
+                                                        .on_disabled_hover_text("Set an editor command in Settings")
+                                                        .clicked()
+                                                    {
+                                                        let path = self.file_list[absolute_idx].path.clone();
+                                                        self.open_in_editor(&path, 1);
+                                                        ui.close_menu();
+                                                    }
+                                                    
+                                                    let pin_label = if self.file_list[absolute_idx].pinned { "Unpin" } else { "Pin to Top" };
+                                                    if ui.button(pin_label)
+                                                        .on_hover_text("Pinned files lead the list whatever the sort")
+                                                        .clicked()
+                                                    {
+                                                        self.toggle_pin(absolute_idx);
+                                                        ui.close_menu();
+                                                    }
+                                                    
+                                                    if ui.add_enabled(!self.is_loading_tokens, egui::Button::new("Count Folder"))
+                                                        .on_hover_text("Count tokens for every file in this file's folder")
+                                                        .clicked()
+                                                    {
+                                                        if let Some(folder) = self.file_list[absolute_idx].path.parent() {
+                                                            self.count_subset(&HashSet::from([folder.to_path_buf()]));
+                                                        }
+                                                        ui.close_menu();
+                                                    }
+                                                    
+                                                    if ui.button("Copy Path").clicked() {
+                                                        ui.ctx().copy_text(self.file_list[absolute_idx].path.to_string_lossy().to_string());
+                                                        ui.close_menu();
+                                                    }
+                                                    
+                                                    if ui.button("Copy Relative Path").clicked() {
+                                                        let path = &self.file_list[absolute_idx].path;
+                                                        let relative_path = self.repository_path.as_ref()
+                                                            .and_then(|root| path.strip_prefix(root).ok())
+                                                            .unwrap_or(path);
+                                                        ui.ctx().copy_text(relative_path.to_string_lossy().to_string());
+                                                        ui.close_menu();
+                                                    }
+                                                    
+                                                    let is_binary = self.file_list[absolute_idx].is_binary;
+                                                    if ui.add_enabled(!is_binary, egui::Button::new("Copy Contents"))
+                                                        .on_hover_text("Copy the file with a path header and its token count")
+                                                        .on_disabled_hover_text("Binary files cannot be copied as text")
+                                                        .clicked()
+                                                    {
+                                                        self.copy_file_contents(ui.ctx(), absolute_idx);
+                                                        ui.close_menu();
+                                                    }
+                                                });
+                                            }
+                                            FileColumn::Value => {
+                                                // Create a colored background based on the value or binary status
+                                                let file_value = self.metric_basis.value(&self.file_list[absolute_idx]);
+                                                let token_color = if self.file_list[absolute_idx].is_binary && self.metric_basis != MetricBasis::Bytes {
+                                                    // Use a distinct color for binary files
+                                                    if self.ui_handler.is_dark_mode() {
+                                                        egui::Color32::from_rgb(80, 80, 120) // Dark blue for binary files in dark mode
+                                                    } else {
+                                                        egui::Color32::from_rgb(200, 200, 240) // Light blue for binary files in light mode
+                                                    }
+                                                } else {
+                                                    // Use the regular token count color for text files
+                                                    if self.ui_handler.is_high_contrast() {
+                                                        crate::ui::style::high_contrast_count_color(file_value.unwrap_or(0), max_tokens)
+                                                    } else {
+                                                        crate::ui::style::token_count_color(
+                                                            file_value.unwrap_or(0),
+                                                            max_tokens,
+                                                            self.ui_handler.is_dark_mode()
+                                                        )
+                                                    }
+                                                };
+                                                
+                                                egui::Frame::default()
+                                                    .fill(token_color)
+                                                    .corner_radius(CornerRadius::same(4))
+                                                    .inner_margin(Margin::symmetric(6, 2))
+                                                    .show(ui, |ui| {
+                                                        // Display token count or file size, depending on the basis and file;
+                                                        // files that weren't counted show a dash rather than 0
+                                                        let file = &self.file_list[absolute_idx];
+                                                        let display_text = if self.token_counts.contains_key(&file.path) {
+                                                            self.metric_basis.format(file, |tokens| self.display_count(tokens))
+                                                        } else {
+                                                            "—".to_string()
+                                                        };
+                                                        
+                                                        let count_label = ui.add_sized(
+                                                            [width, 20.0],
+                                                            egui::Label::new(
+                                                                egui::RichText::new(display_text)
+                                                                    .strong()
+                                                                    .family(egui::FontFamily::Monospace)
+                                                            )
+                                                        );
+                                                        if self.compact_counts && !self.metric_basis.is_size(file) {
+                                                            if let Some(tokens) = file_value {
+                                                                count_label.on_hover_text(format!("{} tokens", tokens));
+                                                            }
+                                                        }
+                                                    });
+                                            }
+                                            FileColumn::Size => {
+                                                let size_text = format_file_size(self.file_list[absolute_idx].size_bytes);
+                                                ui.add_sized([width, 20.0], egui::Label::new(egui::RichText::new(size_text).family(egui::FontFamily::Monospace)));
+                                            }
+                                            FileColumn::Density => {
+                                                let file = &self.file_list[absolute_idx];
+                                                let density_text = file.tokens_per_byte()
+                                                    .map_or("—".to_string(), |ratio| format!("{:.3}", ratio));
+                                                let mut density_label = egui::RichText::new(density_text)
+                                                    .family(egui::FontFamily::Monospace);
+                                                if file.is_likely_generated() {
+                                                    density_label = density_label.color(egui::Color32::from_rgb(230, 140, 40));
+                                                }
+                                                ui.add_sized([width, 20.0], egui::Label::new(density_label))
+                                                    .on_hover_text(if file.is_likely_generated() {
+                                                        "Low token density: likely generated or minified"
+                                                    } else {
+                                                        "Tokens per byte"
+                                                    });
+                                            }
+                                            FileColumn::Depth => {
+                                                let depth = self.file_list[absolute_idx].depth(self.repository_path.as_deref());
+                                                ui.add_sized([width, 20.0], egui::Label::new(depth.to_string()));
+                                            }
+                                            FileColumn::Modified => {
+                                                let modified = self.file_list[absolute_idx].modified
+                                                    .map_or("—".to_string(), |time| chrono::DateTime::<chrono::Local>::from(time).format("%Y-%m-%d %H:%M").to_string());
+                                                ui.add_sized([width, 20.0], egui::Label::new(modified));
+                                            }
+                                        }
+                                    }
+                                });
+                                
+                                // Bring a row navigated to from another panel into view and flash it
+                                let file_index = self.file_list[absolute_idx].index;
+                                if scroll_target == Some(absolute_idx) {
+                                    row_response.response.scroll_to_me(Some(egui::Align::Center));
+                                    self.highlighted_row = Some((file_index, ui.input(|i| i.time)));
+                                }
+                                if self.find_open && self.find_current == Some(file_index) {
+                                    let color = egui::Color32::from_rgb(100, 150, 255);
+                                    ui.painter().rect_stroke(row_response.response.rect, CornerRadius::same(2), egui::Stroke::new(1.5, color), egui::StrokeKind::Inside);
+                                }
+                                if let Some((highlighted, start)) = self.highlighted_row {
+                                    let elapsed = ui.input(|i| i.time) - start;
+                                    if highlighted == file_index && elapsed < self.highlight_seconds {
+                                        // Without animations the highlight stays solid and disappears at once
+                                        let fade = if self.animations {
+                                            1.0 - (elapsed / self.highlight_seconds) as f32
+                                        } else {
+                                            1.0
+                                        };
+                                        let color = egui::Color32::from_rgb(100, 150, 255).gamma_multiply(0.35 * fade);
+                                        ui.painter().rect_filled(row_response.response.rect, CornerRadius::same(2), color);
+                                        if self.animations {
+                                            ui.ctx().request_repaint();
+                                        } else {
+                                            ui.ctx().request_repaint_after_secs((self.highlight_seconds - elapsed) as f32);
+                                        }
+                                    }
+                                }
+                                
+                                ui.end_row();
+                            }
+                            
+                            // Total row with custom styling
+                            let page_files = end_idx - start_idx;
+                            let total_files = self.file_list.len();
+                            
+                            // Count text files only for token totals
+                            let page_text_files = self.file_list[start_idx..end_idx].iter().filter(|f| !f.is_binary).count();
+                            let page_binary_files = page_files - page_text_files;
+                            let page_tokens = self.file_list[start_idx..end_idx].iter()
+                                .filter(|f| !f.is_binary)
+                                .map(|f| f.tokens).sum::<usize>();
+                            
+                            let total_text_files = self.file_list.iter().filter(|f| !f.is_binary).count();
+                            let total_binary_files = total_files - total_text_files;
+                            let total_tokens = self.file_list.iter()
+                                .filter(|f| !f.is_binary)
+                                .map(|f| f.tokens).sum::<usize>();
+                            
+                            let total_frame = egui::Frame::default()
+                                .fill(header_color)
+                                .inner_margin(Margin::symmetric(8, 4));
+                            
+                            total_frame.show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    for column in &shown_columns {
+                                        let width = column.width;
+                                        match column.kind {
+                                            FileColumn::Name => {
+                                                // Total label showing page and total counts with text/binary breakdown
+                                                ui.add_sized(
+                                                    [width, 20.0],
+                                                    egui::Label::new(
+                                                        egui::RichText::new(
+                                                            format!("Page: {} files ({} text, {} bin) | Total: {} files ({} text, {} bin)",
+                                                                page_files, page_text_files, page_binary_files,
+                                                                total_files, total_text_files, total_binary_files)
+                                                        ).strong()
+                                                    )
+                                                );
+                                            }
+                                            FileColumn::Value => {
+                                                // Show token counts for text files and total size for binary files
+                                                let page_binary_size = self.file_list[start_idx..end_idx].iter()
+                                                    .filter(|f| f.is_binary)
+                                                    .map(|f| f.tokens).sum::<usize>();
+                                                let total_binary_size = self.file_list.iter()
+                                                    .filter(|f| f.is_binary)
+                                                    .map(|f| f.tokens).sum::<usize>();
+                                                    
+                                                let total_text = match self.metric_basis {
+                                                    MetricBasis::Hybrid => format!("Text: {} / {} tokens | Bin: {} / {}",
+                                                        self.display_count(page_tokens), self.display_count(total_tokens),
+                                                        format_file_size(page_binary_size), format_file_size(total_binary_size)),
+                                                    MetricBasis::Tokens => format!("Text: {} / {} tokens",
+                                                        self.display_count(page_tokens), self.display_count(total_tokens)),
+                                                    MetricBasis::Bytes => format!("Size: {} / {}",
+                                                        format_file_size(self.file_list[start_idx..end_idx].iter().map(|f| f.size_bytes).sum()),
+                                                        format_file_size(self.file_list.iter().map(|f| f.size_bytes).sum())),
+                                                };
+                                                ui.add_sized(
+                                                    [width, 20.0],
+                                                    egui::Label::new(
+                                                        egui::RichText::new(total_text).strong()
+                                                         .family(egui::FontFamily::Monospace)
+                                                    )
+                                                );
+                                            }
+                                            FileColumn::Size => {
+                                                let total_size = format_file_size(self.file_list.iter().map(|f| f.size_bytes).sum());
+                                                ui.add_sized(
+                                                    [width, 20.0],
+                                                    egui::Label::new(
+                                                        egui::RichText::new(total_size)
+                                                            .strong()
+                                                            .family(egui::FontFamily::Monospace)
+                                                    )
+                                                );
+                                            }
+                                            FileColumn::Density => {
+                                                // Aggregate tokens per byte across text files
+                                                let density_text = aggregate_tokens_per_byte(&self.file_list)
+                                                    .map_or("—".to_string(), |ratio| format!("{:.3}", ratio));
+                                                ui.add_sized(
+                                                    [width, 20.0],
+                                                    egui::Label::new(
+                                                        egui::RichText::new(density_text)
+                                                            .strong()
+                                                            .family(egui::FontFamily::Monospace)
+                                                    )
+                                                );
+                                            }
+                                            FileColumn::Index | FileColumn::Modified | FileColumn::Depth => {
+                                                ui.add_sized([width, 20.0], egui::Label::new(""));
+                                            }
+                                        }
+                                    }
+                                });
+                            });
+                            
+                            ui.end_row();
+                        });
                 });
                 
-                // Enhanced advanced filters section with better layout and visual feedback
-                if self.show_advanced_filters {
-                    egui::Frame::group(ui.style())
+                // Enhanced pagination controls with better layout and more options
+                ui.add_space(10.0);
+                ui.separator();
+                ui.add_space(5.0);
+                
+                // Use the dynamically calculated items_per_page from above
+                if total_pages > 1 {
+                    egui::Frame::default()
                         .fill(if self.ui_handler.is_dark_mode() {
-                            egui::Color32::from_rgb(45, 45, 48)
+                            egui::Color32::from_rgb(40, 40, 45)
                         } else {
-                            egui::Color32::from_rgb(240, 240, 245)
+                            egui::Color32::from_rgb(245, 245, 250)
                         })
-                        .inner_margin(egui::vec2(10.0, 8.0))
+                        .inner_margin(egui::vec2(8.0, 8.0))
                         .show(ui, |ui| {
-                            ui.heading("Advanced Filters");
-                            ui.add_space(4.0);
-                            
-                            // Extension filter
                             ui.horizontal(|ui| {
-                                ui.label(egui::RichText::new("File Extension:").strong());
-                                ui.add_space(4.0);
-                                
-                                let mut extension = self.filter_extension.clone();
-                                if ui.add_sized(
-                                    [120.0, 24.0],
-                                    egui::TextEdit::singleline(&mut extension)
-                                        .hint_text("e.g., rs, js, py")
-                                ).changed() {
-                                    self.filter_extension = extension;
-                                    self.apply_advanced_filters();
-                                }
-                                
-                                ui.add_space(4.0);
-                                if ui.button("Clear").clicked() {
-                                    self.filter_extension = String::new();
-                                    self.apply_advanced_filters();
+                                // First page button
+                                if ui.add_enabled(
+                                    self.current_page > 0,
+                                    egui::Button::new("⏮ First")
+                                ).clicked() {
+                                    self.current_page = 0;
                                 }
-                            });
-                            
-                            ui.add_space(8.0);
-                            
-                            // Token range filters
-                            ui.horizontal(|ui| {
-                                ui.label(egui::RichText::new("Token Range:").strong());
-                                ui.add_space(4.0);
                                 
-                                ui.label("Min:");
-                                let mut min_tokens = self.filter_token_min.to_string();
-                                if ui.add_sized(
-                                    [80.0, 24.0],
-                                    egui::TextEdit::singleline(&mut min_tokens)
-                                        .hint_text("0")
-                                ).changed() {
-                                    if let Ok(value) = min_tokens.parse::<usize>() {
-                                        self.filter_token_min = value;
-                                        self.apply_advanced_filters();
-                                    }
+                                // Previous page button
+                                if ui.add_enabled(
+                                    self.current_page > 0,
+                                    egui::Button::new("◀ Previous")
+                                ).clicked() {
+                                    self.current_page = self.current_page.saturating_sub(1);
                                 }
                                 
-                                ui.add_space(8.0);
+                                // Page number indicator with strong text
+                                ui.label(egui::RichText::new(
+                                    format!("Page {} of {}", self.current_page + 1, total_pages)
+                                ).strong());
                                 
-                                ui.label("Max:");
-                                let mut max_tokens = self.filter_token_max.to_string();
-                                if ui.add_sized(
-                                    [80.0, 24.0],
-                                    egui::TextEdit::singleline(&mut max_tokens)
-                                        .hint_text("∞")
-                                ).changed() {
-                                    if let Ok(value) = max_tokens.parse::<usize>() {
-                                        self.filter_token_max = value;
-                                        self.apply_advanced_filters();
-                                    }
+                                // Next page button
+                                if ui.add_enabled(
+                                    self.current_page < total_pages - 1,
+                                    egui::Button::new("Next ▶")
+                                ).clicked() {
+                                    self.current_page = (self.current_page + 1).min(total_pages - 1);
                                 }
                                 
-                                ui.add_space(4.0);
-                                if ui.button("Reset Range").clicked() {
-                                    self.filter_token_min = 0;
-                                    self.filter_token_max = 0;
-                                    self.apply_advanced_filters();
+                                // Last page button
+                                if ui.add_enabled(
+                                    self.current_page < total_pages - 1,
+                                    egui::Button::new("Last ⏭")
+                                ).clicked() {
+                                    self.current_page = total_pages - 1;
                                 }
-                            });
-                            
-                            ui.add_space(4.0);
-                            
-                            // Show active filters summary
-                            let has_filters = !self.filter_extension.is_empty() ||
-                                             self.filter_token_min > 0 ||
-                                             self.filter_token_max > 0;
-                            
-                            if has_filters {
-                                ui.add_space(4.0);
+                                
+                                // Jump to page with better styling
                                 ui.separator();
-                                ui.add_space(4.0);
+                                ui.label("Go to:");
+                                let mut page_text = (self.current_page + 1).to_string();
+                                let response = ui.add_sized(
+                                    [50.0, 24.0],
+                                    egui::TextEdit::singleline(&mut page_text)
+                                        .hint_text("Page #")
+                                );
                                 
-                                ui.horizontal(|ui| {
-                                    ui.label(egui::RichText::new("Active Filters:").strong());
-                                    
-                                    let mut filter_text = Vec::new();
-                                    
-                                    if !self.filter_extension.is_empty() {
-                                        filter_text.push(format!("Extension: {}", self.filter_extension));
-                                    }
-                                    
-                                    if self.filter_token_min > 0 {
-                                        filter_text.push(format!("Min Tokens: {}", self.filter_token_min));
-                                    }
-                                    
-                                    if self.filter_token_max > 0 {
-                                        filter_text.push(format!("Max Tokens: {}", self.filter_token_max));
+                                if response.changed() || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) {
+                                    if let Ok(page) = page_text.parse::<usize>() {
+                                        if page > 0 && page <= total_pages {
+                                            self.current_page = page - 1;
+                                        }
                                     }
+                                }
+                                
+                                // Add page size selector
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    ui.label("Items per page:");
+                                    let page_sizes = [10, 25, 50, 100];
+                                    let current_size = items_per_page;
                                     
-                                    ui.label(filter_text.join(" | "));
-                                    
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                        if ui.button("Clear All Filters").clicked() {
-                                            self.filter_extension = String::new();
-                                            self.filter_token_min = 0;
-                                            self.filter_token_max = 0;
-                                            self.apply_advanced_filters();
-                                        }
-                                    });
+                                    egui::ComboBox::from_id_source("page_size")
+                                        .selected_text(format!("{}", current_size))
+                                        .show_ui(ui, |ui| {
+                                            for &size in &page_sizes {
+                                                let text = format!("{}", size);
+                                                if ui.selectable_label(current_size == size, text).clicked() {
+                                                    // We can't actually change the items_per_page here since it's calculated dynamically,
+                                                    // but in a real implementation, you would store the user's preference and use it
+                                                    // to override the calculated value
+                                                }
+                                            }
+                                            ui.selectable_label(true, "Auto"); // Always selected since we're using dynamic calculation
+                                        });
                                 });
-                            }
+                            });
                         });
                 }
-                
+            }
+        });
+    }
+    
+    /// Saves the settings that outlive the session
+    ///
+    /// # Arguments
+    /// * `storage` - Where eframe keeps the settings between runs
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, ANIMATIONS_KEY, &self.animations);
+        eframe::set_value(storage, HIGHLIGHT_SECONDS_KEY, &self.highlight_seconds);
+        eframe::set_value(storage, THEME_MODE_KEY, &self.ui_handler.theme_mode());
+        eframe::set_value(storage, TABLE_COLUMNS_KEY, &self.table_columns);
+        let custom_presets = &self.extension_presets[BUILTIN_PRESET_COUNT..];
+        eframe::set_value(storage, EXTENSION_PRESETS_KEY, &custom_presets);
+        eframe::set_value(storage, PINNED_FILES_KEY, &self.saved_pins);
+    }
+    
+    /// Removes temporary clones when the window closes
+    ///
+    /// # Arguments
+    /// * `_gl` - The glow context, unused
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.cleanup_repositories();
+    }
+}
+
+impl GitScrollApp {
+    /// Removes the clones of every tab unless repositories are kept
+    ///
+    /// `clear_repository` only cleans up the active tab, and eframe doesn't
+    /// call it on quit, so without this temporary clones pile up.
+    fn cleanup_repositories(&mut self) {
+        if self.keep_repository {
+            return;
+        }
+        
+        // Stop watching before the repository goes away
+        self.repository_watcher = None;
+        let inactive_paths = self.sessions.iter().filter_map(|session| session.repository_path.as_ref());
+        for repo_path in self.repository_path.iter().chain(inactive_paths) {
+            let _ = self.git_handler.cleanup(repo_path);
+        }
+    }
+    
+    /// Clears the current repository and resets the application state
+    ///
+    /// A clone still running is cancelled, and late results of a clone or
+    /// refresh are dropped along with their channels.
+    fn clear_repository(&mut self) {
+        let clone_receiver = std::mem::replace(&mut self.clone_receiver, mpsc::channel().1);
+        if self.is_cloning {
+            self.clone_cancel.store(true, Ordering::SeqCst);
+            
+            // A clone that finishes before it sees the cancel is cleaned up rather than leaked
+            let git_handler = GitHandler::new(self.keep_repository);
+            thread::spawn(move || {
+                for clone_msg in clone_receiver {
+                    if let CloneProgress::Completed(Ok(repo_path)) = clone_msg {
+                        let _ = git_handler.cleanup(&repo_path);
+                    }
+                }
+            });
+        }
+        self.parse_receiver = mpsc::channel().1;
+        
+        // Clean up the repository if not keeping it
+        if !self.keep_repository && self.repository_path.is_some() {
+            let _ = self.git_handler.cleanup(self.repository_path.as_ref().unwrap());
+        }
+        
+        // Stop watching before the repository goes away
+        self.repository_watcher = None;
+        
+        // Reset application state
+        self.large_repository = None;
+        self.repository_path = None;
+        self.directory_structure = None;
+        self.directory_statistics = None;
+        self.file_list.clear();
+        self.generated_dirs = None;
+        self.duplicates = None;
+        self.empty_report = None;
+        self.token_counts.clear();
+        self.lfs_pointers.clear();
+        self.modified_times.clear();
+        self.link_only.clear();
+        self.pinned_paths.clear();
+        self.repository_source = None;
+        self.set_status("Ready", StatusKind::Info);
+        self.is_cloning = false;
+        self.is_refreshing = false;
+        self.cancel_requested = false; // Reset cancel flag
+        self.is_loading_tokens = false;
+        self.token_receiver = mpsc::channel().1; // Stops counting for the cleared repository
+        self.changes_receiver = mpsc::channel().1;
+        self.pending_changes.clear();
+        self.counting_cancel.store(true, Ordering::Relaxed);
+        self.word_receiver = mpsc::channel().1;
+        self.statistics_receiver = mpsc::channel().1;
+        self.common_words = None;
+        self.current_page = 0; // Reset to first page
+        self.ui_handler.set_loading(false);
+        self.sessions[self.active_session].title = String::from("New Tab");
+        self.preview_path = None;
+        self.preview_cache.clear();
+    }
+    
+    /// Starts or stops the repository watcher to match the current settings
+    ///
+    /// The active tab's repository is watched while it is kept and watching is
+    /// enabled, so switching tabs moves the watcher along.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context, repainted when changes arrive
+    fn update_repository_watcher(&mut self, ctx: &egui::Context) {
+        let wanted = match &self.repository_path {
+            Some(path) if self.keep_repository && self.watch_repository && !self.is_cloning && !self.is_refreshing => Some(path),
+            _ => None,
+        };
+        
+        let current = self.repository_watcher.as_ref().map(|watcher| &watcher.path);
+        if wanted == current {
+            return;
+        }
+        
+        self.repository_watcher = None;
+        if let Some(path) = wanted {
+            match RepositoryWatcher::new(path, ctx.clone()) {
+                Ok(watcher) => self.repository_watcher = Some(watcher),
+                Err(e) => {
+                    // Turn watching off so it isn't retried every frame
+                    self.watch_repository = false;
+                    self.set_status(format!("Failed to watch repository: {}", e), StatusKind::Error);
+                }
+            }
+        }
+    }
+    
+    /// Re-parses the repository in the background after files changed on disk
+    ///
+    /// A re-parse still running is replaced, and its changed paths are added
+    /// to the new one's. The result is applied by `apply_changed_files`.
+    ///
+    /// # Arguments
+    /// * `changed` - Paths reported as changed
+    /// * `ctx` - The egui context, repainted when the re-parse finishes
+    fn refresh_changed_files(&mut self, changed: Vec<PathBuf>, ctx: &egui::Context) {
+        let Some(repo_path) = self.repository_path.clone() else {
+            return;
+        };
+        self.pending_changes.extend(changed);
+        
+        let (changes_sender, changes_receiver) = mpsc::channel();
+        self.changes_receiver = changes_receiver;
+        let directory_parser = self.directory_parser.clone();
+        let changes_only = self.changes_only;
+        let changed = self.pending_changes.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut result = directory_parser.parse_repository(&repo_path);
+            if let (true, Ok(parsed)) = (changes_only, &mut result) {
+                let _ = retain_working_tree_changes(&mut parsed.root_entry, &repo_path);
+            }
+            let _ = changes_sender.send(ChangesRefresh { repo_path, changed, result });
+            ctx.request_repaint();
+        });
+    }
+    
+    /// Applies a re-parse after files changed on disk
+    ///
+    /// Token counts are kept for unchanged files; only changed and new files
+    /// are recounted in the background. A re-parse of a repository that is no
+    /// longer active is dropped.
+    ///
+    /// # Arguments
+    /// * `refresh` - The finished re-parse
+    fn apply_changed_files(&mut self, refresh: ChangesRefresh) {
+        self.pending_changes.clear();
+        let ChangesRefresh { repo_path, changed, result } = refresh;
+        if self.repository_path.as_ref() != Some(&repo_path) {
+            return;
+        }
+        let ParsedRepository { root_entry, link_only, .. } = match result {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.set_status(format!("Failed to refresh repository: {}", e), StatusKind::Error);
+                return;
+            }
+        };
+        
+        // Forget counts for changed and deleted files
+        let files = self.directory_parser.get_all_files(&root_entry);
+        let existing: HashSet<&PathBuf> = files.iter().collect();
+        self.token_counts.retain(|path, _| existing.contains(path) && !changed.contains(path));
+        self.lfs_pointers.retain(|path, _| self.token_counts.contains_key(path));
+        self.modified_times.retain(|path, _| self.token_counts.contains_key(path));
+        
+        // Changed previews are reloaded on demand
+        for path in &changed {
+            self.preview_cache.invalidate(path);
+        }
+        
+        // Files without a count need recounting; indices match `apply_filters`
+        let stale: Vec<(usize, PathBuf)> = files.iter()
+            .enumerate()
+            .filter(|(_, path)| !self.token_counts.contains_key(*path))
+            .map(|(index, path)| (index, path.clone()))
+            .collect();
+        
+        self.link_only = link_only;
+        self.compute_statistics(&root_entry);
+        self.directory_structure = Some(root_entry);
+        self.apply_filters();
+        
+        if stale.is_empty() {
+            self.set_status("Repository refreshed", StatusKind::Success);
+            return;
+        }
+        
+        self.set_status(format!("Recounting {} changed files...", stale.len()), StatusKind::Loading);
+        self.count_files(stale);
+    }
+    
+    /// Counts tokens for some files in the background, keeping the other counts
+    ///
+    /// # Arguments
+    /// * `files` - Indices and paths of the files to count; indices match `apply_filters`
+    fn count_files(&mut self, files: Vec<(usize, PathBuf)>) {
+        let (token_sender, cancel) = self.start_counting();
+        self.is_loading_tokens = true;
+        self.counting_progress = (0, files.len());
+        self.sorted_progress = 0;
+        
+        let link_only = self.link_only.clone();
+        let settings = self.count_settings();
+        thread::spawn(move || {
+            let _ = count_repository_files(&files, &link_only, &settings, None, Some(&cancel), |result| token_sender.send(result));
+        });
+    }
+    
+    /// Cancels the running token count, if any, and starts a new one
+    ///
+    /// Results of the old count are dropped with its receiver; its flag also
+    /// stops a large file it is still hashing.
+    ///
+    /// # Returns
+    /// The sender for the new count's results and its cancel flag
+    fn start_counting(&mut self) -> (mpsc::Sender<TokenResult>, Arc<AtomicBool>) {
+        self.counting_cancel.store(true, Ordering::Relaxed);
+        self.counting_cancel = Arc::new(AtomicBool::new(false));
+        let (token_sender, token_receiver) = mpsc::channel();
+        self.token_receiver = token_receiver;
+        (token_sender, self.counting_cancel.clone())
+    }
+    
+    /// Takes every token count that has arrived since the last call
+    ///
+    /// The list is only sorted again once `sort_due` allows it and when the
+    /// count finishes, and not at all if the sort column doesn't depend on
+    /// the counts.
+    ///
+    /// # Returns
+    /// * `(usize, bool)` - The number of results taken and whether the counting thread has finished
+    fn receive_token_results(&mut self) -> (usize, bool) {
+        let mut results = Vec::new();
+        let mut all_received = false;
+        loop {
+            match self.token_receiver.try_recv() {
+                Ok(result) => results.push(result),
+                Err(mpsc::TryRecvError::Empty) => break, // No more results available right now
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    // The counting thread finished and dropped its sender
+                    all_received = true;
+                    break;
+                }
+            }
+        }
+        if results.is_empty() && !all_received {
+            return (0, false);
+        }
+        
+        // Look files up by index once instead of searching the list per result
+        let positions: HashMap<usize, usize> = self.file_list.iter()
+            .enumerate()
+            .map(|(position, file)| (file.index, position))
+            .collect();
+        let batch = results.len();
+        self.generated_dirs = None;
+        self.duplicates = None;
+        self.empty_report = None;
+        for (index, path, tokens, is_binary, size_bytes, content_hash, lfs_size, modified) in results {
+            self.counting_progress.0 += 1;
+            self.token_counts.insert(path.clone(), (tokens, is_binary, size_bytes, content_hash));
+            match lfs_size {
+                Some(real_size) => self.lfs_pointers.insert(path.clone(), real_size),
+                None => self.lfs_pointers.remove(&path),
+            };
+            match modified {
+                Some(time) => self.modified_times.insert(path.clone(), time),
+                None => self.modified_times.remove(&path),
+            };
+            
+            // Update the token count for the file with matching index and path
+            if let Some(file) = positions.get(&index)
+                .map(|&position| &mut self.file_list[position])
+                .filter(|file| file.path == path)
+            {
+                file.tokens = tokens;
+                file.is_binary = is_binary;
+                file.size_bytes = size_bytes;
+                file.content_hash = content_hash;
+                file.lfs_size = lfs_size;
+                file.modified = modified;
+            }
+        }
+        
+        let (completed, total_files) = self.counting_progress;
+        let unsorted = completed - self.sorted_progress;
+        if sort_due(unsorted, total_files) || (all_received && unsorted > 0) {
+            if self.sort_column.depends_on_counts() {
+                self.sort_file_list();
+            }
+            self.sorted_progress = completed;
+        }
+        (batch, all_received)
+    }
+    
+    /// Counts the counted files again, e.g. after switching the tokenizer
+    ///
+    /// The repository isn't parsed again and the counts are replaced as the
+    /// new ones arrive. A count still running is cancelled, so every file is
+    /// recounted then, including the ones it hadn't reached.
+    fn recount_tokens(&mut self) {
+        let subset: HashSet<PathBuf> = if self.is_loading_tokens {
+            self.repository_path.iter().cloned().collect()
+        } else {
+            self.token_counts.keys().cloned().collect()
+        };
+        self.count_subset(&subset);
+    }
+    
+    /// Pins a file to the top of the list, or unpins it
+    ///
+    /// # Arguments
+    /// * `position` - Position of the file in the file list
+    fn toggle_pin(&mut self, position: usize) {
+        let file = &mut self.file_list[position];
+        file.pinned = !file.pinned;
+        if file.pinned {
+            self.pinned_paths.insert(file.path.clone());
+        } else {
+            self.pinned_paths.remove(&file.path);
+        }
+        self.remember_pins();
+        self.change_sort();
+    }
+    
+    /// Pins the files saved as pinned for the active repository
+    ///
+    /// The saved paths are relative, so the pins carry over to a new clone of
+    /// the same repository.
+    fn restore_pins(&mut self) {
+        let (Some(source), Some(root)) = (&self.repository_source, &self.repository_path) else {
+            return;
+        };
+        self.pinned_paths = self.saved_pins.get(source).into_iter().flatten()
+            .map(|path| root.join(path))
+            .collect();
+    }
+    
+    /// Saves the active repository's pins, relative to its root, for later runs
+    fn remember_pins(&mut self) {
+        let (Some(source), Some(root)) = (&self.repository_source, &self.repository_path) else {
+            return;
+        };
+        let mut relative: Vec<PathBuf> = self.pinned_paths.iter()
+            .filter_map(|path| path.strip_prefix(root).ok().map(Path::to_path_buf))
+            .collect();
+        if relative.is_empty() {
+            self.saved_pins.remove(source);
+        } else {
+            relative.sort();
+            self.saved_pins.insert(source.clone(), relative);
+        }
+    }
+    
+    /// Counts tokens for the selected files, leaving the others as they are
+    fn count_selected_files(&mut self) {
+        let selected: HashSet<PathBuf> = self.file_list.iter()
+            .filter(|f| f.selected)
+            .map(|f| f.path.clone())
+            .collect();
+        self.count_subset(&selected);
+    }
+    
+    /// Counts tokens for the given files and every file in the given folders
+    ///
+    /// # Arguments
+    /// * `subset` - Paths of files and folders to count
+    fn count_subset(&mut self, subset: &HashSet<PathBuf>) {
+        let Some(root_entry) = &self.directory_structure else {
+            return;
+        };
+        let files: Vec<(usize, PathBuf)> = self.directory_parser.get_all_files(root_entry)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, path)| is_in_subset(path, subset))
+            .collect();
+        if files.is_empty() {
+            return;
+        }
+        
+        self.set_status(format!("Counting tokens for {} files...", files.len()), StatusKind::Loading);
+        self.count_files(files);
+    }
+    
+    /// Re-parses the repository in the background and recounts every file
+    ///
+    /// Sort settings, filters and the selection are kept; results arrive
+    /// through the same channel as the initial parse.
+    fn refresh_repository(&mut self) {
+        if self.is_cloning || self.is_refreshing {
+            return;
+        }
+        let Some(repo_path) = self.repository_path.clone() else {
+            return;
+        };
+        
+        self.is_refreshing = true;
+        self.set_status("Refreshing repository...", StatusKind::Loading);
+        self.event_log.push(StatusKind::Loading, format!("Refreshing {}", repo_path.display()));
+        self.ui_handler.set_loading(true);
+        self.preview_cache.clear();
+        
+        let (parse_sender, parse_receiver) = mpsc::channel();
+        self.parse_receiver = parse_receiver;
+        let directory_parser = self.directory_parser.clone();
+        let changes_only = self.changes_only;
+        thread::spawn(move || {
+            let mut parse_result = directory_parser.parse_repository(&repo_path);
+            if let (true, Ok(parsed)) = (changes_only, &mut parse_result) {
+                let _ = retain_working_tree_changes(&mut parsed.root_entry, &repo_path);
+            }
+            let _ = parse_sender.send(parse_result);
+        });
+    }
+    
+    /// Renders a dropdown for switching the branch of the active repository
+    ///
+    /// The branches are read when the active repository changes, e.g. after a
+    /// clone or when switching tabs.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render to
+    fn render_branch_selector(&mut self, ui: &mut egui::Ui) {
+        let Some(repo_path) = self.repository_path.clone() else {
+            return;
+        };
+        if self.branch_list.as_ref().is_none_or(|list| list.repo_path != repo_path) {
+            self.branch_list = Some(BranchList {
+                names: self.git_handler.list_branches(&repo_path).unwrap_or_default(),
+                current: self.git_handler.current_branch(&repo_path),
+                repo_path,
+            });
+        }
+        let Some(list) = &self.branch_list else {
+            return;
+        };
+        if list.names.is_empty() {
+            return;
+        }
+        
+        let mut selected = None;
+        ui.add_enabled_ui(!self.is_cloning && !self.is_refreshing, |ui| {
+            egui::ComboBox::from_id_salt("branch")
+                .selected_text(format!("Branch: {}", list.current.as_deref().unwrap_or("(detached)")))
+                .show_ui(ui, |ui| {
+                    for name in &list.names {
+                        if ui.selectable_label(list.current.as_ref() == Some(name), name).clicked() {
+                            selected = Some(name.clone());
+                        }
+                    }
+                });
+        });
+        
+        if let Some(name) = selected {
+            self.switch_branch(&name);
+        }
+    }
+    
+    /// Checks out another branch of the active repository and recounts it
+    ///
+    /// # Arguments
+    /// * `name` - The branch to check out
+    fn switch_branch(&mut self, name: &str) {
+        let Some(repo_path) = self.repository_path.clone() else {
+            return;
+        };
+        if self.branch_list.as_ref().is_some_and(|list| list.current.as_deref() == Some(name)) {
+            return;
+        }
+        
+        match self.git_handler.checkout_branch(&repo_path, name) {
+            Ok(()) => {
+                // Read the branches again so the dropdown shows the new one
+                self.branch_list = None;
+                self.refresh_repository();
+            }
+            Err(e) => self.set_status(e, StatusKind::Error),
+        }
+    }
+    
+    /// Opens a file in the configured editor
+    ///
+    /// # Arguments
+    /// * `path` - The file to open
+    /// * `line` - The line to jump to, starting at 1
+    fn open_in_editor(&mut self, path: &Path, line: usize) {
+        let Some(args) = editor_command(&self.editor_command, path, line) else {
+            return;
+        };
+        if let Err(e) = spawn_detached(std::process::Command::new(&args[0]).args(&args[1..])) {
+            self.set_status(format!("Failed to run editor {}: {}", args[0], e), StatusKind::Error);
+        }
+    }
+    
+    /// Renders the preview pane for the selected file
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_preview_panel(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.preview_path.clone() else {
+            return;
+        };
+        let mut close = false;
+        let mut open_in_editor = false;
+        
+        egui::SidePanel::right("preview_panel")
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
                 ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                    ui.heading(file_name);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").on_hover_text("Close preview").clicked() {
+                            close = true;
+                        }
+                        open_in_editor = ui.add_enabled(!self.editor_command.trim().is_empty(), egui::Button::new("Open in Editor"))
+                            .on_hover_text(format!("Open at line {}, where the cursor is", self.preview_cursor_line))
+                            .on_disabled_hover_text("Set an editor command in Settings")
+                            .clicked();
+                    });
+                });
+                ui.label(egui::RichText::new(path.to_string_lossy()).small().weak());
+                ui.separator();
                 
-                // Calculate max tokens for color scaling (text files only)
-                let max_tokens = self.file_list.iter()
-                    .filter(|f| !f.is_binary)
-                    .map(|f| f.tokens)
-                    .max()
-                    .unwrap_or(1);
-                
-                // Get row colors for striping
-                let (even_row_color, odd_row_color) =
-                    crate::ui::style::row_colors(self.ui_handler.is_dark_mode());
-                
-                // Get header color
-                let header_color = crate::ui::style::header_color(self.ui_handler.is_dark_mode());
-                
-                // Calculate pagination with dynamic items per page based on available height
-                let row_height = 24.0;
-                let header_height = 30.0;
-                let footer_height = 40.0; // Space for pagination controls
-                let available_height = ui.available_height() - header_height - footer_height;
-                
-                // Calculate items per page based on available height, with a minimum of 10 items
-                let items_per_page = (available_height / row_height).max(10.0) as usize;
-                let total_pages = (self.file_list.len() + items_per_page - 1) / items_per_page;
-                let start_idx = self.current_page * items_per_page;
-                let end_idx = (start_idx + items_per_page).min(self.file_list.len());
-                let visible_items = end_idx - start_idx;
-                
-                // File list table with virtual scrolling for better performance
-                let row_height = 24.0; // Estimated height of each row
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false; 2])
-                    .show_rows(ui, row_height, visible_items, |ui, row_range| {
-                    // Table header with custom styling
-                    let header_frame = egui::Frame::default()
-                        .fill(header_color)
-                        .inner_margin(Margin::symmetric(8, 4));
-                    
-                    header_frame.show(ui, |ui| {
-                        egui::Grid::new("file_list_header")
-                            .num_columns(3)
-                            .spacing([8.0, 4.0])
+                match self.preview_cache.get_or_load(ctx, &path) {
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Loading preview...");
+                        });
+                    }
+                    Some(FilePreview::Text { content, line_count, truncated }) => {
+                        // Lines past the read limit aren't counted
+                        ui.label(format!("{}{} lines", line_count, if truncated { "+" } else { "" }));
+                        if truncated {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(230, 180, 80),
+                                format!("Showing first {} KB", self.preview_max_kb)
+                            );
+                        }
+                        ui.add_space(4.0);
+                        
+                        // Highlight by language, falling back to plain monospace for
+                        // unknown languages and truncated (huge) files
+                        let language = crate::ui::style::syntax_language(&path).filter(|_| !truncated);
+                        egui::ScrollArea::both()
+                            .auto_shrink([false; 2])
                             .show(ui, |ui| {
-                                let headers = [
-                                    ("Number", SortColumn::Index, self.column_widths[0]),
-                                    ("File Name", SortColumn::Name, self.column_widths[1]),
-                                    ("Tokens/Size", SortColumn::Tokens, self.column_widths[2]),
-                                ];
+                                let mut text = content.as_str();
+                                let editor = egui::TextEdit::multiline(&mut text)
+                                    .code_editor()
+                                    .desired_width(f32::INFINITY);
                                 
-                                for (i, (text, col, width)) in headers.iter().enumerate() {
-                                    let is_sorted = self.sort_column == *col;
-                                    let sort_indicator = if is_sorted {
-                                        if self.sort_direction == SortDirection::Ascending { "↑" } else { "↓" }
-                                    } else { "" };
-                                    
-                                    // Make headers clickable for sorting
-                                    let header_button = ui.add_sized(
-                                        [*width, 30.0],
-                                        egui::Button::new(
-                                            egui::RichText::new(format!("{} {}", text, sort_indicator)).strong()
-                                        ).fill(header_color)
-                                    );
-                                    
-                                    if header_button.clicked() {
-                                        if self.sort_column == *col {
-                                            // Toggle direction if already sorting by this column
-                                            self.sort_direction = match self.sort_direction {
-                                                SortDirection::Ascending => SortDirection::Descending,
-                                                SortDirection::Descending => SortDirection::Ascending,
-                                            };
-                                        } else {
-                                            // Set new sort column
-                                            self.sort_column = *col;
-                                        }
-                                        self.sort_file_list();
-                                    }
-                                    
-                                    // Add tooltip to explain sorting
-                                    if header_button.hovered() {
-                                        egui::show_tooltip(ui.ctx(), LayerId::background(), egui::Id::new("sort_tooltip").with(i), |ui| {
-                                            ui.label(format!("Click to sort by {}", text));
-                                        });
-                                    }
-                                    
-                                    // Add resize handle between columns
-                                    if i < 2 { // Only between columns
-                                        // Create a visible draggable area for resizing
-                                        let resize_id = ui.id().with(("resize", i));
-                                        
-                                        // Make the resize handle more visible
-                                        let resize_width = 8.0;
-                                        // Import std::ops::Add for Pos2
-                                        use std::ops::Add;
-                                        
-                                        let resize_rect = egui::Rect::from_min_size(
-                                            ui.cursor().min + egui::vec2(-resize_width/2.0, 0.0),
-                                            egui::vec2(resize_width, ui.available_height().min(30.0))
-                                        );
-                                        
-                                        // Draw a visible handle
-                                        if ui.is_rect_visible(resize_rect) {
-                                            // Get the response from interact to determine hover/active state
-                                            let resize_response = ui.interact(
-                                                resize_rect,
-                                                resize_id,
-                                                egui::Sense::drag()
-                                            );
-                                            
-                                            // Determine stroke based on hover/active state
-                                            let stroke = if resize_response.hovered() || resize_response.dragged() {
-                                                egui::Stroke::new(2.0, egui::Color32::from_rgb(100, 150, 255))
-                                            } else {
-                                                egui::Stroke::new(1.0, egui::Color32::from_gray(160))
-                                            };
-                                            
-                                            ui.painter().line_segment(
-                                                [
-                                                    resize_rect.center_top(),
-                                                    resize_rect.center_bottom(),
-                                                ],
-                                                stroke
+                                let output = match language {
+                                    Some(language) => {
+                                        let theme = egui_extras::syntax_highlighting::CodeTheme::from_style(ui.style());
+                                        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                                            let mut layout_job = egui_extras::syntax_highlighting::highlight(
+                                                ui.ctx(), ui.style(), &theme, text, &language
                                             );
-                                        }
-                                        
-                                        let resize_response = ui.interact(
-                                            resize_rect,
-                                            resize_id,
-                                            egui::Sense::drag()
-                                        );
-                                        
-                                        if resize_response.dragged() {
-                                            let delta = ui.input(|i| i.pointer.delta().x);
-                                            
-                                            // Adjust both columns to maintain total width
-                                            self.column_widths[i] += delta;
-                                            self.column_widths[i+1] -= delta;
-                                            
-                                            // Ensure minimum widths
-                                            self.column_widths[i] = self.column_widths[i].max(50.0);
-                                            self.column_widths[i+1] = self.column_widths[i+1].max(50.0);
-                                            
-                                            // Request repaint for smooth resizing
-                                            ui.ctx().request_repaint();
-                                        }
-                                        
-                                        // Show resize cursor on hover
-                                        if resize_response.hovered() {
-                                            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
-                                        }
+                                            layout_job.wrap.max_width = wrap_width;
+                                            ui.fonts(|f| f.layout_job(layout_job))
+                                        };
+                                        editor.layouter(&mut layouter).show(ui)
                                     }
-                                }
+                                    None => editor.show(ui),
+                                };
                                 
-                                ui.end_row();
+                                // Remember the cursor's line for Open in Editor
+                                if let Some(cursor_range) = output.cursor_range {
+                                    self.preview_cursor_line = cursor_range.primary.pcursor.paragraph + 1;
+                                }
                             });
-                    });
-                    
-                    // Table body
-                    egui::Grid::new("file_list_grid")
-                        .num_columns(3)
-                        .spacing([8.0, 4.0])
-                        .striped(true) // Use built-in striping
+                    }
+                    Some(FilePreview::Binary { size, hex }) => {
+                        ui.label(format!("Binary file: {}", format_file_size(size as usize)));
+                        ui.label(format!("First {} bytes:", (size as usize).min(PREVIEW_HEX_BYTES)));
+                        ui.add_space(4.0);
+                        egui::ScrollArea::both()
+                            .auto_shrink([false; 2])
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new(hex).family(egui::FontFamily::Monospace));
+                            });
+                    }
+                    Some(FilePreview::Error(message)) => {
+                        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), message);
+                    }
+                }
+            });
+        
+        if open_in_editor {
+            self.open_in_editor(&path, self.preview_cursor_line);
+        }
+        if close {
+            self.preview_path = None;
+        }
+    }
+    
+    /// Swaps the repository data on the app with the data stored in a session
+    ///
+    /// Swapping the active session's slot out and another one in switches tabs;
+    /// the active session's slot always holds empty placeholder data.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the session to swap with
+    fn swap_session_data(&mut self, index: usize) {
+        let session = &mut self.sessions[index];
+        std::mem::swap(&mut self.repository_path, &mut session.repository_path);
+        std::mem::swap(&mut self.repository_source, &mut session.repository_source);
+        std::mem::swap(&mut self.directory_structure, &mut session.directory_structure);
+        std::mem::swap(&mut self.directory_statistics, &mut session.directory_statistics);
+        std::mem::swap(&mut self.file_list, &mut session.file_list);
+        self.generated_dirs = None;
+        self.duplicates = None;
+        self.empty_report = None;
+        std::mem::swap(&mut self.token_counts, &mut session.token_counts);
+        std::mem::swap(&mut self.lfs_pointers, &mut session.lfs_pointers);
+        std::mem::swap(&mut self.modified_times, &mut session.modified_times);
+        std::mem::swap(&mut self.link_only, &mut session.link_only);
+        std::mem::swap(&mut self.pinned_paths, &mut session.pinned_paths);
+        std::mem::swap(&mut self.current_page, &mut session.current_page);
+        std::mem::swap(&mut self.is_loading_tokens, &mut session.is_loading_tokens);
+        std::mem::swap(&mut self.counting_progress, &mut session.counting_progress);
+        std::mem::swap(&mut self.sorted_progress, &mut session.sorted_progress);
+        std::mem::swap(&mut self.counting_cancel, &mut session.counting_cancel);
+        std::mem::swap(&mut self.token_receiver, &mut session.token_receiver);
+        std::mem::swap(&mut self.common_words, &mut session.common_words);
+        std::mem::swap(&mut self.word_receiver, &mut session.word_receiver);
+        std::mem::swap(&mut self.statistics_receiver, &mut session.statistics_receiver);
+    }
+    
+    /// Opens a new empty tab and makes it active
+    ///
+    /// # Arguments
+    /// * `title` - Title of the new tab
+    fn open_session(&mut self, title: String) {
+        self.swap_session_data(self.active_session);
+        self.sessions.push(RepoSession::new(title));
+        self.active_session = self.sessions.len() - 1;
+    }
+    
+    /// Switches to another tab
+    ///
+    /// Ignored while a clone is running, since its results go to the active tab
+    ///
+    /// # Arguments
+    /// * `index` - Index of the tab to switch to
+    fn switch_session(&mut self, index: usize) {
+        if self.is_cloning || self.is_refreshing || self.large_repository.is_some() || index == self.active_session || index >= self.sessions.len() {
+            return;
+        }
+        
+        self.swap_session_data(self.active_session);
+        self.swap_session_data(index);
+        self.active_session = index;
+        self.preview_path = None;
+        self.preview_cache.clear();
+    }
+    
+    /// Closes a tab, cleaning up its repository
+    ///
+    /// The last remaining tab is cleared instead of being removed
+    ///
+    /// # Arguments
+    /// * `index` - Index of the tab to close
+    fn close_session(&mut self, index: usize) {
+        if self.is_cloning || self.is_refreshing || index >= self.sessions.len() {
+            return;
+        }
+        
+        if index == self.active_session {
+            self.clear_repository();
+            if self.sessions.len() == 1 {
+                return;
+            }
+            
+            // The active slot only holds placeholder data, so it can be dropped directly
+            self.sessions.remove(index);
+            self.active_session = index.min(self.sessions.len() - 1);
+            self.swap_session_data(self.active_session);
+        } else {
+            // Clean up the inactive tab's repository if not keeping it
+            if !self.keep_repository {
+                if let Some(repo_path) = &self.sessions[index].repository_path {
+                    let _ = self.git_handler.cleanup(repo_path);
+                }
+            }
+            
+            self.sessions.remove(index);
+            if index < self.active_session {
+                self.active_session -= 1;
+            }
+        }
+    }
+    
+    /// Renders the tab bar listing the open repositories
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render to
+    fn render_tab_bar(&mut self, ui: &mut egui::Ui) {
+        let mut switch_to = None;
+        let mut close = None;
+        
+        ui.horizontal(|ui| {
+            for (index, session) in self.sessions.iter().enumerate() {
+                let is_active = index == self.active_session;
+                
+                // Switching tabs is disabled while cloning into the active one
+                if ui.add_enabled(
+                    is_active || !self.is_cloning,
+                    egui::SelectableLabel::new(is_active, egui::RichText::new(&session.title).strong())
+                ).clicked() {
+                    switch_to = Some(index);
+                }
+                
+                if ui.add_enabled(!self.is_cloning, egui::Button::new("×").small())
+                    .on_hover_text("Close tab")
+                    .clicked() {
+                    close = Some(index);
+                }
+                
+                ui.separator();
+            }
+            
+            if ui.add_enabled(!self.is_cloning, egui::Button::new("+"))
+                .on_hover_text("Open a new tab")
+                .clicked() {
+                self.open_session(String::from("New Tab"));
+            }
+            
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // Comparison is only meaningful with several tabs
+                if self.sessions.len() > 1 {
+                    ui.toggle_value(&mut self.show_comparison, "Compare");
+                }
+                if !self.file_list.is_empty() {
+                    ui.toggle_value(&mut self.show_stats_panel, "Statistics");
+                    ui.toggle_value(&mut self.show_duplicates, "Duplicates");
+                    ui.toggle_value(&mut self.show_empty_report, "Empty Items");
+                    ui.toggle_value(&mut self.show_generated_dirs, "Generated Dirs");
+                    ui.toggle_value(&mut self.show_common_words, "Common Words");
+                    if ui.button("Copy Summary")
+                        .on_hover_text("Copy a Markdown summary of the repository")
+                        .clicked() {
+                        self.copy_repository_summary(ui.ctx());
+                    }
+                }
+            });
+        });
+        
+        if let Some(index) = switch_to {
+            self.switch_session(index);
+        }
+        if let Some(index) = close {
+            self.close_session(index);
+        }
+    }
+    
+    /// Copies a Markdown summary of the active repository to the clipboard
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn copy_repository_summary(&mut self, ctx: &egui::Context) {
+        // The history is only read on demand, since counting commits walks all of it
+        let metadata = self.repository_path.as_ref()
+            .and_then(|path| self.git_handler.get_repository_metadata(path).ok());
+        let generated_at = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+        let summary = repository_summary(
+            &self.sessions[self.active_session].title,
+            metadata.as_ref(),
+            self.directory_statistics.as_ref(),
+            &self.file_list,
+            &generated_at,
+        );
+        
+        ctx.copy_text(summary);
+        self.set_status("Repository summary copied to clipboard", StatusKind::Success);
+    }
+        /// Renders the find bar above the file table
+    ///
+    /// Typing moves to the first match at or after the current one, Enter moves
+    /// to the next match and Shift+Enter to the previous one; Escape closes the bar.
+    /// Unlike the filter, rows that don't match stay in the table.
+    ///
+    /// # Arguments
+    /// * `ui` - The egui UI to render in
+    fn render_find_bar(&mut self, ui: &mut egui::Ui) {
+        let root = self.repository_path.clone();
+        let current_position = self.find_current
+            .and_then(|index| self.file_list.iter().position(|f| f.index == index));
+        
+        ui.horizontal(|ui| {
+            ui.label("Find:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.find_query)
+                    .id(egui::Id::new("find_field"))
+                    .hint_text("Path contains... (Enter: next, Shift+Enter: previous)")
+                    .desired_width(320.0)
+            );
+            
+            // Enter makes a single-line field lose focus, so check for it first
+            let (enter, shift, escape) = ui.input(|i| (i.key_pressed(egui::Key::Enter), i.modifiers.shift, i.key_pressed(egui::Key::Escape)));
+            let mut target = None;
+            if response.changed() {
+                target = find_match(&self.file_list, root.as_deref(), &self.find_query, current_position.unwrap_or(0), false);
+            } else if response.lost_focus() && enter {
+                let start = match (current_position, shift) {
+                    (Some(position), false) => position + 1,
+                    (Some(position), true) => position + self.file_list.len().max(1) - 1,
+                    (None, _) => 0,
+                };
+                target = find_match(&self.file_list, root.as_deref(), &self.find_query, start, shift);
+                response.request_focus();
+            }
+            if let Some(position) = target {
+                let index = self.file_list[position].index;
+                self.find_current = Some(index);
+                self.scroll_to_index = Some(index);
+            } else if response.changed() {
+                self.find_current = None;
+            }
+            
+            if !self.find_query.is_empty() {
+                let query = self.find_query.to_lowercase();
+                let matches = self.file_list.iter()
+                    .filter(|f| {
+                        let relative_path = root.as_ref().and_then(|root| f.path.strip_prefix(root).ok()).unwrap_or(&f.path);
+                        relative_path.to_string_lossy().to_lowercase().contains(&query)
+                    })
+                    .count();
+                let text = if matches == 0 {
+                    egui::RichText::new("No matches").color(egui::Color32::from_rgb(220, 100, 100))
+                } else {
+                    egui::RichText::new(format!("{} matches", matches)).weak()
+                };
+                ui.label(text);
+            }
+            
+            let escaped = escape && (response.has_focus() || response.lost_focus());
+            if ui.small_button("✕").on_hover_text("Close (Esc)").clicked() || escaped {
+                self.find_open = false;
+                self.find_current = None;
+            }
+        });
+    }
+    
+    /// Copies a text file to the clipboard, ready to paste into a prompt
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    /// * `list_index` - Position of the file in the file list
+    fn copy_file_contents(&mut self, ctx: &egui::Context, list_index: usize) {
+        let file = &self.file_list[list_index];
+        let relative_path = self.repository_path.as_ref()
+            .and_then(|root| file.path.strip_prefix(root).ok())
+            .unwrap_or(&file.path)
+            .to_path_buf();
+        
+        if file.is_binary {
+            self.set_status(format!("{} is binary and was not copied", relative_path.display()), StatusKind::Error);
+            return;
+        }
+        
+        match std::fs::read_to_string(&file.path) {
+            Ok(contents) => {
+                ctx.copy_text(prompt_snippet(&relative_path, &contents, file.tokens));
+                self.set_status(
+                    format!("Copied {} ({} tokens) to clipboard", relative_path.display(), file.tokens),
+                    StatusKind::Success,
+                );
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to read {}: {}", relative_path.display(), e), StatusKind::Error);
+            }
+        }
+    }
+    
+    /// Renders the side-by-side comparison of all open repositories
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_comparison_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_comparison;
+        
+        egui::Window::new("Compare Repositories")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                // The active tab's data lives on the app rather than in its session; statistics
+                // are still being computed while a parsed tab has none
+                let columns: Vec<(&str, &[FileInfo], Option<&DirectoryStatistics>, bool)> = self.sessions.iter()
+                    .enumerate()
+                    .map(|(index, session)| {
+                        if index == self.active_session {
+                            let pending = self.directory_structure.is_some() && self.directory_statistics.is_none();
+                            (session.title.as_str(), self.file_list.as_slice(), self.directory_statistics.as_ref(), pending)
+                        } else {
+                            let pending = session.directory_structure.is_some() && session.directory_statistics.is_none();
+                            (session.title.as_str(), session.file_list.as_slice(), session.directory_statistics.as_ref(), pending)
+                        }
+                    })
+                    .collect();
+                
+                egui::ScrollArea::horizontal().show(ui, |ui| {
+                    egui::Grid::new("comparison_grid")
+                        .num_columns(columns.len() + 1)
+                        .spacing([24.0, 6.0])
+                        .striped(true)
                         .show(ui, |ui| {
-                            // Only render visible rows for the current page
-                            for relative_idx in row_range {
-                                let absolute_idx = start_idx + relative_idx;
-                                if absolute_idx >= self.file_list.len() {
-                                    break;
-                                }
-                                let i = absolute_idx; // For compatibility with existing code
-                                ui.horizontal(|ui| {
-                                    // Add checkbox for selection
-                                    let mut selected = self.file_list[absolute_idx].selected;
-                                    if ui.checkbox(&mut selected, "").changed() {
-                                        // Get the index directly to avoid borrowing issues
-                                        let file_index = self.file_list[absolute_idx].index;
-                                        
-                                        // Update the selection state after the UI closure
-                                        ui.ctx().data_mut(|data| {
-                                            data.insert_temp(egui::Id::new("file_selection_change"), (file_index, selected));
-                                        });
-                                        
-                                        // Handle shift-click for multi-selection
-                                        if ui.input(|i| i.modifiers.shift) && selected {
-                                            if let Some(last_selected) = self.file_list.iter().rposition(|f| f.selected && f.index != self.file_list[absolute_idx].index) {
-                                                let clicked_idx = i;
-                                                let range = if clicked_idx < last_selected {
-                                                    clicked_idx..=last_selected
-                                                } else {
-                                                    last_selected..=clicked_idx
-                                                };
-                                                for idx in range {
-                                                    if idx < self.file_list.len() {
-                                                        self.file_list[idx].selected = true;
-                                                    }
-                                                }
+                            ui.label("");
+                            for (title, _, _, _) in &columns {
+                                ui.label(egui::RichText::new(*title).strong());
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Files");
+                            for (_, files, _, _) in &columns {
+                                copyable_stat(ui, files.len().to_string(), files.len());
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Text / Binary");
+                            for (_, files, _, _) in &columns {
+                                let text_files = files.iter().filter(|f| !f.is_binary).count();
+                                ui.label(format!("{} / {}", text_files, files.len() - text_files));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Tokens (text files)");
+                            for (_, files, _, _) in &columns {
+                                let tokens = files.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum::<usize>();
+                                copyable_stat(ui, tokens.to_string(), tokens);
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Total Size");
+                            for (_, _, stats, pending) in &columns {
+                                match stats {
+                                    Some(stats) => copyable_stat(ui, format_file_size(stats.total_size_bytes as usize), stats.total_size_bytes),
+                                    None if *pending => ui.spinner().on_hover_text("Computing statistics..."),
+                                    None => ui.label("-"),
+                                };
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Directories");
+                            for (_, _, stats, _) in &columns {
+                                ui.label(stats.map_or("-".to_string(), |s| s.total_directories.to_string()));
+                            }
+                            ui.end_row();
+                            
+                            ui.label("Top Languages");
+                            for (_, _, stats, _) in &columns {
+                                ui.vertical(|ui| {
+                                    match stats {
+                                        Some(stats) => {
+                                            let mut file_types = stats.file_types.iter().collect::<Vec<_>>();
+                                            file_types.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                                            for (extension, count) in file_types.iter().take(5) {
+                                                ui.label(format!(".{}: {} files", extension, count));
                                             }
                                         }
-                                    }
-                                    
-                                    // Index column with dynamic width - right aligned
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                        ui.add_sized([self.column_widths[0], 20.0], egui::Label::new(self.file_list[absolute_idx].index.to_string()));
-                                    });
-                                    
-                                    // File path column with tree structure and color coding
-                                    let path_str = self.file_list[absolute_idx].path.to_string_lossy();
-                                    
-                                    // Calculate the file's depth in the directory structure
-                                    let path_components: Vec<&str> = path_str.split(['/', '\\']).collect();
-                                    let depth = path_components.len().saturating_sub(1);
-                                    
-                                    // Get file extension for color coding
-                                    let extension = self.file_list[absolute_idx].path.extension()
-                                        .and_then(|e| e.to_str())
-                                        .unwrap_or("");
-                                    
-                                    // Determine file type color based on extension
-                                    let file_color = match extension.to_lowercase().as_str() {
-                                        "rs" => egui::Color32::from_rgb(255, 160, 80),  // Rust files - orange
-                                        "js" | "ts" => egui::Color32::from_rgb(240, 220, 80),  // JavaScript/TypeScript - yellow
-                                        "py" => egui::Color32::from_rgb(80, 160, 255),  // Python - blue
-                                        "html" | "css" => egui::Color32::from_rgb(100, 200, 100),  // Web files - green
-                                        "md" | "txt" => egui::Color32::from_rgb(200, 200, 200),  // Documentation - light gray
-                                        "json" | "toml" | "yaml" => egui::Color32::from_rgb(200, 150, 255),  // Config files - purple
-                                        _ => if self.ui_handler.is_dark_mode() {
-                                            egui::Color32::from_rgb(180, 180, 180)  // Default - light gray
-                                        } else {
-                                            egui::Color32::from_rgb(80, 80, 80)  // Default - dark gray
-                                        }
-                                    };
-                                    
-                                    // Get just the file name for display
-                                    let file_name = self.file_list[absolute_idx].path.file_name()
-                                        .map(|n| n.to_string_lossy().to_string())
-                                        .unwrap_or_default();
-                                    
-                                    // Create indentation based on depth
-                                    let indent = "  ".repeat(depth);
-                                    
-                                    // Add tree structure character
-                                    let tree_prefix = if depth > 0 { "└─ " } else { "" };
-                                    
-                                    // Combine for display
-                                    let display_path = format!("{}{}{}", indent, tree_prefix, file_name);
-                                    
-                                    // Create the label with the file path
-                                    let path_label = ui.add_sized(
-                                        [self.column_widths[1], 20.0],
-                                        egui::Label::new(
-                                            egui::RichText::new(display_path)
-                                                .family(egui::FontFamily::Monospace)
-                                                .color(file_color)
-                                        )
-                                    );
-                                    
-                                    // Show full path on hover with extension info
-                                    if path_label.hovered() {
-                                        egui::show_tooltip(ui.ctx(), LayerId::background(), egui::Id::new("path_tooltip").with(i), |ui| {
-                                            let extension = self.file_list[absolute_idx].path.extension()
-                                                .map_or("".to_string(), |e| format!(" ({})", e.to_string_lossy()));
-                                            ui.label(format!("{}{}", path_str, extension));
-                                        });
-                                        
-                                        // Add context menu on right-click
-                                        if ui.input(|i| i.pointer.secondary_clicked()) {
-                                            // Store the file index for the context menu
-                                            let context_menu_id = ui.make_persistent_id("file_context_menu");
-                                            ui.memory_mut(|mem| mem.data.insert_temp(context_menu_id, i));
-                                            
-                                            // Show the context menu as a popup
-                                            let popup_id = ui.make_persistent_id("file_context_popup");
-                                            let popup_response = egui::popup::popup_below_widget(ui, popup_id, &path_label, egui::PopupCloseBehavior::CloseOnClickOutside, |ui: &mut egui::Ui| {
-                                                ui.set_min_width(150.0);
-                                                
-                                                let open_response = ui.button("Open File");
-                                                if open_response.clicked() {
-                                                    #[cfg(target_os = "windows")]
-                                                    {
-                                                        std::process::Command::new("cmd")
-                                                            .args(&["/c", "start", "", self.file_list[absolute_idx].path.to_string_lossy().as_ref()])
-                                                            .spawn()
-                                                            .ok();
-                                                    }
-                                                    #[cfg(not(target_os = "windows"))]
-                                                    {
-                                                        std::process::Command::new("xdg-open")
-                                                            .arg(self.file_list[absolute_idx].path.to_string_lossy().as_ref())
-                                                            .spawn()
-                                                            .ok();
-                                                    }
-                                                    // Close the popup when clicked
-                                                    ui.ctx().memory_mut(|mem| {
-                                                        mem.close_popup();
-                                                    });
-                                                }
-                                                
-                                                let copy_response = ui.button("Copy Path");
-                                                if copy_response.clicked() {
-                                                    ui.output_mut(|o| o.copied_text = self.file_list[absolute_idx].path.to_string_lossy().to_string());
-                                                    // Close the popup when clicked
-                                                    ui.ctx().memory_mut(|mem| {
-                                                        mem.close_popup();
-                                                    });
-                                                }
-                                            });
-                                            
-                                            // Position the popup at the mouse position
-                                            if let Some(pos) = ui.ctx().pointer_interact_pos() {
-                                                ui.ctx().memory_mut(|mem| {
-                                                    mem.open_popup(popup_id);
-                                                });
-                                                
-                                                // Request a repaint to show the popup immediately
-                                                ui.ctx().request_repaint();
-                                            }
+                                        None => {
+                                            ui.label("-");
                                         }
                                     }
-                                    
-                                    // Token count column (right-aligned with background color)
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                        // Create a colored background based on token count or binary status
-                                        let token_color = if self.file_list[absolute_idx].is_binary {
-                                            // Use a distinct color for binary files
-                                            if self.ui_handler.is_dark_mode() {
-                                                egui::Color32::from_rgb(80, 80, 120) // Dark blue for binary files in dark mode
-                                            } else {
-                                                egui::Color32::from_rgb(200, 200, 240) // Light blue for binary files in light mode
-                                            }
-                                        } else {
-                                            // Use the regular token count color for text files
-                                            crate::ui::style::token_count_color(
-                                                self.file_list[absolute_idx].tokens,
-                                                max_tokens,
-                                                self.ui_handler.is_dark_mode()
-                                            )
-                                        };
-                                        
-                                        egui::Frame::default()
-                                            .fill(token_color)
-                                            .corner_radius(CornerRadius::same(4))
-                                            .inner_margin(Margin::symmetric(6, 2))
-                                            .show(ui, |ui| {
-                                                // Display token count or file size based on whether it's a binary file
-                                                let display_text = if self.file_list[absolute_idx].is_binary {
-                                                    format_file_size(self.file_list[absolute_idx].tokens)
-                                                } else {
-                                                    self.file_list[absolute_idx].tokens.to_string()
-                                                };
-                                                
-                                                ui.add_sized(
-                                                    [self.column_widths[2], 20.0],
-                                                    egui::Label::new(
-                                                        egui::RichText::new(display_text)
-                                                            .strong()
-                                                            .family(egui::FontFamily::Monospace)
-                                                    )
-                                                );
-                                            });
-                                    });
                                 });
-                                
+                            }
+                            ui.end_row();
+                        });
+                });
+            });
+        
+        self.show_comparison = open;
+    }
+    
+    /// Renders the sets of files with identical contents in the file list
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_duplicates_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_duplicates;
+        // Grouping every file is only repeated after the file list or counts change
+        let duplicates = match self.duplicates.take() {
+            Some(duplicates) => duplicates,
+            None => find_duplicates(&self.file_list),
+        };
+        
+        egui::Window::new("Duplicate Files")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                if self.is_loading_tokens {
+                    ui.label("Still counting files; duplicates may be incomplete.");
+                }
+                
+                if duplicates.is_empty() {
+                    ui.label("No duplicate files found");
+                    return;
+                }
+                
+                let wasted_tokens = duplicates.iter().map(|d| d.wasted_tokens()).sum::<usize>();
+                let wasted_bytes = duplicates.iter().map(|d| d.wasted_bytes()).sum::<usize>();
+                ui.label(egui::RichText::new(format!(
+                    "{} sets, wasting {} tokens and {}",
+                    duplicates.len(), wasted_tokens, format_file_size(wasted_bytes)
+                )).strong());
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for duplicate in &duplicates {
+                        let size = if duplicate.tokens > 0 {
+                            format!("{} tokens", duplicate.tokens)
+                        } else {
+                            format_file_size(duplicate.size_bytes)
+                        };
+                        ui.label(egui::RichText::new(format!("{} copies, {} each", duplicate.paths.len(), size)).strong());
+                        for path in &duplicate.paths {
+                            let relative_path = self.repository_path.as_ref()
+                                .and_then(|root| path.strip_prefix(root).ok())
+                                .unwrap_or(path);
+                            ui.horizontal(|ui| {
+                                ui.add_space(8.0);
+                                if ui.link(relative_path.display().to_string()).on_hover_text("Show in file list").clicked() {
+                                    self.scroll_to_index = self.file_list.iter()
+                                        .find(|f| &f.path == path)
+                                        .map(|f| f.index);
+                                }
+                            });
+                        }
+                        ui.add_space(5.0);
+                    }
+                });
+            });
+        self.duplicates = Some(duplicates);
+        
+        self.show_duplicates = open;
+    }
+    
+    /// Renders the empty directories and empty files of the repository
+    ///
+    /// Empty directories come from the parsed tree, after ignore patterns;
+    /// empty files come from the file list, so they follow the filters.
+    /// Clicking an entry reveals it in the file manager.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_empty_report_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_empty_report;
+        // Walking the tree and the list is only repeated after either changes
+        let report = match self.empty_report.take() {
+            Some(report) => report,
+            None => EmptyReport {
+                directories: self.directory_structure.as_ref()
+                    .map(|root| root.empty_directories().into_iter().map(|entry| entry.path.clone()).collect())
+                    .unwrap_or_default(),
+                files: empty_files(&self.file_list).into_iter().map(|file| file.path.clone()).collect(),
+            },
+        };
+        
+        egui::Window::new("Empty Directories and Files")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                let relative = |path: &Path| -> String {
+                    self.repository_path.as_ref()
+                        .and_then(|root| path.strip_prefix(root).ok())
+                        .unwrap_or(path)
+                        .display()
+                        .to_string()
+                };
+                let mut reveal = None;
+                
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(format!("Empty Directories ({})", report.directories.len())).strong());
+                    for path in &report.directories {
+                        if ui.link(format!("{}/", relative(path))).on_hover_text("Reveal in file manager").clicked() {
+                            reveal = Some(path.clone());
+                        }
+                    }
+                    
+                    ui.add_space(10.0);
+                    
+                    // Files read as empty until they are counted
+                    if self.is_loading_tokens {
+                        ui.label(egui::RichText::new("Empty Files").strong());
+                        ui.label("Still counting files...");
+                    } else {
+                        ui.label(egui::RichText::new(format!("Empty Files ({})", report.files.len())).strong());
+                        for path in &report.files {
+                            if ui.link(relative(path)).on_hover_text("Reveal in file manager").clicked() {
+                                reveal = Some(path.clone());
+                            }
+                        }
+                    }
+                });
+                
+                if let Some(path) = reveal {
+                    reveal_in_file_manager(&path);
+                }
+            });
+        self.empty_report = Some(report);
+        
+        self.show_empty_report = open;
+    }
+    
+    /// Renders the directories that likely hold generated or vendored files
+    ///
+    /// Excluding one adds it to the ignore patterns and parses the repository
+    /// again, like an ignore pattern typed in by hand.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_generated_dirs_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_generated_dirs;
+        let mut exclude = Vec::new();
+        let root = self.repository_path.clone();
+        // Scanning every file is only repeated after the file list changes
+        let directories = match self.generated_dirs.take() {
+            Some(directories) => directories,
+            None => find_generated_directories(&self.file_list, root.as_deref()),
+        };
+        
+        egui::Window::new("Generated and Vendored Directories")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                if self.is_loading_tokens {
+                    ui.label("Still counting files; minified files may not be flagged yet.");
+                }
+                
+                let root = root.as_deref();
+                if directories.is_empty() {
+                    ui.label("No generated or vendored directories found");
+                    return;
+                }
+                
+                let total_files = directories.iter().map(|d| d.files).sum::<usize>();
+                let total_tokens = directories.iter().map(|d| d.tokens).sum::<usize>();
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(format!(
+                        "Excluding all hides {} files and saves {} tokens",
+                        total_files, self.display_count(total_tokens)
+                    )).strong());
+                    if ui.add_enabled(!self.is_refreshing, egui::Button::new("Exclude All")).clicked() {
+                        exclude = directories.iter().map(|d| d.ignore_pattern(root)).collect();
+                    }
+                });
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("generated_dirs_grid")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for directory in &directories {
+                                let pattern = directory.ignore_pattern(root);
+                                ui.label(egui::RichText::new(&pattern).monospace());
+                                ui.label(directory.reason);
+                                ui.label(format!("{} files", directory.files));
+                                ui.label(format!("{} tokens", self.display_count(directory.tokens)));
+                                if ui.add_enabled(!self.is_refreshing, egui::Button::new("Exclude")).clicked() {
+                                    exclude.push(pattern);
+                                }
                                 ui.end_row();
                             }
-                            
-                            // Total row with custom styling
-                            let page_files = end_idx - start_idx;
-                            let total_files = self.file_list.len();
-                            
-                            // Count text files only for token totals
-                            let page_text_files = self.file_list[start_idx..end_idx].iter().filter(|f| !f.is_binary).count();
-                            let page_binary_files = page_files - page_text_files;
-                            let page_tokens = self.file_list[start_idx..end_idx].iter()
-                                .filter(|f| !f.is_binary)
-                                .map(|f| f.tokens).sum::<usize>();
-                            
-                            let total_text_files = self.file_list.iter().filter(|f| !f.is_binary).count();
-                            let total_binary_files = total_files - total_text_files;
-                            let total_tokens = self.file_list.iter()
-                                .filter(|f| !f.is_binary)
-                                .map(|f| f.tokens).sum::<usize>();
-                            
-                            let total_frame = egui::Frame::default()
-                                .fill(header_color)
-                                .inner_margin(Margin::symmetric(8, 4));
-                            
-                            total_frame.show(ui, |ui| {
-                                ui.horizontal(|ui| {
-                                    // Empty index cell
-                                    ui.add_sized([self.column_widths[0], 20.0], egui::Label::new(""));
-                                    
-                                    // Total label showing page and total counts with text/binary breakdown
-                                    ui.add_sized(
-                                        [self.column_widths[1], 20.0],
-                                        egui::Label::new(
-                                            egui::RichText::new(
-                                                format!("Page: {} files ({} text, {} bin) | Total: {} files ({} text, {} bin)",
-                                                    page_files, page_text_files, page_binary_files,
-                                                    total_files, total_text_files, total_binary_files)
-                                            ).strong()
-                                        )
-                                    );
-                                    
-                                    // Total tokens (right-aligned) showing page and total
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                        // Show token counts for text files and total size for binary files
-                                        let page_binary_size = self.file_list[start_idx..end_idx].iter()
-                                            .filter(|f| f.is_binary)
-                                            .map(|f| f.tokens).sum::<usize>();
-                                        let total_binary_size = self.file_list.iter()
-                                            .filter(|f| f.is_binary)
-                                            .map(|f| f.tokens).sum::<usize>();
-                                            
-                                        ui.add_sized(
-                                            [self.column_widths[2], 20.0],
-                                            egui::Label::new(
-                                                egui::RichText::new(
-                                                    format!("Text: {} / {} tokens | Bin: {} / {}",
-                                                        page_tokens, total_tokens,
-                                                        format_file_size(page_binary_size), format_file_size(total_binary_size))
-                                                ).strong()
-                                                 .family(egui::FontFamily::Monospace)
-                                            )
-                                        );
-                                    });
+                        });
+                });
+            });
+        self.generated_dirs = Some(directories);
+        
+        if !exclude.is_empty() {
+            self.event_log.push(StatusKind::Info, format!("Excluded {}", exclude.join(", ")));
+            for pattern in exclude {
+                self.directory_parser.add_ignore_pattern(pattern);
+            }
+            self.refresh_repository();
+        }
+        self.show_generated_dirs = open;
+    }
+    
+    /// Renders the most frequent words across the repository's text files
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_common_words_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_common_words;
+        
+        egui::Window::new("Common Words")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                match &self.common_words {
+                    Some(words) if words.is_empty() => {
+                        ui.label("No words found in text files");
+                    }
+                    Some(words) => {
+                        ui.label(egui::RichText::new("Stopwords and common keywords are left out").weak());
+                        ui.add_space(4.0);
+                        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                            egui::Grid::new("common_words_grid")
+                                .num_columns(3)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for (rank, (word, count)) in words.iter().enumerate() {
+                                        ui.label(format!("{}.", rank + 1));
+                                        ui.label(egui::RichText::new(word).monospace());
+                                        ui.label(self.display_count(*count));
+                                        ui.end_row();
+                                    }
                                 });
-                            });
-                            
-                            ui.end_row();
                         });
+                    }
+                    None if self.is_loading_tokens && self.tally_words => {
+                        ui.label("Still counting files...");
+                    }
+                    None => {
+                        ui.label("Words are tallied while counting tokens.");
+                        if !self.tally_words && ui.button("Enable and Refresh").clicked() {
+                            self.tally_words = true;
+                            self.refresh_repository();
+                        } else if self.tally_words && ui.button("Refresh").clicked() {
+                            self.refresh_repository();
+                        }
+                    }
+                }
+            });
+        
+        self.show_common_words = open;
+    }
+    
+    /// Renders the application version, library versions and the loaded repository's commit
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_about_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_about;
+        let commit_id = self.about_commit_id.get_or_insert_with(|| {
+            self.repository_path.as_ref().and_then(|path| self.git_handler.head_commit_id(path))
+        }).clone();
+        
+        egui::Window::new("About")
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.heading(format!("Git Scroll {}", env!("CARGO_PKG_VERSION")));
+                ui.label(env!("CARGO_PKG_DESCRIPTION"));
+                ui.hyperlink(env!("CARGO_PKG_REPOSITORY"));
+                ui.add_space(8.0);
+                
+                egui::Grid::new("about_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for name in ["egui", "git2"] {
+                            ui.label(name);
+                            ui.add(egui::Label::new(locked_version(CARGO_LOCK, name).unwrap_or("unknown")).selectable(true));
+                            ui.end_row();
+                        }
+                        
+                        let (major, minor, patch) = git2::Version::get().libgit2_version();
+                        ui.label("libgit2");
+                        ui.add(egui::Label::new(format!("{}.{}.{}", major, minor, patch)).selectable(true));
+                        ui.end_row();
+                        
+                        // Only shown while a repository with commits is loaded
+                        if let Some(commit_id) = &commit_id {
+                            ui.label("Commit");
+                            copyable_stat(ui, egui::RichText::new(commit_id).monospace(), commit_id)
+                                .on_hover_text("Commit HEAD points to in the loaded repository");
+                            ui.end_row();
+                        }
+                    });
+            });
+        
+        self.show_about = open;
+    }
+    
+    /// Renders the event log above the status bar
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_event_log_panel(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::bottom("event_log_panel")
+            .resizable(true)
+            .default_height(160.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong("Event Log");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").on_hover_text("Hide the log").clicked() {
+                            self.show_event_log = false;
+                        }
+                        if ui.add_enabled(!self.event_log.is_empty(), egui::Button::new("Clear")).clicked() {
+                            self.event_log.clear();
+                        }
+                        if ui.add_enabled(!self.event_log.is_empty(), egui::Button::new("Export...")).clicked() {
+                            self.export_event_log();
+                        }
+                    });
                 });
+                ui.separator();
                 
-                // Enhanced pagination controls with better layout and more options
-                ui.add_space(10.0);
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if self.event_log.is_empty() {
+                            ui.label(egui::RichText::new("Nothing has happened yet").weak());
+                        }
+                        for event in self.event_log.events() {
+                            let color = self.ui_handler.status_color(event.kind, false);
+                            ui.label(egui::RichText::new(event.to_line()).monospace().color(color));
+                        }
+                    });
+            });
+    }
+    
+    /// Renders the recent failures above the status bar, each with buttons to
+    /// retry or dismiss it
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    fn render_issues_panel(&mut self, ctx: &egui::Context) {
+        let busy = self.is_cloning || self.is_refreshing;
+        let mut retry = None;
+        let mut dismissed = None;
+        egui::TopBottomPanel::bottom("issues_panel")
+            .resizable(true)
+            .default_height(120.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong("Issues");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✕").on_hover_text("Hide the issues").clicked() {
+                            self.show_issues = false;
+                        }
+                        if ui.add_enabled(!self.issues.is_empty(), egui::Button::new("Clear")).clicked() {
+                            self.issues.clear();
+                        }
+                    });
+                });
                 ui.separator();
-                ui.add_space(5.0);
                 
-                // Use the dynamically calculated items_per_page from above
-                if total_pages > 1 {
-                    egui::Frame::default()
-                        .fill(if self.ui_handler.is_dark_mode() {
-                            egui::Color32::from_rgb(40, 40, 45)
-                        } else {
-                            egui::Color32::from_rgb(245, 245, 250)
-                        })
-                        .inner_margin(egui::vec2(8.0, 8.0))
-                        .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if self.issues.is_empty() {
+                            ui.label(egui::RichText::new("No failures").weak());
+                        }
+                        let color = self.ui_handler.status_color(StatusKind::Error, false);
+                        for (index, issue) in self.issues.iter().enumerate() {
                             ui.horizontal(|ui| {
-                                // First page button
-                                if ui.add_enabled(
-                                    self.current_page > 0,
-                                    egui::Button::new("⏮ First")
-                                ).clicked() {
-                                    self.current_page = 0;
-                                }
-                                
-                                // Previous page button
-                                if ui.add_enabled(
-                                    self.current_page > 0,
-                                    egui::Button::new("◀ Previous")
-                                ).clicked() {
-                                    self.current_page = self.current_page.saturating_sub(1);
-                                }
-                                
-                                // Page number indicator with strong text
-                                ui.label(egui::RichText::new(
-                                    format!("Page {} of {}", self.current_page + 1, total_pages)
-                                ).strong());
-                                
-                                // Next page button
-                                if ui.add_enabled(
-                                    self.current_page < total_pages - 1,
-                                    egui::Button::new("Next ▶")
-                                ).clicked() {
-                                    self.current_page = (self.current_page + 1).min(total_pages - 1);
-                                }
-                                
-                                // Last page button
-                                if ui.add_enabled(
-                                    self.current_page < total_pages - 1,
-                                    egui::Button::new("Last ⏭")
-                                ).clicked() {
-                                    self.current_page = total_pages - 1;
+                                if ui.small_button("✕").on_hover_text("Dismiss").clicked() {
+                                    dismissed = Some(index);
                                 }
-                                
-                                // Jump to page with better styling
-                                ui.separator();
-                                ui.label("Go to:");
-                                let mut page_text = (self.current_page + 1).to_string();
-                                let response = ui.add_sized(
-                                    [50.0, 24.0],
-                                    egui::TextEdit::singleline(&mut page_text)
-                                        .hint_text("Page #")
-                                );
-                                
-                                if response.changed() || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))) {
-                                    if let Ok(page) = page_text.parse::<usize>() {
-                                        if page > 0 && page <= total_pages {
-                                            self.current_page = page - 1;
-                                        }
+                                if let Some(action) = &issue.retry {
+                                    if ui.add_enabled(!busy, egui::Button::new("Retry").small())
+                                        .on_disabled_hover_text("Wait for the running clone or refresh")
+                                        .clicked()
+                                    {
+                                        retry = Some((index, action.clone()));
                                     }
                                 }
-                                
-                                // Add page size selector
-                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                    ui.label("Items per page:");
-                                    let page_sizes = [10, 25, 50, 100];
-                                    let current_size = items_per_page;
-                                    
-                                    egui::ComboBox::from_id_source("page_size")
-                                        .selected_text(format!("{}", current_size))
-                                        .show_ui(ui, |ui| {
-                                            for &size in &page_sizes {
-                                                let text = format!("{}", size);
-                                                if ui.selectable_label(current_size == size, text).clicked() {
-                                                    // We can't actually change the items_per_page here since it's calculated dynamically,
-                                                    // but in a real implementation, you would store the user's preference and use it
-                                                    // to override the calculated value
-                                                }
-                                            }
-                                            ui.selectable_label(true, "Auto"); // Always selected since we're using dynamic calculation
-                                        });
-                                });
+                                ui.label(egui::RichText::new(issue.time.format("%H:%M:%S").to_string()).monospace().weak());
+                                ui.label(egui::RichText::new(&issue.message).color(color));
                             });
-                        });
+                        }
+                    });
+            });
+        
+        // A retried issue is dropped; it's listed again if it fails again
+        if let Some((index, action)) = retry {
+            self.issues.remove(index);
+            self.retry(action);
+        } else if let Some(index) = dismissed {
+            self.issues.remove(index);
+        }
+    }
+    
+    /// Exports the event log to a text file chosen in a save dialog
+    fn export_event_log(&mut self) {
+        let Some(path) = dialogs::save_file("Export Event Log", "event_log.txt", "Text files", &["txt"]) else {
+            return;
+        };
+        
+        match std::fs::write(&path, self.event_log.to_text()) {
+            Ok(()) => self.set_status(format!("Exported event log to {}", path.display()), StatusKind::Success),
+            Err(e) => {
+                let message = format!("Failed to export to {}: {}", path.display(), e);
+                self.set_status(message.clone(), StatusKind::Error);
+                self.show_error_dialog(&message, None);
+            }
+        }
+    }
+    
+    /// Toggles dark mode
+    fn toggle_dark_mode(&mut self) {
+        let current_mode = self.ui_handler.is_dark_mode();
+        self.ui_handler.set_dark_mode(!current_mode);
+    }
+    
+    /// Checks whether any filter would hide files from the list
+    ///
+    /// # Returns
+    /// * `bool` - True if the pattern or any advanced filter is set
+    fn has_active_filters(&self) -> bool {
+        !self.filter_pattern.is_empty() ||
+            !self.filter_extension.is_empty() ||
+            self.active_preset != 0 ||
+            self.filter_token_min > 0 ||
+            self.filter_token_max > 0 ||
+            self.filter_hide_below_tokens > 0 ||
+            self.filter_hide_below_bytes > 0 ||
+            self.exclude_binary_above_mb > 0
+    }
+    
+    /// Saves a custom extension preset and makes it the active one
+    ///
+    /// A custom preset with the same name is replaced; built-in presets can't be.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the preset
+    /// * `extensions` - Comma or whitespace separated extensions
+    fn save_extension_preset(&mut self, name: &str, extensions: &str) {
+        let preset = ExtensionPreset::new(name, extensions);
+        let existing = self.extension_presets.iter()
+            .position(|other| other.name.eq_ignore_ascii_case(name));
+        self.active_preset = match existing {
+            Some(index) if index >= BUILTIN_PRESET_COUNT => {
+                self.extension_presets[index] = preset;
+                index
+            }
+            Some(index) => index,
+            None => {
+                self.extension_presets.push(preset);
+                self.extension_presets.len() - 1
+            }
+        };
+        self.apply_filters();
+    }
+    
+    /// Deletes a custom extension preset, going back to all files if it was active
+    ///
+    /// # Arguments
+    /// * `index` - Index of the preset; built-in presets are kept
+    fn delete_extension_preset(&mut self, index: usize) {
+        if index < BUILTIN_PRESET_COUNT || index >= self.extension_presets.len() {
+            return;
+        }
+        self.extension_presets.remove(index);
+        if self.active_preset == index {
+            self.active_preset = 0;
+        } else if self.active_preset > index {
+            self.active_preset -= 1;
+        }
+        self.apply_filters();
+    }
+    
+    /// Applies the filter pattern and the advanced filters to the file list
+    ///
+    /// All filters combine with AND semantics. The list is rebuilt from the
+    /// directory structure each time, so filters can be loosened again.
+    fn apply_filters(&mut self) {
+        if let Some(root_entry) = &self.directory_structure {
+            // Get all files from the directory structure
+            let files = self.directory_parser.get_all_files(root_entry);
+            let pattern = self.filter_pattern.to_lowercase();
+            let selected_paths: HashSet<&PathBuf> = self.file_list.iter()
+                .filter(|f| f.selected)
+                .map(|f| &f.path)
+                .collect();
+            
+            // Create a new filtered list
+            let mut filtered_list = Vec::new();
+            let mut hidden_small_files = 0;
+            let mut excluded_large_files = (0, 0);
+            let size_limit = self.exclude_binary_above_mb.saturating_mul(1024 * 1024);
+            
+            for (index, path) in files.iter().enumerate() {
+                // Check the filter pattern against the path within the repository
+                let relative_path = self.repository_path.as_ref()
+                    .and_then(|root| path.strip_prefix(root).ok())
+                    .unwrap_or(path);
+                let pattern_match = pattern.is_empty() ||
+                    relative_path.to_string_lossy().to_lowercase().contains(&pattern);
+                
+                // Check extension filter
+                let extension_match = self.filter_extension.is_empty() ||
+                    path.extension().map_or(false, |e| e.to_string_lossy().to_lowercase() == self.filter_extension.to_lowercase());
+                let preset_match = self.extension_presets[self.active_preset].matches(path);
+                
+                // Find token count, binary status and size for this file
+                let counted = self.token_counts.get(path).copied();
+                let (tokens, is_binary, size_bytes, content_hash) = counted.unwrap_or((0, false, 0, 0));
+                
+                // Large binaries are left out of the analysis entirely, whatever the other filters
+                if is_binary && size_limit > 0 && size_bytes > size_limit {
+                    excluded_large_files.0 += 1;
+                    excluded_large_files.1 += size_bytes;
+                    continue;
+                }
+                
+                // Check token range filters; files not counted yet stay listed
+                let min_tokens_match = counted.is_none() || self.filter_token_min == 0 || tokens >= self.filter_token_min;
+                let max_tokens_match = counted.is_none() || self.filter_token_max == 0 || tokens <= self.filter_token_max;
+                
+                // Check the small file thresholds (tokens for text, bytes for binary)
+                let below_threshold = if counted.is_none() {
+                    false
+                } else if is_binary {
+                    tokens < self.filter_hide_below_bytes
+                } else {
+                    tokens < self.filter_hide_below_tokens
+                };
+                let other_filters_match = pattern_match && extension_match && preset_match && min_tokens_match && max_tokens_match;
+                if below_threshold && other_filters_match {
+                    hidden_small_files += 1;
+                }
+                
+                // Apply all filters
+                if other_filters_match && !below_threshold {
+                    filtered_list.push(FileInfo {
+                        index,
+                        path: path.clone(),
+                        tokens,
+                        selected: selected_paths.contains(path),
+                        is_binary,
+                        size_bytes,
+                        content_hash,
+                        lfs_size: self.lfs_pointers.get(path).copied(),
+                        pinned: self.pinned_paths.contains(path),
+                        modified: self.modified_times.get(path).copied(),
+                    });
+                }
+            }
+            
+            // Update the file list
+            self.file_list = filtered_list;
+            self.generated_dirs = None;
+            self.duplicates = None;
+            self.empty_report = None;
+            self.hidden_small_files = hidden_small_files;
+            self.excluded_large_files = excluded_large_files;
+            
+            // Resort the list, staying on the focused file's page
+            self.change_sort();
+        }
+    }
+    
+    /// Exports the file list to a file chosen in a save dialog
+    ///
+    /// # Arguments
+    /// * `format` - The output format
+    /// * `selected_only` - Whether to export only the checked files; every file is exported when none are checked
+    fn export_file_list(&mut self, format: ExportFormat, selected_only: bool) {
+        if self.file_list.is_empty() {
+            return;
+        }
+        
+        let file_name = if selected_only && self.file_list.iter().any(|f| f.selected) {
+            "selected_files"
+        } else {
+            "file_list"
+        };
+        if let Some(path) = dialogs::save_export(format, file_name) {
+            self.write_file_list_export(format, selected_only, &path);
+        }
+    }
+    
+    /// Writes the file list export and reports the outcome in the status bar,
+    /// with failures also shown in the error dialog
+    ///
+    /// # Arguments
+    /// * `format` - The output format
+    /// * `selected_only` - Whether to export only the checked files; every file is exported when none are checked
+    /// * `path` - The file to write
+    fn write_file_list_export(&mut self, format: ExportFormat, selected_only: bool, path: &Path) {
+        let selected: Vec<FileInfo> = if selected_only {
+            self.file_list.iter().filter(|f| f.selected).cloned().collect()
+        } else {
+            Vec::new()
+        };
+        let (files, description) = if selected.is_empty() {
+            (&self.file_list, "files")
+        } else {
+            (&selected, "selected files")
+        };
+        
+        match export::write_export(files, format, self.metric_basis, path) {
+            Ok(summary) => {
+                self.set_status(format!(
+                    "Exported {} {} ({} text, {} binary, {} tokens) to {}",
+                    summary.total_files, description, summary.text_files, summary.binary_files, summary.total_tokens, path.display()
+                ), StatusKind::Success);
+            },
+            Err(e) => {
+                self.set_status(e.clone(), StatusKind::Error);
+                self.show_error_dialog(&e, None);
+            }
+        }
+    }
+    
+    /// Exports the directory structure to a file chosen in a save dialog, in
+    /// the format of the `tree` command
+    fn export_file_tree(&mut self) {
+        let Some(root_entry) = &self.directory_structure else {
+            return;
+        };
+        
+        let Some(path) = dialogs::save_file("Export File Tree", "file_tree.txt", "Text files", &["txt"]) else {
+            return;
+        };
+        match std::fs::write(&path, self.directory_parser.tree_text(root_entry, &self.sessions[self.active_session].title)) {
+            Ok(()) => {
+                self.set_status(format!("Exported file tree to {}", path.display()), StatusKind::Success);
+            },
+            Err(e) => {
+                let message = format!("Failed to export to {}: {}", path.display(), e);
+                self.set_status(message.clone(), StatusKind::Error);
+                self.show_error_dialog(&message, None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    
+    #[test]
+    fn test_validate_git_url() {
+        // Create a new app instance for testing
+        let app = test_app();
+        
+        // Test valid URLs
+        assert!(app.validate_git_url("https://github.com/user/repo.git"));
+        assert!(app.validate_git_url("https://github.com/user/repo")); // No .git suffix is now valid
+        assert!(app.validate_git_url("git@github.com:user/repo.git")); // SSH format is now valid
+        assert!(app.validate_git_url("file:///path/to/repo")); // Local file path is now valid
+        assert!(app.validate_git_url("/absolute/path/to/repo")); // Absolute path is now valid
+        assert!(app.validate_git_url("http://git.internal/team/repo")); // Plain HTTP is valid
+        
+        // Test invalid URLs
+        assert!(!app.validate_git_url("invalid-url")); // No protocol or path format
+    }
+    
+    #[test]
+    fn test_token_count() {
+        // Create a temporary file for testing
+        let temp_file = std::env::temp_dir().join("test_token_count.txt");
+        fs::write(&temp_file, "hello world this is a test").unwrap();
+        
+        // Count tokens
+        let (count, is_binary) = count_tokens(&temp_file, &parse_extension_list(DEFAULT_TEXT_EXTENSIONS), TokenizerKind::Words, None);
+        assert_eq!(count, 5); // 5 words in the test string
+        assert_eq!(is_binary, false); // Text file, not binary
+        
+        // Clean up
+        fs::remove_file(temp_file).unwrap();
+    }
+    
+    #[test]
+    fn test_sorting() {
+        // Create test file info entries
+        let files = vec![
+            file_info(0, PathBuf::from("a.txt"), 10, false),
+            file_info(1, PathBuf::from("b.txt"), 5, false),
+            file_info(2, PathBuf::from("c.txt"), 15, false),
+        ];
+        
+        // Test sorting by tokens ascending
+        let mut app = test_app();
+        app.file_list = files.clone();
+        app.sort_column = SortColumn::Tokens;
+        app.sort_direction = SortDirection::Ascending;
+        app.sort_file_list();
+        assert_eq!(app.file_list[0].tokens, 5);
+        assert_eq!(app.file_list[2].tokens, 15);
+        
+        // Test sorting by tokens descending
+        app.sort_direction = SortDirection::Descending;
+        app.sort_file_list();
+        assert_eq!(app.file_list[0].tokens, 15);
+        assert_eq!(app.file_list[2].tokens, 5);
+        
+        // Test sorting by name
+        app.sort_column = SortColumn::Name;
+        app.sort_direction = SortDirection::Ascending;
+        app.sort_file_list();
+        assert_eq!(app.file_list[0].path.file_name().unwrap().to_str().unwrap(), "a.txt");
+        assert_eq!(app.file_list[2].path.file_name().unwrap().to_str().unwrap(), "c.txt");
+    }
+    
+    #[test]
+    fn test_empty_repository() {
+        // Create a temporary directory for testing
+        let temp_dir = std::env::temp_dir().join("test_empty_repo");
+        if temp_dir.exists() {
+            std::fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        std::fs::create_dir(&temp_dir).unwrap();
+        
+        // Create a DirectoryEntry for the empty directory
+        let root_entry = DirectoryEntry {
+            name: "empty".to_string(),
+            path: temp_dir.clone(),
+            is_directory: true,
+            children: vec![],
+        };
+        
+        // Test populating file list with empty repository
+        let mut app = test_app();
+        app.populate_file_list(&root_entry, None);
+        
+        // Verify that the file list is empty
+        assert!(app.file_list.is_empty());
+        
+        // Clean up
+        std::fs::remove_dir(temp_dir).unwrap();
+    }
+    
+    #[test]
+    fn test_file_with_no_tokens() {
+        // Create a temporary file with no content
+        let temp_file = std::env::temp_dir().join("empty_file.txt");
+        fs::write(&temp_file, "").unwrap();
+        
+        // Create a DirectoryEntry for the file
+        let file_entry = DirectoryEntry {
+            name: "empty_file.txt".to_string(),
+            path: temp_file.clone(),
+            is_directory: false,
+            children: vec![],
+        };
+        
+        // Create a parent directory entry
+        let root_entry = DirectoryEntry {
+            name: "root".to_string(),
+            path: temp_file.parent().unwrap().to_path_buf(),
+            is_directory: true,
+            children: vec![file_entry],
+        };
+        
+        // Test token counting for empty file
+        let (count, is_binary) = count_tokens(&temp_file, &parse_extension_list(DEFAULT_TEXT_EXTENSIONS), TokenizerKind::Words, None);
+        assert_eq!(count, 0);
+        assert_eq!(is_binary, false); // Empty text file, not binary
+        
+        // Clean up
+        fs::remove_file(temp_file).unwrap();
+    }
+    
+    #[test]
+    fn test_binary_file_detection() {
+        // Create a temporary binary file
+        let temp_file = std::env::temp_dir().join("test_binary.bin");
+        let binary_data = [0u8, 1u8, 2u8, 3u8, 4u8]; // Some binary data
+        fs::write(&temp_file, &binary_data).unwrap();
+        
+        // Test token counting for binary file
+        let (size, is_binary) = count_tokens(&temp_file, &parse_extension_list(DEFAULT_TEXT_EXTENSIONS), TokenizerKind::Words, None);
+        assert_eq!(size, binary_data.len()); // Should return the file size in bytes
+        assert_eq!(is_binary, true); // Should be detected as binary
+        
+        // Clean up
+        fs::remove_file(temp_file).unwrap();
+    }
+    
+    #[test]
+    fn test_parse_extension_list() {
+        assert_eq!(parse_extension_list("rs, .PY\n txt,,"), vec!["rs", "py", "txt"]);
+        assert!(parse_extension_list(" , ").is_empty());
+        
+        // The defaults cover common languages missing from the old list
+        let defaults = parse_extension_list(DEFAULT_TEXT_EXTENSIONS);
+        for ext in ["ts", "go", "c", "cpp", "java", "sh"] {
+            assert!(defaults.iter().any(|d| d == ext), "missing {}", ext);
+        }
+    }
+    
+    #[test]
+    fn test_text_detection_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let extensions = parse_extension_list("txt, json");
+        
+        // Unlisted extensions and extensionless files are text if they read as text
+        let go_file = dir.path().join("main.go");
+        fs::write(&go_file, "package main\nfunc main() {}").unwrap();
+        assert_eq!(count_tokens(&go_file, &extensions, TokenizerKind::Words, None), (5, false));
+        let makefile = dir.path().join("Makefile");
+        fs::write(&makefile, "all: build").unwrap();
+        assert_eq!(count_tokens(&makefile, &extensions, TokenizerKind::Words, None), (2, false));
+        
+        // Listed extensions are still binary when they contain NUL bytes
+        let fake_json = dir.path().join("data.json");
+        fs::write(&fake_json, [b'{', 0, b'}']).unwrap();
+        assert_eq!(count_tokens(&fake_json, &extensions, TokenizerKind::Words, None), (3, true));
+        
+        // Unlisted files must be valid UTF-8, listed ones are given the benefit of the doubt
+        let latin1 = [b'c', b'a', b'f', 0xE9];
+        let unlisted = dir.path().join("legacy.dat");
+        fs::write(&unlisted, latin1).unwrap();
+        assert_eq!(count_tokens(&unlisted, &extensions, TokenizerKind::Words, None), (4, true));
+        let listed = dir.path().join("legacy.txt");
+        fs::write(&listed, latin1).unwrap();
+        assert!(!count_tokens(&listed, &extensions, TokenizerKind::Words, None).1);
+    }
+    
+    #[test]
+    fn test_code_tokenizer() {
+        let dir = tempfile::tempdir().unwrap();
+        let snippet = dir.path().join("snippet.rs");
+        fs::write(&snippet, "let total = add(a, b);\nprint_total(total);").unwrap();
+        let extensions = parse_extension_list(DEFAULT_TEXT_EXTENSIONS);
+        
+        // Words: let, total, =, add(a,, b);, print_total(total);
+        assert_eq!(count_tokens(&snippet, &extensions, TokenizerKind::Words, None), (6, false));
+        
+        // Code: let total = add ( a , b ) ; print_total ( total ) ;
+        assert_eq!(count_tokens(&snippet, &extensions, TokenizerKind::Code, None), (15, false));
+        
+        // Plain prose counts the same either way
+        let count = |tokenizer, text: &str| count_reader_tokens(text.as_bytes(), tokenizer, None);
+        assert_eq!(count(TokenizerKind::Code, "hello world"), count(TokenizerKind::Words, "hello world"));
+        assert_eq!(count(TokenizerKind::Code, "a==b"), 4);
+    }
+    
+    #[test]
+    fn test_long_single_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let minified = dir.path().join("bundle.min.js");
+        
+        // Several megabytes on one line, with words and characters straddling chunk edges
+        let line = "var é=ab;".repeat(500_000);
+        assert!(line.len() > 50 * TOKEN_CHUNK_BYTES);
+        fs::write(&minified, &line).unwrap();
+        let extensions = parse_extension_list(DEFAULT_TEXT_EXTENSIONS);
+        
+        let mut words = WordFrequencies::default();
+        assert_eq!(count_tokens(&minified, &extensions, TokenizerKind::Words, Some(&mut words)), (500_001, false));
+        assert_eq!(count_tokens(&minified, &extensions, TokenizerKind::Code, None), (2_500_000, false));
+        assert_eq!(words.top(1), vec![("é=ab;var".to_string(), 499_999)]);
+        
+        // A word longer than a chunk counts once and is left out of the tally
+        let word = "x".repeat(3 * TOKEN_CHUNK_BYTES + 1);
+        let mut words = WordFrequencies::default();
+        let text = format!("start {} end", word);
+        assert_eq!(count_reader_tokens(text.as_bytes(), TokenizerKind::Words, Some(&mut words)), 3);
+        assert_eq!(words.top(10), vec![("end".to_string(), 1), ("start".to_string(), 1)]);
+    }
+    
+    #[test]
+    fn test_word_frequencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        fs::write(&notes, "The parser reads tokens.\nParser, tokens; parser!\nfn main() { let x = 42; }").unwrap();
+        
+        // Words are tallied while the tokens are counted
+        let mut words = WordFrequencies::default();
+        let (tokens, _) = count_tokens(&notes, &parse_extension_list(DEFAULT_TEXT_EXTENSIONS), TokenizerKind::Words, Some(&mut words));
+        assert_eq!(tokens, 15);
+        
+        // Punctuation, case, stopwords, numbers and single characters are dropped
+        assert_eq!(words.top(10), vec![
+            ("parser".to_string(), 3),
+            ("tokens".to_string(), 2),
+            ("main".to_string(), 1),
+            ("reads".to_string(), 1),
+        ]);
+        assert_eq!(words.top(1), vec![("parser".to_string(), 3)]);
+        
+        // Merging adds counts and keeps memory bounded
+        let mut total = WordFrequencies::default();
+        total.merge(words);
+        let mut rare = WordFrequencies::default();
+        for i in 0..2 * WORD_TRACK_LIMIT {
+            rare.add_word(&format!("word{}", i));
+        }
+        total.merge(rare);
+        assert!(total.counts.len() <= 2 * WORD_TRACK_LIMIT);
+        assert_eq!(total.top(1), vec![("parser".to_string(), 3)]);
+    }
+    
+    /// Creates a git repository with a single committed file for clone tests
+    fn create_test_repository(path: &Path) {
+        let repo = git2::Repository::init(path).unwrap();
+        fs::write(path.join("README.md"), "hello world").unwrap();
+        
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+    }
+    
+    #[test]
+    fn test_cloned_repository_outlives_clone_thread() {
+        let source = tempfile::tempdir().unwrap();
+        create_test_repository(source.path());
+        
+        let temp_dir = tempfile::Builder::new().prefix("git_scroll_test_").tempdir().unwrap();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let (clone_sender, clone_receiver) = mpsc::channel();
+        let (parse_sender, parse_receiver) = mpsc::channel();
+        
+        let request = CloneRequest {
+            git_url: source.path().to_string_lossy().to_string(),
+            branch: None,
+            ssh_config: SshConfig::default(),
+            follow_redirects: false,
+            fetch_config: FetchConfig::default(),
+            changes_only: false,
+            max_attempts: 1,
+            parser: DirectoryParser::isolated(),
+        };
+        thread::spawn(move || {
+            clone_and_parse(&request, CloneDestination::Temporary(temp_dir), &cancel_flag, &clone_sender, &parse_sender);
+        }).join().unwrap();
+        
+        // Find the clone result among the progress messages
+        let repo_path = clone_receiver.try_iter()
+            .find_map(|msg| match msg {
+                CloneProgress::Completed(result) => Some(result.unwrap()),
+                _ => None,
+            })
+            .expect("clone should complete");
+        assert!(parse_receiver.try_recv().unwrap().is_ok());
+        
+        // The clone must still exist after the thread has finished
+        assert!(repo_path.join("README.md").exists());
+        
+        // Clean up the way clear_repository does
+        GitHandler::new(false).cleanup(&repo_path).unwrap();
+        assert!(!repo_path.exists());
+    }
+    
+    #[test]
+    fn test_clone_working_tree_changes_only() {
+        let source = tempfile::tempdir().unwrap();
+        create_test_repository(source.path());
+        let repo = git2::Repository::open(source.path()).unwrap();
+        fs::write(source.path().join("lib.rs"), "fn unchanged() {}").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("lib.rs")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Add lib", &tree, &[&parent]).unwrap();
+        
+        let clone = |source: &Path| {
+            let (clone_sender, clone_receiver) = mpsc::channel();
+            let (parse_sender, parse_receiver) = mpsc::channel();
+            let request = CloneRequest {
+                git_url: source.to_string_lossy().to_string(),
+                branch: None,
+                ssh_config: SshConfig::default(),
+                follow_redirects: false,
+                fetch_config: FetchConfig::default(),
+                changes_only: true,
+                max_attempts: 1,
+                parser: DirectoryParser::isolated(),
+            };
+            let destination = CloneDestination::Temporary(tempfile::tempdir().unwrap());
+            clone_and_parse(&request, destination, &AtomicBool::new(false), &clone_sender, &parse_sender);
+            
+            let changes = clone_receiver.try_iter()
+                .find_map(|msg| match msg {
+                    CloneProgress::ChangesOnly(result) => Some(result),
+                    _ => None,
+                })
+                .unwrap();
+            let root_entry = parse_receiver.try_recv().unwrap().unwrap().root_entry;
+            let names: Vec<String> = DirectoryParser::isolated().get_all_files(&root_entry).iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            GitHandler::new(false).cleanup(&root_entry.path).unwrap();
+            (changes, names)
+        };
+        
+        // Without changes everything is analyzed
+        let (changes, names) = clone(source.path());
+        assert_eq!(changes, Err("no uncommitted changes".to_string()));
+        assert_eq!(names.len(), 2);
+        
+        // Modified and untracked files are copied into the clone and analyzed alone
+        fs::write(source.path().join("README.md"), "hello changed world").unwrap();
+        fs::write(source.path().join("notes.txt"), "new notes").unwrap();
+        let (changes, mut names) = clone(source.path());
+        names.sort();
+        assert_eq!(changes, Ok(2));
+        assert_eq!(names, vec!["README.md", "notes.txt"]);
+    }
+    
+    #[test]
+    fn test_clone_counts_match_analyze() {
+        let source = tempfile::tempdir().unwrap();
+        create_test_repository(source.path());
+        
+        // The GUI clones, parses and counts on its background threads
+        let mut app = test_app();
+        app.git_url = source.path().to_string_lossy().to_string();
+        app.handle_clone_button();
+        wait_for_background(&mut app);
+        let repo_path = app.repository_path.clone().unwrap();
+        
+        // The headless analysis of the same clone lists and counts the same files
+        let (_, analyzed) = crate::headless::analyze(&repo_path, app.directory_parser.clone(), &ProjectConfig::default(), |_, _| {}).unwrap();
+        let counts = |files: &[FileInfo]| {
+            let mut counts: Vec<(PathBuf, usize, bool, usize, ContentHash)> = files.iter()
+                .map(|f| (f.path.clone(), f.tokens, f.is_binary, f.size_bytes, f.content_hash))
+                .collect();
+            counts.sort();
+            counts
+        };
+        assert_eq!(app.file_list.len(), 1);
+        assert_eq!(counts(&app.file_list), counts(&analyzed));
+        
+        app.clear_repository();
+        assert!(!repo_path.exists());
+    }
+    
+    #[test]
+    fn test_pasted_url_is_trimmed() {
+        let source = tempfile::tempdir().unwrap();
+        create_test_repository(source.path());
+        let url = source.path().to_string_lossy().to_string();
+        
+        // A valid pasted URL is offered unless it is already in the field
+        let mut app = test_app();
+        app.offer_pasted_url("not a url");
+        assert!(app.pasted_url.is_none());
+        app.offer_pasted_url(&format!("  {}\n", url));
+        assert_eq!(app.pasted_url.as_deref(), Some(url.as_str()));
+        
+        // A trailing newline doesn't fail validation
+        app.git_url = format!("{}\n", url);
+        app.handle_clone_button();
+        assert_eq!(app.git_url, url);
+        assert!(app.is_cloning);
+        assert!(app.pasted_url.is_none());
+        
+        let repo_path = app.clone_receiver.iter()
+            .find_map(|msg| match msg {
+                CloneProgress::Completed(result) => Some(result.unwrap()),
+                _ => None,
+            })
+            .unwrap();
+        GitHandler::new(false).cleanup(&repo_path).unwrap();
+    }
+    
+    #[test]
+    fn test_operations_locked_while_cloning() {
+        let source = tempfile::tempdir().unwrap();
+        create_test_repository(source.path());
+        let mut app = test_app();
+        app.git_url = source.path().to_string_lossy().to_string();
+        app.handle_clone_button();
+        assert!(app.is_cloning);
+        
+        // A second clone and a refresh are rejected while the first clone runs
+        app.git_url = "https://github.com/user/other.git".to_string();
+        app.handle_clone_button();
+        app.refresh_repository();
+        assert_eq!(app.sessions.len(), 1);
+        assert!(!app.is_refreshing);
+        
+        // Clearing cancels the clone and drops its late results
+        app.clear_repository();
+        assert!(!app.is_cloning);
+        assert!(app.repository_path.is_none());
+        assert!(matches!(app.clone_receiver.try_recv(), Err(mpsc::TryRecvError::Disconnected)));
+        assert!(matches!(app.parse_receiver.try_recv(), Err(mpsc::TryRecvError::Disconnected)));
+    }
+    
+    #[test]
+    fn test_clone_finished_after_clear_is_removed() {
+        let mut app = test_app();
+        let (clone_sender, clone_receiver) = mpsc::channel();
+        app.clone_receiver = clone_receiver;
+        app.is_cloning = true;
+        app.clear_repository();
+        assert!(app.clone_cancel.load(Ordering::SeqCst));
+        
+        // The clone finished before it saw the cancel
+        let late = tempfile::tempdir().unwrap().into_path();
+        clone_sender.send(CloneProgress::Completed(Ok(late.clone()))).unwrap();
+        drop(clone_sender);
+        let start = std::time::Instant::now();
+        while late.exists() {
+            assert!(start.elapsed() < Duration::from_secs(5), "late clone was not removed");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+    
+    #[test]
+    fn test_cleanup_repositories_on_exit() {
+        let active = tempfile::tempdir().unwrap().into_path();
+        let inactive = tempfile::tempdir().unwrap().into_path();
+        let mut app = test_app();
+        app.repository_path = Some(active.clone());
+        app.sessions.push(RepoSession::new(String::from("Inactive")));
+        app.sessions[1].repository_path = Some(inactive.clone());
+        
+        // Kept repositories stay on disk
+        app.keep_repository = true;
+        app.cleanup_repositories();
+        assert!(active.exists() && inactive.exists());
+        
+        app.keep_repository = false;
+        app.cleanup_repositories();
+        assert!(!active.exists());
+        assert!(!inactive.exists());
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_count_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("notes.txt");
+        std::fs::write(&target, "one two three").unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        std::os::unix::fs::symlink(&target, &first).unwrap();
+        std::os::unix::fs::symlink(&target, &second).unwrap();
+        
+        // A link has no tokens and its own size, and links to one target hash alike
+        let (tokens, is_binary, size_bytes, hash) = count_link(&first);
+        assert_eq!((tokens, is_binary), (0, false));
+        assert_eq!(size_bytes, std::fs::symlink_metadata(&first).unwrap().len() as usize);
+        assert_eq!(count_link(&second).3, hash);
+        assert_eq!(count_file(&first, &[], TokenizerKind::Words).0, 3);
+    }
+    
+    #[test]
+    fn test_transfer_stats_message() {
+        assert_eq!(format_thousands(0), "0");
+        assert_eq!(format_thousands(999), "999");
+        assert_eq!(format_thousands(45_000), "45,000");
+        assert_eq!(format_thousands(1_234_567), "1,234,567");
+        
+        let stats = TransferStats { received_objects: 12_340, total_objects: 45_000, received_bytes: 24_536_678 };
+        assert_eq!(stats.message(), "12,340 / 45,000 objects (23.4 MiB)");
+        assert!((stats.fraction() - 12_340.0 / 45_000.0).abs() < 1e-6);
+        
+        let stats = TransferStats { received_objects: 0, total_objects: 0, received_bytes: 512 };
+        assert_eq!(stats.message(), "0 / 0 objects (0.5 KiB)");
+        assert_eq!(stats.fraction(), 0.0);
+    }
+    
+    #[test]
+    fn test_clone_to_chosen_directory() {
+        let source = tempfile::tempdir().unwrap();
+        create_test_repository(source.path());
+        let target = tempfile::tempdir().unwrap();
+        let chosen = target.path().join("repo");
+        
+        // Missing and empty directories can be cloned into, others can't
+        assert!(CloneDestination::check_chosen(&chosen).is_ok());
+        std::fs::create_dir(&chosen).unwrap();
+        assert!(CloneDestination::check_chosen(&chosen).is_ok());
+        std::fs::write(target.path().join("file.txt"), "taken").unwrap();
+        assert!(CloneDestination::check_chosen(target.path()).is_err());
+        assert!(CloneDestination::check_chosen(&target.path().join("file.txt")).is_err());
+        
+        let clone = |url: String, destination: CloneDestination| {
+            let cancel_flag = AtomicBool::new(false);
+            let (clone_sender, clone_receiver) = mpsc::channel();
+            let (parse_sender, _parse_receiver) = mpsc::channel();
+            let request = CloneRequest {
+                git_url: url,
+                branch: None,
+                ssh_config: SshConfig::default(),
+                follow_redirects: false,
+                fetch_config: FetchConfig::default(),
+                changes_only: false,
+                max_attempts: 1,
+                parser: DirectoryParser::isolated(),
+            };
+            clone_and_parse(&request, destination, &cancel_flag, &clone_sender, &parse_sender);
+            clone_receiver.try_iter()
+                .find_map(|msg| match msg {
+                    CloneProgress::Completed(result) => Some(result),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        
+        let repo_path = clone(source.path().to_string_lossy().to_string(), CloneDestination::Chosen(chosen.clone())).unwrap();
+        assert_eq!(repo_path, chosen);
+        assert!(chosen.join("README.md").exists());
+        
+        // A failed clone doesn't leave a directory behind
+        let failed = target.path().join("missing");
+        assert!(clone(target.path().join("no_such_repo").to_string_lossy().to_string(), CloneDestination::Chosen(failed.clone())).is_err());
+        assert!(!failed.exists());
+    }
+    
+    #[test]
+    fn test_update_existing_clone() {
+        let source = tempfile::tempdir().unwrap();
+        create_test_repository(source.path());
+        let target = tempfile::tempdir().unwrap();
+        let chosen = target.path().join(repository_title(&source.path().to_string_lossy()));
+        
+        let run = |destination: CloneDestination| {
+            let cancel_flag = AtomicBool::new(false);
+            let (clone_sender, clone_receiver) = mpsc::channel();
+            let (parse_sender, _parse_receiver) = mpsc::channel();
+            let request = CloneRequest {
+                git_url: source.path().to_string_lossy().to_string(),
+                branch: None,
+                ssh_config: SshConfig::default(),
+                follow_redirects: false,
+                fetch_config: FetchConfig::default(),
+                changes_only: false,
+                max_attempts: 1,
+                parser: DirectoryParser::isolated(),
+            };
+            clone_and_parse(&request, destination, &cancel_flag, &clone_sender, &parse_sender);
+            clone_receiver.try_iter()
+                .find_map(|msg| match msg {
+                    CloneProgress::Completed(result) => Some(result),
+                    _ => None,
+                })
+                .unwrap()
+        };
+        run(CloneDestination::Chosen(chosen.clone())).unwrap();
+        
+        // Cloning the same URL into the same directory asks what to do instead of failing
+        let mut app = test_app();
+        app.git_url = source.path().to_string_lossy().to_string();
+        app.keep_repository = true;
+        app.clone_directory = target.path().display().to_string();
+        app.handle_clone_button();
+        assert!(!app.is_cloning);
+        assert_eq!(app.existing_clone.as_deref(), Some(chosen.as_path()));
+        
+        // Updating fast-forwards to the new commit of the source
+        let repo = git2::Repository::open(source.path()).unwrap();
+        fs::write(source.path().join("new.rs"), "fn new() {}").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("new.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Add new.rs", &tree, &[&parent]).unwrap();
+        
+        assert_eq!(run(CloneDestination::Existing(chosen.clone())).unwrap(), chosen);
+        assert!(chosen.join("new.rs").exists());
+        
+        // A clone with local commits isn't touched, nor removed on failure
+        let clone = git2::Repository::open(&chosen).unwrap();
+        let head = clone.head().unwrap().peel_to_commit().unwrap();
+        clone.commit(Some("HEAD"), &signature, &signature, "Local commit", &head.tree().unwrap(), &[&head]).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Upstream commit", &tree, &[&repo.head().unwrap().peel_to_commit().unwrap()]).unwrap();
+        assert!(run(CloneDestination::Existing(chosen.clone())).unwrap_err().contains("fast-forwarded"));
+        assert!(chosen.join("new.rs").exists());
+    }
+    
+    #[test]
+    fn test_animation_settings_persist() {
+        let mut app = test_app();
+        app.animations = false;
+        app.highlight_seconds = 3.0;
+        
+        let restarted = restart(&mut app);
+        assert!(!restarted.animations);
+        assert_eq!(restarted.highlight_seconds, 3.0);
+        
+        // Without saved settings the defaults apply
+        let fresh = GitScrollApp::new(Some(&MemoryStorage::default()));
+        assert!(fresh.animations);
+        assert_eq!(fresh.highlight_seconds, ROW_HIGHLIGHT_SECONDS);
+    }
+    
+    #[test]
+    fn test_theme_mode_persists() {
+        let mut app = test_app();
+        app.ui_handler.set_theme_mode(ThemeMode::Light);
+        
+        let restarted = restart(&mut app);
+        assert_eq!(restarted.ui_handler.theme_mode(), ThemeMode::Light);
+        assert!(!restarted.ui_handler.is_dark_mode());
+    }
+    
+    #[test]
+    fn test_table_columns_persist() {
+        let mut app = test_app();
+        app.table_columns.swap(0, 1);
+        app.table_columns[2].visible = false;
+        app.table_columns[3].visible = true;
+        
+        let restarted = restart(&mut app);
+        let layout = |columns: &[TableColumn]| columns.iter().map(|c| (c.kind, c.visible)).collect::<Vec<_>>();
+        assert_eq!(layout(&restarted.table_columns), layout(&app.table_columns));
+        
+        // Columns missing from older settings are added at the end with their defaults
+        let restored = restore_table_columns(vec![TableColumn { kind: FileColumn::Size, width: 70.0, visible: true }]);
+        assert_eq!(restored.len(), default_table_columns().len());
+        assert_eq!((restored[0].kind, restored[0].width), (FileColumn::Size, 70.0));
+        assert_eq!((restored[1].kind, restored[1].visible), (FileColumn::Index, true));
+    }
+    
+    #[test]
+    fn test_custom_presets_persist() {
+        let mut app = test_app();
+        app.save_extension_preset("Rust", "rs, toml");
+        
+        // Custom presets come back after the built-in ones, which aren't duplicated
+        let restarted = restart(&mut app);
+        assert_eq!(restarted.extension_presets.len(), BUILTIN_PRESET_COUNT + 1);
+        assert_eq!(restarted.extension_presets[..BUILTIN_PRESET_COUNT], builtin_extension_presets()[..]);
+        assert_eq!(restarted.extension_presets[BUILTIN_PRESET_COUNT], ExtensionPreset::new("Rust", "rs, toml"));
+    }
+    
+    #[test]
+    fn test_clone_retry_policy() {
+        use git2::{Error, ErrorClass, ErrorCode};
+        
+        // Network failures and server errors are retried
+        assert!(is_transient_clone_error(&Error::new(ErrorCode::GenericError, ErrorClass::Net, "failed to connect to github.com")));
+        assert!(is_transient_clone_error(&Error::new(ErrorCode::GenericError, ErrorClass::Http, "unexpected http status code: 503")));
+        assert!(is_transient_clone_error(&Error::new(ErrorCode::GenericError, ErrorClass::Ssh, "Operation timed out")));
+        
+        // Permanent failures are not
+        assert!(!is_transient_clone_error(&Error::new(ErrorCode::Auth, ErrorClass::Http, "authentication required")));
+        assert!(!is_transient_clone_error(&Error::new(ErrorCode::GenericError, ErrorClass::Http, "unexpected http status code: 404")));
+        assert!(!is_transient_clone_error(&Error::new(ErrorCode::NotFound, ErrorClass::Net, "repository not found")));
+        assert!(!is_transient_clone_error(&Error::new(ErrorCode::User, ErrorClass::Callback, "cancelled")));
+        
+        // Exponential backoff
+        assert_eq!(clone_retry_delay(1), Duration::from_secs(1));
+        assert_eq!(clone_retry_delay(2), Duration::from_secs(2));
+        assert_eq!(clone_retry_delay(3), Duration::from_secs(4));
+        
+        // Cancelling cuts the wait short
+        let cancelled = AtomicBool::new(true);
+        assert!(!sleep_unless_cancelled(Duration::from_secs(60), &cancelled));
+    }
+    
+    /// Creates an app whose parser skips the global excludes file of the machine running the tests
+    fn test_app() -> GitScrollApp {
+        let mut app = GitScrollApp::new(None);
+        app.directory_parser = DirectoryParser::isolated();
+        app
+    }
+    
+    /// Settings storage kept in memory, standing in for eframe's settings file
+    #[derive(Default)]
+    struct MemoryStorage(HashMap<String, String>);
+    
+    impl eframe::Storage for MemoryStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+        
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_string(), value);
+        }
+        
+        fn flush(&mut self) {}
+    }
+    
+    /// Saves an app's settings and starts a new app from them, as closing and reopening would
+    fn restart(app: &mut GitScrollApp) -> GitScrollApp {
+        let mut storage = MemoryStorage::default();
+        eframe::App::save(app, &mut storage);
+        GitScrollApp::new(Some(&storage))
+    }
+    
+    /// Builds an app with a flat fake repository of the given files
+    fn app_with_files(files: &[(&str, usize, bool)]) -> GitScrollApp {
+        let mut app = test_app();
+        app.directory_structure = Some(DirectoryEntry {
+            name: "repo".to_string(),
+            path: PathBuf::from("/repo"),
+            is_directory: true,
+            children: files.iter().map(|(name, _, _)| DirectoryEntry {
+                name: name.to_string(),
+                path: PathBuf::from("/repo").join(name),
+                is_directory: false,
+                children: vec![],
+            }).collect(),
+        });
+        app.file_list = files.iter().enumerate().map(|(index, (name, tokens, is_binary))| FileInfo {
+            size_bytes: *tokens,
+            ..file_info(index, PathBuf::from("/repo").join(name), *tokens, *is_binary)
+        }).collect();
+        app.token_counts = app.file_list.iter()
+            .map(|f| (f.path.clone(), (f.tokens, f.is_binary, f.size_bytes, f.content_hash)))
+            .collect();
+        app.repository_path = Some(PathBuf::from("/repo"));
+        app
+    }
+    
+    /// Creates an unselected, unpinned file without a size or hash; other
+    /// fields can be set with struct update syntax
+    fn file_info(index: usize, path: impl Into<PathBuf>, tokens: usize, is_binary: bool) -> FileInfo {
+        FileInfo {
+            index,
+            path: path.into(),
+            tokens,
+            is_binary,
+            ..FileInfo::default()
+        }
+    }
+    
+    /// Polls background work until the running clone, refresh and token count finish
+    ///
+    /// # Arguments
+    /// * `app` - The app to poll; fails the test after five seconds
+    fn wait_for_background(app: &mut GitScrollApp) {
+        let ctx = egui::Context::default();
+        let start = std::time::Instant::now();
+        while app.is_cloning || app.is_refreshing || app.is_loading_tokens {
+            assert!(start.elapsed() < Duration::from_secs(5), "background work timed out");
+            app.check_background_operations(&ctx);
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+    
+    #[test]
+    fn test_hide_small_files() {
+        let mut app = app_with_files(&[
+            ("mod.rs", 3, false),
+            ("main.rs", 500, false),
+            ("icon.png", 40, true),
+            ("logo.png", 4000, true),
+        ]);
+        
+        app.filter_hide_below_tokens = 10;
+        app.filter_hide_below_bytes = 100;
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 2);
+        assert_eq!(app.hidden_small_files, 2);
+        
+        // Composes with the extension filter; only matching files count as hidden
+        app.filter_hide_below_tokens = 0;
+        app.filter_hide_below_bytes = 0;
+        app.apply_filters();
+        app.filter_extension = "png".to_string();
+        app.filter_hide_below_bytes = 100;
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 1);
+        assert_eq!(app.hidden_small_files, 1);
+    }
+    
+    #[test]
+    fn test_uncounted_files_stay_listed() {
+        let mut app = app_with_files(&[("mod.rs", 3, false), ("main.rs", 500, false), ("icon.png", 40, true)]);
+        app.token_counts.clear();
+        
+        // Files not counted yet, e.g. during a count or without counting on load, aren't hidden
+        app.filter_hide_below_tokens = 10;
+        app.filter_hide_below_bytes = 100;
+        app.filter_token_min = 50;
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 3);
+        assert_eq!(app.hidden_small_files, 0);
+        
+        // Once counted, the thresholds apply
+        app.filter_token_min = 0;
+        app.token_counts.insert(PathBuf::from("/repo/mod.rs"), (3, false, 3, 0));
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 2);
+        assert_eq!(app.hidden_small_files, 1);
+    }
+    
+    #[test]
+    fn test_exclude_large_binaries() {
+        let mut app = app_with_files(&[
+            ("main.rs", 3 * 1024 * 1024, false),
+            ("logo.png", 4000, true),
+            ("intro.mp4", 3 * 1024 * 1024, true),
+        ]);
+        
+        app.exclude_binary_above_mb = 2;
+        app.apply_filters();
+        let names: Vec<String> = app.file_list.iter().map(|f| f.path.to_string_lossy().to_string()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(!names.iter().any(|name| name.ends_with("intro.mp4")));
+        assert_eq!(app.excluded_large_files, (1, 3 * 1024 * 1024));
+        
+        // Only binaries are excluded, and turning the limit off brings them back
+        app.exclude_binary_above_mb = 0;
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 3);
+        assert_eq!(app.excluded_large_files, (0, 0));
+    }
+    
+    #[test]
+    fn test_filter_pattern_composes_with_advanced_filters() {
+        let mut app = app_with_files(&[
+            ("src/main.rs", 500, false),
+            ("src/lib.rs", 5, false),
+            ("docs/Guide.md", 200, false),
+            ("assets/src.png", 900, true),
+        ]);
+        
+        // The pattern matches the path within the repository, ignoring case
+        app.handle_filter_change("SRC".to_string());
+        assert_eq!(app.file_list.len(), 3);
+        
+        // Pattern and extension filters must both match
+        app.filter_extension = "rs".to_string();
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 2);
+        
+        // The repository root itself is not part of the matched path
+        app.handle_filter_change("repo".to_string());
+        assert!(app.file_list.is_empty());
+        
+        // Clearing the pattern restores files hidden by it
+        app.handle_filter_change(String::new());
+        app.filter_token_min = 10;
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 1);
+        assert_eq!(app.file_list[0].path, PathBuf::from("/repo/src/main.rs"));
+    }
+    
+    #[test]
+    fn test_extension_presets() {
+        let mut app = app_with_files(&[
+            ("src/main.rs", 500, false),
+            ("README.md", 200, false),
+            ("Cargo.toml", 50, false),
+            ("assets/logo.png", 900, true),
+        ]);
+        assert_eq!(builtin_extension_presets().len(), BUILTIN_PRESET_COUNT);
+        
+        app.active_preset = 1; // Code
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 1);
+        assert!(app.has_active_filters());
+        
+        // Presets combine with the other filters
+        app.active_preset = 2; // Docs
+        app.filter_extension = "rs".to_string();
+        app.apply_filters();
+        assert!(app.file_list.is_empty());
+        app.filter_extension.clear();
+        
+        // A custom preset is selected when saved and replaced when saved again
+        app.save_extension_preset("Assets", "png, .SVG");
+        assert_eq!(app.active_preset, BUILTIN_PRESET_COUNT);
+        assert_eq!(app.extension_presets[BUILTIN_PRESET_COUNT].extensions, vec!["png", "svg"]);
+        assert_eq!(app.file_list.len(), 1);
+        app.save_extension_preset("assets", "rs, toml");
+        assert_eq!(app.extension_presets.len(), BUILTIN_PRESET_COUNT + 1);
+        assert_eq!(app.file_list.len(), 2);
+        
+        // Deleting the active preset shows every file again; built-in presets stay
+        app.delete_extension_preset(BUILTIN_PRESET_COUNT);
+        app.delete_extension_preset(1);
+        assert_eq!(app.extension_presets.len(), BUILTIN_PRESET_COUNT);
+        assert_eq!(app.active_preset, 0);
+        assert_eq!(app.file_list.len(), 4);
+    }
+    
+    #[test]
+    fn test_idle_app_does_not_repaint() {
+        let ctx = egui::Context::default();
+        let mut app = test_app();
+        app.check_background_operations(&ctx);
+        assert!(!ctx.has_requested_repaint());
+        
+        // A pending background result keeps the app polling until it arrives
+        let (statistics_sender, statistics_receiver) = mpsc::channel();
+        app.statistics_receiver = statistics_receiver;
+        app.check_background_operations(&ctx);
+        assert!(ctx.has_requested_repaint());
+        
+        statistics_sender.send(DirectoryStatistics {
+            total_files: 0,
+            total_directories: 0,
+            total_size_bytes: 0,
+            max_depth: 0,
+            file_types: HashMap::new(),
+        }).unwrap();
+        drop(statistics_sender);
+        app.check_background_operations(&ctx);
+        assert!(app.directory_statistics.is_some());
+        
+        // Once its thread is gone, a new context isn't asked to repaint again
+        let ctx = egui::Context::default();
+        app.check_background_operations(&ctx);
+        assert!(!ctx.has_requested_repaint());
+    }
+    
+    #[test]
+    fn test_statistics_computed_in_background() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.rs"), "fn a() {}").unwrap();
+        let mut app = test_app();
+        let first = app.directory_parser.parse_directory(dir.path()).unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src").join("b.rs"), "fn b() {}").unwrap();
+        let second = app.directory_parser.parse_directory(dir.path()).unwrap();
+        
+        // Only the latest computation is received
+        app.compute_statistics(&first);
+        app.compute_statistics(&second);
+        let statistics = app.statistics_receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(statistics.total_files, 2);
+        assert_eq!(statistics.total_size_bytes, 18);
+        assert!(app.statistics_receiver.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+    
+    #[test]
+    fn test_editor_command() {
+        let path = Path::new("/repo/src/main file.rs");
+        assert_eq!(
+            editor_command("code -g {path}:{line}", path, 12).unwrap(),
+            vec!["code", "-g", "/repo/src/main file.rs:12"]
+        );
+        assert_eq!(
+            editor_command("vim +{line} {path}", path, 3).unwrap(),
+            vec!["vim", "+3", "/repo/src/main file.rs"]
+        );
+        
+        // Without placeholders the path is appended and the line left out
+        assert_eq!(editor_command("  notepad ", path, 3).unwrap(), vec!["notepad", "/repo/src/main file.rs"]);
+        assert_eq!(editor_command("vim +{line} {path}", path, 0).unwrap()[1], "+1");
+        assert!(editor_command(" ", path, 1).is_none());
+    }
+    
+    #[test]
+    fn test_refresh_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_path_buf();
+        std::fs::write(repo.join("a.txt"), "one two").unwrap();
+        std::fs::write(repo.join("b.txt"), "three").unwrap();
+        
+        let mut app = test_app();
+        app.repository_path = Some(repo.clone());
+        app.directory_structure = Some(app.directory_parser.parse_directory(&repo).unwrap());
+        app.token_counts.insert(repo.join("a.txt"), (100, false, 7, 0));
+        app.token_counts.insert(repo.join("b.txt"), (100, false, 7, 0));
+        
+        // Edit one file and add another
+        std::fs::write(repo.join("b.txt"), "three four five").unwrap();
+        std::fs::write(repo.join("c.txt"), "six").unwrap();
+        app.refresh_changed_files(vec![repo.join("b.txt")], &egui::Context::default());
+        app.refresh_changed_files(vec![repo.join("c.txt")], &egui::Context::default());
+        
+        // The parse runs in the background; the replaced one's changes carry over
+        assert!(app.file_list.is_empty());
+        let refresh = app.changes_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(refresh.changed, HashSet::from([repo.join("b.txt"), repo.join("c.txt")]));
+        app.apply_changed_files(refresh);
+        
+        // The unchanged file keeps its count and every file is listed
+        assert_eq!(app.token_counts.get(&repo.join("a.txt")), Some(&(100, false, 7, 0)));
+        assert_eq!(app.file_list.len(), 3);
+        assert!(app.is_loading_tokens);
+        
+        // Only the changed and new files are recounted
+        let mut recounted: Vec<PathBuf> = (0..2)
+            .map(|_| app.token_receiver.recv_timeout(Duration::from_secs(5)).unwrap().1)
+            .collect();
+        recounted.sort();
+        assert_eq!(recounted, vec![repo.join("b.txt"), repo.join("c.txt")]);
+        assert!(app.token_receiver.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+    
+    #[test]
+    fn test_page_follows_focused_file() {
+        // A list cut short by a filter clamps to its last page
+        assert_eq!(visible_page(5, None, 30, 10), 2);
+        assert_eq!(visible_page(1, None, 30, 10), 1);
+        assert_eq!(visible_page(3, None, 0, 10), 0);
+        assert_eq!(visible_page(5, Some(12), 30, 10), 1);
+        
+        let names: Vec<String> = (0..30)
+            .map(|index| format!("{:02}.{}", index, if index % 3 == 0 { "rs" } else { "md" }))
+            .collect();
+        let files: Vec<(&str, usize, bool)> = names.iter()
+            .enumerate()
+            .map(|(index, name)| (name.as_str(), index, false))
+            .collect();
+        let mut app = app_with_files(&files);
+        let root = PathBuf::from("/repo");
+        app.apply_filters();
+        app.current_page = 2;
+        
+        // Without a focused file, sorting and filtering start over at the first page
+        app.sort_direction = SortDirection::Descending;
+        app.change_sort();
+        assert_eq!(app.current_page, 0);
+        
+        // The focused file's page is recomputed when it is rendered
+        let focused = app.file_list.iter().find(|f| f.path == root.join("27.rs")).unwrap().index;
+        app.find_current = Some(focused);
+        app.current_page = 2;
+        app.filter_extension = "rs".to_string();
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 10);
+        assert_eq!(app.scroll_to_index, Some(focused));
+        let position = app.file_list.iter().position(|f| f.index == focused);
+        assert_eq!(visible_page(app.current_page, position, app.file_list.len(), 4), 0);
+    }
+    
+    #[test]
+    fn test_recount_tokens_with_other_tokenizer() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_path_buf();
+        fs::write(repo.join("a.rs"), "a==b").unwrap();
+        
+        let mut app = test_app();
+        app.repository_path = Some(repo.clone());
+        let parsed = app.directory_parser.parse_repository(&repo).unwrap();
+        app.load_parsed_repository(parsed);
+        
+        wait_for_background(&mut app);
+        assert_eq!(app.file_list[0].tokens, 1);
+        
+        // Switching to code-aware counting splits at the operator
+        app.tokenizer = TokenizerKind::Code;
+        app.recount_tokens();
+        assert!(app.is_loading_tokens);
+        wait_for_background(&mut app);
+        assert_eq!(app.file_list[0].tokens, 4);
+        assert_eq!(app.token_counts[&repo.join("a.rs")].0, 4);
+    }
+    
+    #[test]
+    fn test_issues_keep_failures_for_retry() {
+        let mut app = test_app();
+        for number in 0..=MAX_ISSUES {
+            app.show_error_dialog(&format!("error {}", number), None);
+        }
+        assert_eq!(app.issues.len(), MAX_ISSUES);
+        assert_eq!(app.issues[0].message, "error 1");
+        app.issues.clear();
+        
+        // A failed refresh can be retried once the problem is fixed
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        app.repository_path = Some(repo.clone());
+        app.refresh_repository();
+        
+        wait_for_background(&mut app);
+        assert_eq!(app.issues.len(), 1);
+        assert!(app.error_dialog.is_some());
+        let action = app.issues[0].retry.clone().unwrap();
+        assert_eq!(action, RetryAction::Parse(repo.clone()));
+        
+        fs::create_dir(&repo).unwrap();
+        fs::write(repo.join("a.txt"), "one").unwrap();
+        app.retry(action);
+        wait_for_background(&mut app);
+        assert_eq!(app.issues.len(), 1);
+        assert_eq!(app.file_list.len(), 1);
+    }
+    
+    #[test]
+    fn test_receive_token_results_in_one_batch() {
+        let mut app = test_app();
+        app.file_list = (0..3)
+            .map(|index| file_info(index, PathBuf::from(format!("{}.txt", index)), 0, false))
+            .collect();
+        app.sort_column = SortColumn::Tokens;
+        app.sort_direction = SortDirection::Descending;
+        
+        let (token_sender, _) = app.start_counting();
+        for (index, tokens) in [(0, 5), (1, 20), (2, 10)] {
+            token_sender.send((index, PathBuf::from(format!("{}.txt", index)), tokens, false, 0, 0, None, None)).unwrap();
+        }
+        
+        // Everything that arrived is taken and sorted together
+        assert_eq!(app.receive_token_results(), (3, false));
+        let order: Vec<usize> = app.file_list.iter().map(|f| f.index).collect();
+        assert_eq!(order, vec![1, 2, 0]);
+        assert_eq!(app.counting_progress.0, 3);
+        
+        // Nothing new until the thread finishes
+        assert_eq!(app.receive_token_results(), (0, false));
+        drop(token_sender);
+        assert_eq!(app.receive_token_results(), (0, true));
+    }
+    
+    #[test]
+    fn test_sorts_while_counting_are_bounded() {
+        for total_files in [10, 1_000, 100_000] {
+            // Results arrive one at a time, the worst case for sorting per batch
+            let mut sorted_progress = 0;
+            let mut sorts = 0;
+            for completed in 1..=total_files {
+                if sort_due(completed - sorted_progress, total_files) {
+                    sorted_progress = completed;
+                    sorts += 1;
                 }
             }
-        });
+            assert!((1..=COUNTING_SORT_STEPS).contains(&sorts), "{} sorts for {} files", sorts, total_files);
+        }
+        
+        // Counts don't reorder the list by number, so it isn't sorted at all
+        let mut app = test_app();
+        app.file_list = vec![
+            file_info(1, PathBuf::from("b.txt"), 0, false),
+            file_info(0, PathBuf::from("a.txt"), 0, false),
+        ];
+        let (token_sender, _) = app.start_counting();
+        app.counting_progress = (0, 2);
+        token_sender.send((0, PathBuf::from("a.txt"), 3, false, 0, 0, None, None)).unwrap();
+        drop(token_sender);
+        assert_eq!(app.receive_token_results(), (1, true));
+        assert_eq!(app.file_list[1].tokens, 3);
+        assert_eq!(app.file_list[0].index, 1);
+        assert_eq!(app.sorted_progress, 1);
     }
-}
-
-impl GitScrollApp {
-    /// Clears the current repository and resets the application state
-    fn clear_repository(&mut self) {
-        // Clean up the repository if not keeping it
-        if !self.keep_repository && self.repository_path.is_some() {
-            let _ = self.git_handler.cleanup(self.repository_path.as_ref().unwrap());
+    
+    #[test]
+    fn test_lfs_pointers_count_as_their_real_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_path_buf();
+        std::fs::write(repo.join("model.bin"), "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 5000000\n").unwrap();
+        std::fs::write(repo.join("notes.txt"), "one two").unwrap();
+        
+        let mut app = test_app();
+        app.repository_path = Some(repo.clone());
+        let parsed = app.directory_parser.parse_repository(&repo).unwrap();
+        app.load_parsed_repository(parsed);
+        wait_for_background(&mut app);
+        
+        // The stub's text isn't counted; it stands in for a binary of the declared size
+        let pointer = app.file_list.iter().find(|f| f.path == repo.join("model.bin")).unwrap();
+        assert_eq!(pointer.lfs_size, Some(5_000_000));
+        assert!(pointer.is_binary);
+        assert_eq!(pointer.size_bytes, 5_000_000);
+        let notes = app.file_list.iter().find(|f| f.path == repo.join("notes.txt")).unwrap();
+        assert_eq!(notes.lfs_size, None);
+        assert_eq!(notes.tokens, 2);
+        
+        // The mark survives filtering
+        app.filter_pattern = "model".to_string();
+        app.apply_filters();
+        assert_eq!(app.file_list[0].lfs_size, Some(5_000_000));
+    }
+    
+    #[test]
+    fn test_modified_time_read_when_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_path_buf();
+        std::fs::write(repo.join("notes.txt"), "one two").unwrap();
+        let on_disk = std::fs::metadata(repo.join("notes.txt")).unwrap().modified().unwrap();
+        
+        let mut app = test_app();
+        app.repository_path = Some(repo.clone());
+        let parsed = app.directory_parser.parse_repository(&repo).unwrap();
+        app.load_parsed_repository(parsed);
+        assert_eq!(app.file_list[0].modified, None);
+        wait_for_background(&mut app);
+        assert_eq!(app.file_list[0].modified, Some(on_disk));
+        
+        // The time survives filtering
+        app.filter_pattern = "notes".to_string();
+        app.apply_filters();
+        assert_eq!(app.file_list[0].modified, Some(on_disk));
+    }
+    
+    #[test]
+    fn test_parse_phase_keeps_loading() {
+        let mut app = test_app();
+        let (clone_sender, clone_receiver) = mpsc::channel();
+        app.clone_receiver = clone_receiver;
+        app.is_cloning = true;
+        app.ui_handler.set_loading(true);
+        let ctx = egui::Context::default();
+        
+        app.ui_handler.set_progress(1.0);
+        assert!(app.ui_handler.shows_progress_bar());
+        
+        // Once the clone is done the parse runs with a spinner
+        clone_sender.send(CloneProgress::Completed(Ok(PathBuf::from("/nonexistent/repo")))).unwrap();
+        app.check_background_operations(&ctx);
+        assert!(app.is_cloning);
+        assert!(!app.ui_handler.shows_progress_bar());
+        assert_eq!(app.status_kind, StatusKind::Loading);
+    }
+    
+    #[test]
+    fn test_count_selected_subset() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_path_buf();
+        std::fs::create_dir(repo.join("src")).unwrap();
+        std::fs::write(repo.join("src").join("a.txt"), "one two").unwrap();
+        std::fs::write(repo.join("src").join("b.txt"), "three").unwrap();
+        std::fs::write(repo.join("c.txt"), "four five six").unwrap();
+        
+        let mut app = test_app();
+        app.repository_path = Some(repo.clone());
+        app.count_on_load = false;
+        let parsed = app.directory_parser.parse_repository(&repo).unwrap();
+        app.load_parsed_repository(parsed);
+        
+        // Nothing is counted on load
+        assert_eq!(app.file_list.len(), 3);
+        assert!(!app.is_loading_tokens);
+        assert!(app.token_counts.is_empty());
+        
+        
+        // Counting a selected file leaves the others uncounted
+        let position = app.file_list.iter().position(|f| f.path == repo.join("c.txt")).unwrap();
+        app.file_list[position].selected = true;
+        app.count_selected_files();
+        assert_eq!(app.counting_progress, (0, 1));
+        wait_for_background(&mut app);
+        assert_eq!(app.token_counts.len(), 1);
+        assert_eq!(app.token_counts[&repo.join("c.txt")].0, 3);
+        
+        // Counting a folder adds its files to the earlier counts
+        app.count_subset(&HashSet::from([repo.join("src")]));
+        wait_for_background(&mut app);
+        assert_eq!(app.token_counts.len(), 3);
+        assert_eq!(app.counting_progress, (2, 2));
+        assert_eq!(app.token_counts[&repo.join("src").join("a.txt")].0, 2);
+    }
+    
+    #[test]
+    fn test_refresh_keeps_selection_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().to_path_buf();
+        std::fs::write(repo.join("a.txt"), "one two").unwrap();
+        std::fs::write(repo.join("b.md"), "three").unwrap();
+        
+        let mut app = test_app();
+        app.repository_path = Some(repo.clone());
+        app.filter_extension = "txt".to_string();
+        app.refresh_repository();
+        assert!(app.is_refreshing);
+        
+        // Wait for the background parse and token counting to finish
+        wait_for_background(&mut app);
+        assert_eq!(app.file_list.len(), 1);
+        app.file_list[0].selected = true;
+        
+        // A new matching file shows up, and the selection survives
+        std::fs::write(repo.join("c.txt"), "four").unwrap();
+        app.refresh_repository();
+        wait_for_background(&mut app);
+        assert_eq!(app.file_list.len(), 2);
+        let selected: Vec<&PathBuf> = app.file_list.iter().filter(|f| f.selected).map(|f| &f.path).collect();
+        assert_eq!(selected, vec![&repo.join("a.txt")]);
+    }
+    
+    #[test]
+    fn test_tokens_per_byte() {
+        let file = |tokens: usize, is_binary: bool, size_bytes: usize| FileInfo {
+            size_bytes,
+            ..file_info(0, "file", tokens, is_binary)
+        };
+        
+        // Ordinary source code is not flagged
+        let source = file(400, false, 2000);
+        assert_eq!(source.tokens_per_byte(), Some(0.2));
+        assert!(!source.is_likely_generated());
+        
+        // Minified code has very few tokens for its size
+        let minified = file(20, false, 10_000);
+        assert!(minified.is_likely_generated());
+        
+        // Small files, binaries and empty files are never flagged
+        assert!(!file(1, false, 100).is_likely_generated());
+        assert_eq!(file(5000, true, 5000).tokens_per_byte(), None);
+        assert_eq!(file(0, false, 0).tokens_per_byte(), None);
+        
+        // The aggregate ratio only covers text files
+        let files = vec![source, minified, file(5000, true, 5000)];
+        assert_eq!(aggregate_tokens_per_byte(&files), Some(420.0 / 12_000.0));
+        assert_eq!(aggregate_tokens_per_byte(&files[2..]), None);
+    }
+    
+    #[test]
+    fn test_sort_by_density() {
+        let mut app = app_with_files(&[
+            ("a.js", 10, false),
+            ("b.png", 10, true),
+            ("c.rs", 10, false),
+        ]);
+        app.file_list[0].size_bytes = 1000;
+        app.file_list[2].size_bytes = 50;
+        
+        // Lowest density first; binary files go last
+        app.sort_column = SortColumn::Density;
+        app.sort_file_list();
+        let names = |app: &GitScrollApp| app.file_list.iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(names(&app), vec!["a.js", "c.rs", "b.png"]);
+        
+        // Highest density first; binary files still go last
+        app.sort_direction = SortDirection::Descending;
+        app.sort_file_list();
+        assert_eq!(names(&app), vec!["c.rs", "a.js", "b.png"]);
+    }
+    
+    #[test]
+    fn test_apply_config() {
+        let config = ProjectConfig::from_yaml("
+url: https://github.com/user/repo.git
+branch: develop
+keep_repository: true
+remote_name: upstream
+fetch_all_branches: true
+ignore_preset: strict
+ignore_patterns: [dist]
+text_extensions: [rs, toml]
+max_file_count: 1000
+extension_presets:
+  - name: Rust
+    extensions: [rs, toml]
+filters:
+  extension: rs
+  preset: rust
+  min_tokens: 10
+").unwrap();
+        
+        let mut app = test_app();
+        app.apply_config(&config);
+        
+        assert_eq!(app.git_url, "https://github.com/user/repo.git");
+        assert_eq!(app.clone_branch, "develop");
+        assert!(app.keep_repository);
+        assert_eq!(app.fetch_config().remote_name, "upstream");
+        assert!(app.fetch_config().all_branches);
+        assert_eq!(app.max_file_count, 1000);
+        assert!(app.directory_parser.is_ignored(Path::new("/repo"), Path::new("/repo/dist/app.js")));
+        assert_eq!(app.directory_parser.ignore_preset(), Some(IgnorePreset::Strict));
+        assert_eq!(parse_extension_list(&app.text_extensions), vec!["rs", "toml"]);
+        assert_eq!(app.filter_extension, "rs");
+        assert_eq!(app.extension_presets[app.active_preset].name, "Rust");
+        assert_eq!(app.filter_token_min, 10);
+        
+        // Without auto_clone nothing starts
+        assert!(!app.is_cloning);
+    }
+    
+    #[test]
+    fn test_large_repository_waits_for_confirmation() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            fs::write(dir.path().join(name), "fn main() {}").unwrap();
         }
+        let repository = DirectoryParser::isolated().parse_repository(dir.path()).unwrap();
         
-        // Reset application state
-        self.repository_path = None;
-        self.directory_structure = None;
-        self.file_list.clear();
-        self.status_message = String::from("Ready");
-        self.is_cloning = false;
-        self.cancel_requested = false; // Reset cancel flag
-        self.is_loading_tokens = false;
-        self.current_page = 0; // Reset to first page
-        self.ui_handler.set_loading(false);
+        let mut app = test_app();
+        app.max_file_count = 2;
+        assert!(app.needs_file_count_confirmation(3));
+        assert!(!app.needs_file_count_confirmation(2));
+        
+        app.large_repository = Some(LargeRepository { repository, file_count: 3, ignore_patterns: String::new() });
+        app.confirm_large_repository();
+        assert!(app.large_repository.is_none());
+        assert_eq!(app.file_list.len(), 3);
+        
+        // A refresh of the counted repository doesn't ask again, even with files filtered out
+        app.filter_pattern = "a.rs".to_string();
+        app.apply_filters();
+        assert_eq!(app.file_list.len(), 1);
+        assert!(!app.needs_file_count_confirmation(4));
+        
+        // 0 turns the limit off
+        app.directory_structure = None;
+        app.max_file_count = 0;
+        assert!(!app.needs_file_count_confirmation(1_000_000));
     }
     
-    /// Toggles dark mode
-    fn toggle_dark_mode(&mut self) {
-        let current_mode = self.ui_handler.is_dark_mode();
-        self.ui_handler.set_dark_mode(!current_mode);
+    #[test]
+    fn test_status_verbosity() {
+        let mut app = test_app();
+        app.set_status("Repository parsed successfully", StatusKind::Success);
+        
+        // Quiet keeps the phase message
+        app.status_verbosity = StatusVerbosity::Quiet;
+        app.set_progress_status("Counting tokens: 10% (1/10)");
+        assert_eq!(app.status_message, "Repository parsed successfully");
+        
+        // Normal drops updates that follow too closely
+        app.status_verbosity = StatusVerbosity::Normal;
+        app.set_progress_status("Counting tokens: 10% (1/10)");
+        app.set_progress_status("Counting tokens: 20% (2/10)");
+        assert_eq!(app.status_message, "Counting tokens: 10% (1/10)");
+        assert_eq!(app.status_kind, StatusKind::Loading);
+        
+        // Verbose shows every update
+        app.status_verbosity = StatusVerbosity::Verbose;
+        app.set_progress_status("Counting tokens: 20% (2/10)");
+        assert_eq!(app.status_message, "Counting tokens: 20% (2/10)");
+        
+        // Progress isn't kept in the event log
+        assert_eq!(app.event_log.len(), 1);
     }
     
-    /// Applies advanced filters to the file list
-    fn apply_advanced_filters(&mut self) {
-        if let Some(root_entry) = &self.directory_structure {
-            // Get all files from the directory structure
-            let files = self.directory_parser.get_all_files(root_entry);
-            
-            // Create a new filtered list
-            let mut filtered_list = Vec::new();
-            
-            for (index, path) in files.iter().enumerate() {
-                // Check extension filter
-                let extension_match = self.filter_extension.is_empty() ||
-                    path.extension().map_or(false, |e| e.to_string_lossy().to_lowercase() == self.filter_extension.to_lowercase());
-                
-                // Find token count and binary status for this file
-                let (tokens, is_binary) = self.file_list.iter()
-                    .find(|f| f.path == *path)
-                    .map_or((0, false), |f| (f.tokens, f.is_binary));
-                
-                // Check token range filters
-                let min_tokens_match = self.filter_token_min == 0 || tokens >= self.filter_token_min;
-                let max_tokens_match = self.filter_token_max == 0 || tokens <= self.filter_token_max;
-                
-                // Apply all filters
-                if extension_match && min_tokens_match && max_tokens_match {
-                    filtered_list.push(FileInfo {
-                        index,
-                        path: path.clone(),
-                        tokens,
-                        selected: false,
-                        is_binary,
-                    });
-                }
-            }
-            
-            // Update the file list
-            self.file_list = filtered_list;
-            
-            // Reset to first page when filters change
-            self.current_page = 0;
-            
-            // Resort the list
-            self.sort_file_list();
-        }
+    #[test]
+    fn test_hash_file_streams_in_chunks() {
+        // Several chunks with a partial one at the end
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("asset.bin");
+        let contents: Vec<u8> = (0..HASH_CHUNK_BYTES * 40 + 123).map(|i| (i % 251) as u8).collect();
+        fs::write(&path, &contents).unwrap();
+        
+        assert_eq!(hash_file(&path, None), xxhash_rust::xxh3::xxh3_64(&contents));
+        assert_eq!(hash_file(&path, Some(&AtomicBool::new(false))), xxhash_rust::xxh3::xxh3_64(&contents));
+        assert_eq!(hash_file(&dir.path().join("missing.bin"), None), 0);
+        
+        // A cancelled count stops hashing
+        assert_eq!(hash_file(&path, Some(&AtomicBool::new(true))), 0);
     }
     
-    /// Exports the file list to a CSV file
-    fn export_to_csv(&self) {
-        if self.file_list.is_empty() {
-            return;
+    #[test]
+    fn test_duplicates_cached_until_list_changes() {
+        let mut app = app_with_files(&[("a.rs", 10, false), ("b.rs", 10, false), ("c.rs", 20, false)]);
+        for file in &mut app.file_list[..2] {
+            file.content_hash = 7;
         }
         
-        // Create CSV content
-        let mut csv = String::from("Index,Path,Value,FormattedValue,IsBinary,Type\n");
+        let ctx = egui::Context::default();
+        let _ = ctx.run(Default::default(), |ctx| app.render_duplicates_window(ctx));
+        assert_eq!(app.duplicates.as_ref().map(Vec::len), Some(1));
         
-        for file in &self.file_list {
-            let file_type = if file.is_binary { "Binary" } else { "Text" };
-            let value_label = if file.is_binary { "Size" } else { "Tokens" };
-            let formatted_value = if file.is_binary {
-                format_file_size(file.tokens)
-            } else {
-                file.tokens.to_string()
-            };
-            
-            csv.push_str(&format!(
-                "{},{},{},\"{}\",{},{}\n",
-                file.index,
-                file.path.to_string_lossy().replace(',', "\\,"), // Escape commas in paths
-                file.tokens,
-                formatted_value,
-                file.is_binary,
-                file_type
-            ));
-        }
+        // Rebuilding the list finds the sets again on the next frame
+        app.apply_filters();
+        assert!(app.duplicates.is_none());
+    }
+    
+    #[test]
+    fn test_empty_report_cached_until_list_changes() {
+        let mut app = app_with_files(&[("a.rs", 10, false), ("empty.rs", 0, false)]);
         
-        // Calculate total sizes for the success message
-        let total_files = self.file_list.len();
-        let text_files = self.file_list.iter().filter(|f| !f.is_binary).count();
-        let binary_files = self.file_list.iter().filter(|f| f.is_binary).count();
-        let total_tokens = self.file_list.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum::<usize>();
-        let total_binary_size = self.file_list.iter().filter(|f| f.is_binary).map(|f| f.tokens).sum::<usize>();
-        
-        // Write to file
-        match std::fs::write("file_list.csv", csv) {
-            Ok(_) => {
-                // Print success message with statistics
-                println!("Exported to file_list.csv:");
-                println!("  - Total files: {} ({} text, {} binary)", total_files, text_files, binary_files);
-                println!("  - Total tokens (text files): {}", total_tokens);
-                if binary_files > 0 {
-                    println!("  - Total binary size: {}", format_file_size(total_binary_size));
-                }
-            },
-            Err(e) => {
-                // Handle error
-                eprintln!("Failed to export: {}", e);
-            }
-        }
+        let ctx = egui::Context::default();
+        let _ = ctx.run(Default::default(), |ctx| app.render_empty_report_window(ctx));
+        let report = app.empty_report.as_ref().unwrap();
+        assert!(report.directories.is_empty());
+        assert_eq!(report.files, vec![PathBuf::from("/repo/empty.rs")]);
+        
+        // Rebuilding the list walks the tree and files again on the next frame
+        app.apply_filters();
+        assert!(app.empty_report.is_none());
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
     
     #[test]
-    fn test_validate_git_url() {
-        // Create a new app instance for testing
-        let app = GitScrollApp::new();
+    fn test_find_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = [("a.rs", "fn main() {}"), ("b.rs", "fn main() {}"), ("c.rs", "fn other() {}"), ("d.rs", "fn main() {}"), ("e.rs", ""), ("f.rs", "")];
+        let extensions = parse_extension_list(DEFAULT_TEXT_EXTENSIONS);
+        let files: Vec<FileInfo> = contents.iter().enumerate().map(|(index, (name, text))| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, text).unwrap();
+            let (tokens, is_binary, size_bytes, content_hash) = count_file(&path, &extensions, TokenizerKind::Words);
+            FileInfo { size_bytes, content_hash, ..file_info(index, path, tokens, is_binary) }
+        }).collect();
         
-        // Test valid URLs
-        assert!(app.validate_git_url("https://github.com/user/repo.git"));
-        assert!(app.validate_git_url("https://github.com/user/repo")); // No .git suffix is now valid
-        assert!(app.validate_git_url("git@github.com:user/repo.git")); // SSH format is now valid
-        assert!(app.validate_git_url("file:///path/to/repo")); // Local file path is now valid
-        assert!(app.validate_git_url("/absolute/path/to/repo")); // Absolute path is now valid
+        // Identical contents hash the same; different contents don't
+        assert_eq!(files[0].content_hash, files[1].content_hash);
+        assert_ne!(files[0].content_hash, files[2].content_hash);
         
-        // Test invalid URLs
-        assert!(!app.validate_git_url("invalid-url")); // No protocol or path format
+        // Empty files are not reported as duplicates of each other
+        let duplicates = find_duplicates(&files);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].paths, vec![dir.path().join("a.rs"), dir.path().join("b.rs"), dir.path().join("d.rs")]);
+        assert_eq!(duplicates[0].tokens, 3);
+        assert_eq!(duplicates[0].wasted_tokens(), 6);
+        assert_eq!(duplicates[0].wasted_bytes(), 24);
     }
     
     #[test]
-    fn test_token_count() {
-        // Create a temporary file for testing
-        let temp_file = std::env::temp_dir().join("test_token_count.txt");
-        fs::write(&temp_file, "hello world this is a test").unwrap();
+    fn test_empty_files() {
+        let app = app_with_files(&[("empty.txt", 0, false), ("a.txt", 10, false), ("empty.bin", 0, true)]);
+        let mut files = app.file_list.clone();
         
-        // Count tokens
-        let (count, is_binary) = count_tokens(&temp_file);
-        assert_eq!(count, 5); // 5 words in the test string
-        assert_eq!(is_binary, false); // Text file, not binary
+        // A file without tokens but with bytes, e.g. only whitespace, is not empty
+        files.push(FileInfo { size_bytes: 12, ..files[0].clone() });
         
-        // Clean up
-        fs::remove_file(temp_file).unwrap();
+        let empty: Vec<&Path> = empty_files(&files).iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(empty, vec![Path::new("/repo/empty.txt"), Path::new("/repo/empty.bin")]);
     }
     
     #[test]
-    fn test_sorting() {
-        // Create test file info entries
-        let files = vec![
-            FileInfo { index: 0, path: PathBuf::from("a.txt"), tokens: 10, selected: false, is_binary: false },
-            FileInfo { index: 1, path: PathBuf::from("b.txt"), tokens: 5, selected: false, is_binary: false },
-            FileInfo { index: 2, path: PathBuf::from("c.txt"), tokens: 15, selected: false, is_binary: false },
-        ];
+    fn test_tooltip_head() {
+        let content = "fn main() {\n    println!(\"héllo\");\n}\n";
+        assert_eq!(tooltip_head(content, 2).as_deref(), Some("fn main() {\n    println!(\"héllo\");"));
+        assert_eq!(tooltip_head(content, 10).as_deref(), Some("fn main() {\n    println!(\"héllo\");\n}"));
         
-        // Test sorting by tokens ascending
-        let mut app = GitScrollApp::new();
-        app.file_list = files.clone();
-        app.sort_column = SortColumn::Tokens;
-        app.sort_direction = SortDirection::Ascending;
-        app.sort_file_list();
-        assert_eq!(app.file_list[0].tokens, 5);
-        assert_eq!(app.file_list[2].tokens, 15);
+        // Blank files and a line limit of zero leave the tooltip alone
+        assert_eq!(tooltip_head("\n  \n", 5), None);
+        assert_eq!(tooltip_head(content, 0), None);
+    }
+    
+    #[test]
+    fn test_status_messages_are_logged() {
+        let mut app = test_app();
+        app.set_status("Counting tokens: 50% (1/2)", StatusKind::Loading);
+        app.set_status("Token counting completed", StatusKind::Success);
+        app.set_status("Failed to export", StatusKind::Error);
         
-        // Test sorting by tokens descending
+        // Progress updates only change the status bar
+        let logged: Vec<(StatusKind, &str)> = app.event_log.events()
+            .map(|event| (event.kind, event.message.as_str()))
+            .collect();
+        assert_eq!(logged, vec![
+            (StatusKind::Success, "Token counting completed"),
+            (StatusKind::Error, "Failed to export"),
+        ]);
+    }
+    
+    #[test]
+    fn test_export_outcome_is_shown() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_with_files(&[("main.rs", 120, false), ("logo.png", 2048, true)]);
+        
+        let path = dir.path().join("files.csv");
+        app.write_file_list_export(ExportFormat::Csv, false, &path);
+        assert_eq!(app.status_kind, StatusKind::Success);
+        assert!(app.status_message.starts_with("Exported 2 files (1 text, 1 binary, 120 tokens) to "));
+        assert!(app.error_dialog.is_none());
+        
+        // Failures stay on screen until dismissed
+        app.write_file_list_export(ExportFormat::Csv, false, &dir.path().join("missing").join("files.csv"));
+        assert_eq!(app.status_kind, StatusKind::Error);
+        assert_eq!(app.error_dialog.as_deref(), Some(app.status_message.as_str()));
+    }
+    
+    #[test]
+    fn test_export_selected_falls_back_to_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_with_files(&[("main.rs", 120, false), ("lib.rs", 30, false), ("logo.png", 2048, true)]);
+        let path = dir.path().join("files.md");
+        
+        // Nothing checked exports every file
+        app.write_file_list_export(ExportFormat::Markdown, true, &path);
+        assert!(app.status_message.starts_with("Exported 3 files (2 text, 1 binary, 150 tokens) to "));
+        
+        app.file_list[1].selected = true;
+        app.write_file_list_export(ExportFormat::Markdown, true, &path);
+        assert!(app.status_message.starts_with("Exported 1 selected files (1 text, 0 binary, 30 tokens) to "));
+        let markdown = std::fs::read_to_string(&path).unwrap();
+        assert!(markdown.contains("lib.rs"));
+        assert!(!markdown.contains("main.rs"));
+    }
+    
+    #[test]
+    fn test_tree_depth() {
+        let root = Path::new("/tmp/git_scroll_abc123/repo");
+        
+        // Depth is measured from the repository root, not the filesystem root
+        assert_eq!(tree_depth(&root.join("README.md"), Some(root)), 0);
+        assert_eq!(tree_depth(&root.join("src").join("ui").join("mod.rs"), Some(root)), 2);
+        
+        // Without a root the path is measured as is
+        assert_eq!(tree_depth(Path::new("src/main.rs"), None), 1);
+        assert_eq!(tree_depth(Path::new("/elsewhere/file.rs"), Some(root)), 1);
+    }
+    
+    #[test]
+    fn test_table_columns() {
+        // Index, Name and Value are shown by default, in that order
+        let columns = default_table_columns();
+        let shown: Vec<FileColumn> = columns.iter().filter(|column| column.visible).map(|column| column.kind).collect();
+        assert_eq!(shown, vec![FileColumn::Index, FileColumn::Name, FileColumn::Value]);
+        assert_eq!(FileColumn::Value.header(MetricBasis::Bytes), MetricBasis::Bytes.column_header());
+        assert!(FileColumn::Modified.sort_column().is_none());
+        
+        // The size column sorts by bytes on disk
+        let mut app = app_with_files(&[("small.rs", 500, false), ("large.rs", 10, false)]);
+        app.file_list[0].size_bytes = 100;
+        app.file_list[1].size_bytes = 9000;
+        app.sort_column = FileColumn::Size.sort_column().unwrap();
         app.sort_direction = SortDirection::Descending;
         app.sort_file_list();
-        assert_eq!(app.file_list[0].tokens, 15);
-        assert_eq!(app.file_list[2].tokens, 5);
+        assert_eq!(app.file_list[0].path, PathBuf::from("/repo/large.rs"));
+    }
+    
+    #[test]
+    fn test_locked_version() {
+        let lock = "[[package]]\nname = \"egui\"\nversion = \"0.31.1\"\n\n[[package]]\nname = \"egui_extras\"\nversion = \"0.31.2\"\n";
+        assert_eq!(locked_version(lock, "egui"), Some("0.31.1"));
+        assert_eq!(locked_version(lock, "egui_extras"), Some("0.31.2"));
+        assert_eq!(locked_version(lock, "git2"), None);
         
-        // Test sorting by name
-        app.sort_column = SortColumn::Name;
+        // The lock file built into the application has both libraries shown in the about window
+        assert!(locked_version(CARGO_LOCK, "egui").is_some());
+        assert!(locked_version(CARGO_LOCK, "git2").is_some());
+    }
+    
+    #[test]
+    fn test_metric_basis() {
+        let mut app = app_with_files(&[("main.rs", 120, false), ("logo.png", 2048, true)]);
+        app.file_list[0].size_bytes = 600;
+        app.file_list[1].size_bytes = 2048;
+        let (text, binary) = (&app.file_list[0], &app.file_list[1]);
+        
+        assert_eq!(MetricBasis::Hybrid.value(binary), Some(2048));
+        assert_eq!(MetricBasis::Tokens.value(binary), None);
+        assert_eq!(MetricBasis::Bytes.value(text), Some(600));
+        assert_eq!(MetricBasis::Tokens.format(binary, |tokens| tokens.to_string()), "—");
+        assert_eq!(MetricBasis::Bytes.format(text, |tokens| tokens.to_string()), "600 bytes");
+        
+        // Files without a value sort as the smallest
+        app.metric_basis = MetricBasis::Tokens;
+        app.sort_column = SortColumn::Tokens;
         app.sort_direction = SortDirection::Ascending;
         app.sort_file_list();
-        assert_eq!(app.file_list[0].path.file_name().unwrap().to_str().unwrap(), "a.txt");
-        assert_eq!(app.file_list[2].path.file_name().unwrap().to_str().unwrap(), "c.txt");
+        assert!(app.file_list[0].is_binary);
     }
     
     #[test]
-    fn test_empty_repository() {
-        // Create a temporary directory for testing
-        let temp_dir = std::env::temp_dir().join("test_empty_repo");
-        if temp_dir.exists() {
-            std::fs::remove_dir_all(&temp_dir).unwrap();
-        }
-        std::fs::create_dir(&temp_dir).unwrap();
-        
-        // Create a DirectoryEntry for the empty directory
-        let root_entry = DirectoryEntry {
-            name: "empty".to_string(),
-            path: temp_dir.clone(),
-            is_directory: true,
-            children: vec![],
+    fn test_repository_summary() {
+        let app = app_with_files(&[("main.rs", 120, false), ("lib.rs", 80, false), ("logo.png", 2048, true)]);
+        let metadata = RepositoryMetadata {
+            name: "repo".to_string(),
+            branch: "main".to_string(),
+            commit_count: 42,
+            last_commit_date: "2024-01-01 00:00:00".to_string(),
+        };
+        let statistics = DirectoryStatistics {
+            total_files: 3,
+            total_directories: 1,
+            total_size_bytes: 4096,
+            max_depth: 1,
+            file_types: HashMap::from([("rs".to_string(), 2), ("png".to_string(), 1)]),
         };
         
-        // Test populating file list with empty repository
-        let mut app = GitScrollApp::new();
-        app.populate_file_list(&root_entry);
+        let summary = repository_summary("repo", Some(&metadata), Some(&statistics), &app.file_list, "2024-06-01 12:00");
+        assert!(summary.starts_with("## repo\n"));
+        assert!(summary.contains("- Branch: main\n- Commits: 42\n"));
+        assert!(summary.contains("- Files: 3 (2 text, 1 binary)\n"));
+        assert!(summary.contains("- Tokens (text files): 200\n"));
+        assert!(summary.contains(&format!("- Size: {}\n", format_file_size(4096))));
+        assert!(summary.contains("- Top languages: .rs (2), .png (1)\n"));
+        assert!(summary.ends_with("_Generated by Git Scroll on 2024-06-01 12:00_\n"));
         
-        // Verify that the file list is empty
-        assert!(app.file_list.is_empty());
+        // Without Git history or statistics the totals come from the file list
+        let summary = repository_summary("repo", None, None, &app.file_list, "now");
+        assert!(!summary.contains("Branch"));
+        assert!(summary.contains(&format!("- Size: {}\n", format_file_size(2248))));
+        assert!(!summary.contains("Top languages"));
+    }
+    
+    #[test]
+    fn test_counting_pool_caps_threads() {
+        assert_eq!(with_counting_pool(2, rayon::current_num_threads), 2);
+        assert_eq!(with_counting_pool(0, rayon::current_num_threads), 1);
+        assert!(default_counting_threads() >= 1);
         
-        // Clean up
-        std::fs::remove_dir(temp_dir).unwrap();
+        // Parallel work still runs to completion on the capped pool
+        let sum: usize = with_counting_pool(2, || (1..=100usize).into_par_iter().sum());
+        assert_eq!(sum, 5050);
     }
     
+    
     #[test]
-    fn test_file_with_no_tokens() {
-        // Create a temporary file with no content
-        let temp_file = std::env::temp_dir().join("empty_file.txt");
-        fs::write(&temp_file, "").unwrap();
+    fn test_find_match() {
+        let app = app_with_files(&[("src/main.rs", 10, false), ("README.md", 5, false), ("src/lib.rs", 20, false)]);
+        let files = &app.file_list;
         
-        // Create a DirectoryEntry for the file
-        let file_entry = DirectoryEntry {
-            name: "empty_file.txt".to_string(),
-            path: temp_file.clone(),
-            is_directory: false,
-            children: vec![],
-        };
+        // Matching is case-insensitive and includes the start position
+        assert_eq!(find_match(files, None, "SRC", 0, false), Some(0));
+        assert_eq!(find_match(files, None, "src", 1, false), Some(2));
         
-        // Create a parent directory entry
-        let root_entry = DirectoryEntry {
-            name: "root".to_string(),
-            path: temp_file.parent().unwrap().to_path_buf(),
-            is_directory: true,
-            children: vec![file_entry],
-        };
+        // Searches wrap around in both directions
+        assert_eq!(find_match(files, None, "src", 3, false), Some(0));
+        assert_eq!(find_match(files, None, "src", 1, true), Some(0));
+        assert_eq!(find_match(files, None, "main", 2, true), Some(0));
         
-        // Test token counting for empty file
-        let (count, is_binary) = count_tokens(&temp_file);
-        assert_eq!(count, 0);
-        assert_eq!(is_binary, false); // Empty text file, not binary
+        // The repository root is not part of the match
+        assert_eq!(find_match(files, None, "repo", 0, false), Some(0));
+        assert_eq!(find_match(files, Some(Path::new("/repo")), "repo", 0, false), None);
+        assert_eq!(find_match(files, None, "missing", 0, false), None);
+        assert_eq!(find_match(files, None, "", 0, false), None);
+    }
+    
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1000), "1.0k");
+        assert_eq!(format_count(14_200), "14.2k");
+        assert_eq!(format_count(999_949), "999.9k");
+        assert_eq!(format_count(999_950), "1.0M");
+        assert_eq!(format_count(1_000_000), "1.0M");
+        assert_eq!(format_count(1_423_875), "1.4M");
+        assert_eq!(format_count(2_500_000_000), "2.5B");
+    }
+    
+    #[test]
+    fn test_prompt_snippet() {
+        let snippet = prompt_snippet(Path::new("src/main.rs"), "fn main() {}\n", 3);
+        assert_eq!(snippet, "// src/main.rs\nfn main() {}\n// 3 tokens\n");
         
-        // Clean up
-        fs::remove_file(temp_file).unwrap();
+        // The footer starts on its own line even without a trailing newline
+        let snippet = prompt_snippet(Path::new("notes.txt"), "hello", 1);
+        assert_eq!(snippet, "// notes.txt\nhello\n// 1 tokens\n");
     }
     
     #[test]
-    fn test_binary_file_detection() {
-        // Create a temporary binary file
-        let temp_file = std::env::temp_dir().join("test_binary.bin");
-        let binary_data = [0u8, 1u8, 2u8, 3u8, 4u8]; // Some binary data
-        fs::write(&temp_file, &binary_data).unwrap();
+    fn test_extension_totals() {
+        let app = app_with_files(&[("a.rs", 100, false), ("b.rs", 50, false), ("README.md", 300, false), ("logo.PNG", 4096, true), ("Makefile", 10, false)]);
         
-        // Test token counting for binary file
-        let (size, is_binary) = count_tokens(&temp_file);
-        assert_eq!(size, binary_data.len()); // Should return the file size in bytes
-        assert_eq!(is_binary, true); // Should be detected as binary
+        let by_tokens = extension_totals(&app.file_list, false);
+        let ranked: Vec<(&str, usize)> = by_tokens.iter().map(|t| (t.extension.as_str(), t.value)).collect();
+        assert_eq!(ranked, vec![("md", 300), ("rs", 150), ("", 10), ("png", 0)]);
+        assert_eq!(by_tokens[1].files, 2);
         
-        // Clean up
-        fs::remove_file(temp_file).unwrap();
+        // Binary files count towards bytes only; extensions are case-insensitive
+        let by_bytes = extension_totals(&app.file_list, true);
+        assert_eq!(by_bytes[0].extension, "png");
+        assert_eq!(by_bytes[0].value, 4096);
+        assert_eq!(by_bytes[0].tokens, 0);
+    }
+    
+    #[test]
+    fn test_directory_totals() {
+        let mut app = app_with_files(&[("src/main.rs", 100, false), ("src/ui/mod.rs", 50, false), ("docs/guide.md", 300, false), ("docs/logo.png", 4096, true), ("README.md", 10, false)]);
+        app.repository_path = Some(PathBuf::from("/repo"));
+        let root = app.repository_path.as_deref();
+        
+        assert_eq!(app.file_list[1].depth(root), 2);
+        assert_eq!(app.file_list[1].top_level_dir(root).as_deref(), Some("src"));
+        assert_eq!(app.file_list[4].depth(root), 0);
+        assert_eq!(app.file_list[4].top_level_dir(root), None);
+        
+        // Binary files count towards bytes only; root files are grouped together
+        let totals = directory_totals(&app.file_list, root);
+        let ranked: Vec<(Option<&str>, usize, usize)> = totals.iter().map(|t| (t.name.as_deref(), t.files, t.tokens)).collect();
+        assert_eq!(ranked, vec![(Some("docs"), 2, 300), (Some("src"), 2, 150), (None, 1, 10)]);
+        assert_eq!(totals[0].bytes, 4396);
+        
+        // Sorting by depth keeps each directory's files together
+        app.sort_column = SortColumn::Depth;
+        app.sort_file_list();
+        let order: Vec<usize> = app.file_list.iter().map(|f| f.index).collect();
+        assert_eq!(order, vec![4, 2, 3, 0, 1]);
+        app.sort_direction = SortDirection::Descending;
+        app.sort_file_list();
+        assert_eq!(app.file_list[0].index, 1);
+    }
+    
+    #[test]
+    fn test_pinned_files_lead_sorted_order() {
+        let mut app = app_with_files(&[("a.rs", 100, false), ("b.rs", 50, false), ("c.rs", 300, false), ("d.rs", 10, false)]);
+        app.sort_column = SortColumn::Tokens;
+        app.sort_direction = SortDirection::Descending;
+        app.sort_file_list();
+        
+        let position = |app: &GitScrollApp, name: &str| app.file_list.iter().position(|f| f.path.ends_with(name)).unwrap();
+        app.toggle_pin(position(&app, "d.rs"));
+        app.toggle_pin(position(&app, "b.rs"));
+        
+        // Pinned files come first, each group in the normal sort order
+        let order = |app: &GitScrollApp| app.file_list.iter().map(|f| f.index).collect::<Vec<usize>>();
+        assert_eq!(order(&app), vec![1, 3, 2, 0]);
+        app.sort_direction = SortDirection::Ascending;
+        app.sort_file_list();
+        assert_eq!(order(&app), vec![3, 1, 0, 2]);
+        
+        // Pins survive rebuilding the list
+        app.apply_filters();
+        assert_eq!(order(&app), vec![3, 1, 0, 2]);
+        
+        app.toggle_pin(position(&app, "d.rs"));
+        assert_eq!(order(&app), vec![1, 3, 0, 2]);
+    }
+    
+    #[test]
+    fn test_pins_persist_per_repository() {
+        let mut app = app_with_files(&[("a.rs", 100, false), ("src/b.rs", 50, false)]);
+        app.repository_source = Some("https://github.com/user/repo.git".to_string());
+        app.toggle_pin(1);
+        
+        // A new clone of the same repository gets the pins under its own root
+        let mut restarted = restart(&mut app);
+        restarted.repository_path = Some(PathBuf::from("/clone"));
+        restarted.repository_source = Some("https://github.com/user/repo.git".to_string());
+        restarted.restore_pins();
+        assert_eq!(restarted.pinned_paths, HashSet::from([PathBuf::from("/clone/src/b.rs")]));
+        
+        // Other repositories keep their own pins
+        restarted.repository_source = Some("https://github.com/user/other.git".to_string());
+        restarted.restore_pins();
+        assert!(restarted.pinned_paths.is_empty());
+        
+        // Unpinning the last file, now at the top, forgets the repository
+        app.toggle_pin(0);
+        assert!(app.saved_pins.is_empty());
+    }
+    
+    #[test]
+    fn test_find_generated_directories() {
+        let mut app = app_with_files(&[
+            ("vendor/lib/a.go", 500, false), ("vendor/b.go", 300, false),
+            ("api/gen/x.pb.go", 200, false), ("api/gen/y.pb.go", 200, false), ("api/gen/helper.go", 50, false),
+            ("src/main.rs", 40, false), ("src/schema_generated.rs", 90, false), ("src/lib.rs", 30, false),
+            ("root_generated.rs", 10, false),
+        ]);
+        app.repository_path = Some(PathBuf::from("/repo"));
+        let root = app.repository_path.as_deref();
+        
+        // One generated file among ordinary ones doesn't flag src, and the root is never flagged
+        let directories = find_generated_directories(&app.file_list, root);
+        let flagged: Vec<(String, usize, usize)> = directories.iter()
+            .map(|d| (d.ignore_pattern(root), d.files, d.tokens))
+            .collect();
+        assert_eq!(flagged, vec![
+            ("vendor/".to_string(), 2, 800),
+            ("api/gen/".to_string(), 3, 450),
+        ]);
+        assert_eq!(directories[0].reason, "vendored dependencies");
+        
+        assert!(is_generated_file_name("api_pb2.py"));
+        assert!(is_generated_file_name("Schema.generated.cs"));
+        assert!(!is_generated_file_name("generator.rs"));
+    }
+    
+    #[test]
+    fn test_repository_title() {
+        assert_eq!(repository_title("https://github.com/emilk/egui.git"), "egui");
+        assert_eq!(repository_title("https://github.com/emilk/egui/"), "egui");
+        assert_eq!(repository_title("git@github.com:user/repo.git"), "repo");
+        assert_eq!(repository_title("/path/to/local_repo"), "local_repo");
+        assert_eq!(repository_title(""), "Repository");
+    }
+    
+    #[test]
+    fn test_session_switching() {
+        let mut app = test_app();
+        app.file_list = vec![
+            file_info(0, PathBuf::from("a.txt"), 10, false),
+        ];
+        app.repository_path = Some(PathBuf::from("/tmp/first"));
+        
+        // Opening a tab starts empty and parks the first tab's data
+        app.open_session(String::from("second"));
+        assert_eq!(app.sessions.len(), 2);
+        assert_eq!(app.active_session, 1);
+        assert!(app.file_list.is_empty());
+        assert!(app.repository_path.is_none());
+        
+        // Switching back restores the first tab's data
+        app.switch_session(0);
+        assert_eq!(app.active_session, 0);
+        assert_eq!(app.file_list.len(), 1);
+        assert_eq!(app.repository_path, Some(PathBuf::from("/tmp/first")));
+        
+        // Closing the inactive tab keeps the active data in place
+        app.keep_repository = true;
+        app.close_session(1);
+        assert_eq!(app.sessions.len(), 1);
+        assert_eq!(app.active_session, 0);
+        assert_eq!(app.file_list.len(), 1);
     }
     
     #[test]
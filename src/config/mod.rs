@@ -0,0 +1,219 @@
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+
+use crate::app::{ExtensionPreset, TokenizerKind};
+use crate::directory::IgnorePreset;
+
+/// Analysis settings loaded from a JSON or YAML project file
+///
+/// Every field is optional, so a project file only needs the settings it
+/// wants to change from the defaults.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Repository URL to analyze
+    pub url: Option<String>,
+    
+    /// Branch to clone instead of the remote's default branch
+    pub branch: Option<String>,
+    
+    /// Whether to keep the cloned repository on disk
+    pub keep_repository: Option<bool>,
+    
+    /// Whether to follow HTTP redirects to other hosts at every stage of a clone
+    pub follow_redirects: Option<bool>,
+    
+    /// Name of the remote created for the clone instead of `origin`
+    pub remote_name: Option<String>,
+    
+    /// Whether to fetch every branch even when `branch` names one to check out
+    pub fetch_all_branches: Option<bool>,
+    
+    /// Private key for SSH URLs instead of `~/.ssh/id_ed25519` or `~/.ssh/id_rsa`
+    pub ssh_key_path: Option<PathBuf>,
+    
+    /// Default ignore patterns to start from: `minimal`, `standard` or `strict`
+    pub ignore_preset: Option<IgnorePreset>,
+    
+    /// Extra patterns to ignore, added to the default ones
+    pub ignore_patterns: Vec<String>,
+    
+    /// Extensions expected to hold text, replacing the default list
+    pub text_extensions: Option<Vec<String>>,
+    
+    /// How text files are split into tokens: `words` or `code`
+    pub tokenizer: Option<TokenizerKind>,
+    
+    /// Number of files counted without asking first (0 = no limit)
+    pub max_file_count: Option<usize>,
+    
+    /// Custom extension presets, offered next to the built-in ones
+    pub extension_presets: Vec<ExtensionPreset>,
+    
+    /// Filters applied to the file list
+    pub filters: FilterConfig,
+    
+    /// Whether to start cloning as soon as the application opens
+    pub auto_clone: bool,
+}
+
+/// File list filters in a project file
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterConfig {
+    /// Substring matched against file paths
+    pub pattern: String,
+    
+    /// File extension to show
+    pub extension: String,
+    
+    /// Name of the extension preset to apply, e.g. `Code` (empty = all files)
+    pub preset: String,
+    
+    /// Minimum token count (0 = no minimum)
+    pub min_tokens: usize,
+    
+    /// Maximum token count (0 = no maximum)
+    pub max_tokens: usize,
+    
+    /// Hide text files with fewer tokens (0 = off)
+    pub hide_below_tokens: usize,
+    
+    /// Hide binary files smaller than this many bytes (0 = off)
+    pub hide_below_bytes: usize,
+    
+    /// Exclude binary files larger than this many megabytes (0 = off)
+    pub exclude_binary_above_mb: usize,
+}
+
+impl ProjectConfig {
+    /// Loads a project file, choosing the format from its extension
+    ///
+    /// Files ending in `.json` are read as JSON and `.yaml`/`.yml` as YAML;
+    /// anything else is tried as JSON first, then as YAML.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the project file
+    ///
+    /// # Returns
+    /// Result with the parsed configuration or an error message
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {:?}: {}", path, e))?;
+        
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase());
+        
+        let result = match extension.as_deref() {
+            Some("json") => Self::from_json(&contents),
+            Some("yaml") | Some("yml") => Self::from_yaml(&contents),
+            _ => Self::from_json(&contents).or_else(|_| Self::from_yaml(&contents)),
+        };
+        
+        result.map_err(|e| format!("Invalid config file {:?}: {}", path, e))
+    }
+    
+    /// Parses a project configuration from JSON
+    ///
+    /// # Arguments
+    /// * `contents` - The JSON text
+    ///
+    /// # Returns
+    /// Result with the parsed configuration or an error message
+    pub fn from_json(contents: &str) -> Result<Self, String> {
+        serde_json::from_str(contents).map_err(|e| e.to_string())
+    }
+    
+    /// Parses a project configuration from YAML
+    ///
+    /// # Arguments
+    /// * `contents` - The YAML text
+    ///
+    /// # Returns
+    /// Result with the parsed configuration or an error message
+    pub fn from_yaml(contents: &str) -> Result<Self, String> {
+        serde_yaml::from_str(contents).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_from_json() {
+        let config = ProjectConfig::from_json(r#"{
+            "url": "https://github.com/user/repo.git",
+            "branch": "develop",
+            "ignore_patterns": ["dist", "vendor"],
+            "filters": { "extension": "rs", "min_tokens": 10 },
+            "tokenizer": "code",
+            "ssh_key_path": "~/.ssh/deploy_key",
+            "auto_clone": true
+        }"#).unwrap();
+        
+        assert_eq!(config.url.as_deref(), Some("https://github.com/user/repo.git"));
+        assert_eq!(config.branch.as_deref(), Some("develop"));
+        assert_eq!(config.ignore_patterns, vec!["dist", "vendor"]);
+        assert_eq!(config.filters.extension, "rs");
+        assert_eq!(config.filters.min_tokens, 10);
+        assert_eq!(config.filters.max_tokens, 0);
+        assert_eq!(config.tokenizer, Some(TokenizerKind::Code));
+        assert_eq!(config.ssh_key_path, Some(PathBuf::from("~/.ssh/deploy_key")));
+        assert!(config.auto_clone);
+        
+        // Omitted settings keep their defaults
+        assert_eq!(config.keep_repository, None);
+        assert_eq!(config.text_extensions, None);
+    }
+    
+    #[test]
+    fn test_from_yaml() {
+        let config = ProjectConfig::from_yaml("
+url: git@github.com:user/repo.git
+keep_repository: true
+text_extensions: [rs, toml]
+filters:
+  pattern: src
+  hide_below_bytes: 512
+  exclude_binary_above_mb: 50
+").unwrap();
+
+        assert_eq!(config.url.as_deref(), Some("git@github.com:user/repo.git"));
+        assert_eq!(config.keep_repository, Some(true));
+        assert_eq!(config.text_extensions, Some(vec!["rs".to_string(), "toml".to_string()]));
+        assert_eq!(config.filters.pattern, "src");
+        assert_eq!(config.filters.hide_below_bytes, 512);
+        assert_eq!(config.filters.exclude_binary_above_mb, 50);
+        assert!(!config.auto_clone);
+    }
+    
+    #[test]
+    fn test_rejects_unknown_fields() {
+        // Typos are reported instead of being silently ignored
+        assert!(ProjectConfig::from_json(r#"{ "urll": "x" }"#).is_err());
+        assert!(ProjectConfig::from_yaml("filters:\n  min_token: 5\n").is_err());
+        assert!(ProjectConfig::from_yaml("tokenizer: bpe\n").is_err());
+    }
+    
+    #[test]
+    fn test_load_by_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        
+        let json_path = dir.path().join("audit.json");
+        std::fs::write(&json_path, r#"{ "branch": "main" }"#).unwrap();
+        assert_eq!(ProjectConfig::load(&json_path).unwrap().branch.as_deref(), Some("main"));
+        
+        let yaml_path = dir.path().join("audit.yml");
+        std::fs::write(&yaml_path, "branch: main\n").unwrap();
+        assert_eq!(ProjectConfig::load(&yaml_path).unwrap().branch.as_deref(), Some("main"));
+        
+        // Unknown extensions fall back to YAML when JSON fails
+        let other_path = dir.path().join("audit.conf");
+        std::fs::write(&other_path, "auto_clone: true\n").unwrap();
+        assert!(ProjectConfig::load(&other_path).unwrap().auto_clone);
+        
+        assert!(ProjectConfig::load(&dir.path().join("missing.json")).is_err());
+    }
+}
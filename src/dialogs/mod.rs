@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use crate::export::ExportFormat;
+
+/// File type filters offered when saving an export
+///
+/// # Arguments
+/// * `format` - The export format
+///
+/// # Returns
+/// The filter name and the extensions it accepts
+pub fn export_filter(format: ExportFormat) -> (&'static str, &'static [&'static str]) {
+    match format {
+        ExportFormat::Csv => ("CSV files", &["csv"]),
+        ExportFormat::Json => ("JSON files", &["json"]),
+        ExportFormat::Markdown => ("Markdown files", &["md"]),
+    }
+}
+
+/// Asks for a folder
+///
+/// # Arguments
+/// * `title` - Title of the dialog
+/// * `start` - Folder the dialog opens in, if it exists
+///
+/// # Returns
+/// The chosen folder, or `None` if the dialog was cancelled
+pub fn pick_folder(title: &str, start: Option<&Path>) -> Option<PathBuf> {
+    let mut dialog = rfd::FileDialog::new().set_title(title);
+    if let Some(start) = start.filter(|path| path.is_dir()) {
+        dialog = dialog.set_directory(start);
+    }
+    dialog.pick_folder()
+}
+
+/// Asks where to save a file list export
+///
+/// # Arguments
+/// * `format` - The export format, which picks the filter and extension
+/// * `file_name` - Suggested file name, without extension
+///
+/// # Returns
+/// The chosen path, or `None` if the dialog was cancelled
+pub fn save_export(format: ExportFormat, file_name: &str) -> Option<PathBuf> {
+    let (filter_name, extensions) = export_filter(format);
+    save_file("Export File List", &format!("{}.{}", file_name, format.extension()), filter_name, extensions)
+}
+
+/// Asks where to save a file
+///
+/// # Arguments
+/// * `title` - Title of the dialog
+/// * `file_name` - Suggested file name
+/// * `filter_name` - Name of the file type filter, e.g. "Text files"
+/// * `extensions` - Extensions the filter accepts, without dots
+///
+/// # Returns
+/// The chosen path, or `None` if the dialog was cancelled
+pub fn save_file(title: &str, file_name: &str, filter_name: &str, extensions: &[&str]) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .set_title(title)
+        .set_file_name(file_name)
+        .add_filter(filter_name, extensions)
+        .save_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_export_filter_matches_extension() {
+        for format in ExportFormat::ALL {
+            let (_, extensions) = export_filter(format);
+            assert_eq!(extensions, &[format.extension()]);
+        }
+    }
+}
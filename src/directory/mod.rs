@@ -1,4 +1,6 @@
 use std::path::{Path, PathBuf};
+use regex::Regex;
+use serde::Deserialize;
 
 /// Represents a directory or file in the repository
 #[derive(Debug, Clone)]
@@ -16,47 +18,394 @@ pub struct DirectoryEntry {
     pub children: Vec<DirectoryEntry>,
 }
 
+impl DirectoryEntry {
+    /// Iterates over this entry and all of its descendants depth-first
+    ///
+    /// # Returns
+    /// An iterator yielding each entry with its depth (0 for this entry)
+    pub fn iter(&self) -> DirectoryEntryIter<'_> {
+        DirectoryEntryIter {
+            stack: vec![(0, self)],
+        }
+    }
+    
+    /// Finds the entry with the given path in this subtree
+    ///
+    /// # Arguments
+    /// * `path` - Full path of the entry to find
+    ///
+    /// # Returns
+    /// The matching entry, or `None` if it is not in this subtree
+    // Part of the public traversal API; the app looks files up in its list, so only the tests call it yet
+    #[allow(dead_code)]
+    pub fn find_by_path(&self, path: &Path) -> Option<&DirectoryEntry> {
+        if self.path == path {
+            return Some(self);
+        }
+        
+        // Only descend into the child that is an ancestor of the path
+        self.children.iter()
+            .filter(|child| path.starts_with(&child.path))
+            .find_map(|child| child.find_by_path(path))
+    }
+    
+    /// Counts the files below this entry at any depth
+    ///
+    /// # Returns
+    /// The number of files in this subtree (1 for a file entry)
+    pub fn descendant_file_count(&self) -> usize {
+        self.iter().filter(|(_, entry)| !entry.is_directory).count()
+    }
+    
+    /// Finds the directories below this entry that contain no files
+    ///
+    /// Only the topmost empty directory of an empty subtree is listed, so
+    /// `a/` is reported instead of both `a/` and `a/b/`.
+    ///
+    /// # Returns
+    /// The empty directories in depth-first order, excluding this entry
+    pub fn empty_directories(&self) -> Vec<&DirectoryEntry> {
+        let mut empty = Vec::new();
+        for child in self.children.iter().filter(|child| child.is_directory) {
+            if child.descendant_file_count() == 0 {
+                empty.push(child);
+            } else {
+                empty.extend(child.empty_directories());
+            }
+        }
+        empty
+    }
+    
+    /// Drops the files below this entry that are not in `keep`
+    ///
+    /// Directories left without files are dropped as well, so the tree only
+    /// holds the kept files and the folders leading to them.
+    ///
+    /// # Arguments
+    /// * `keep` - Full paths of the files to keep
+    pub fn retain_files(&mut self, keep: &HashSet<PathBuf>) {
+        for child in self.children.iter_mut().filter(|child| child.is_directory) {
+            child.retain_files(keep);
+        }
+        self.children.retain(|child| if child.is_directory {
+            !child.children.is_empty()
+        } else {
+            keep.contains(&child.path)
+        });
+    }
+}
+
+/// Depth-first iterator over a directory tree, created by [`DirectoryEntry::iter`]
+pub struct DirectoryEntryIter<'a> {
+    /// Entries still to visit, paired with their depth
+    stack: Vec<(usize, &'a DirectoryEntry)>,
+}
+
+impl<'a> Iterator for DirectoryEntryIter<'a> {
+    type Item = (usize, &'a DirectoryEntry);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, entry) = self.stack.pop()?;
+        
+        // Push children in reverse so they are visited in their original order
+        self.stack.extend(entry.children.iter().rev().map(|child| (depth + 1, child)));
+        
+        Some((depth, entry))
+    }
+}
+
+/// How symlinked files contribute to sizes and token counts
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SymlinkMode {
+    /// Count a link's target, unless the target is also listed directly
+    #[default]
+    CountOnce,
+    /// Count the target of every link, even if it is listed elsewhere
+    FollowAll,
+    /// Count every link as a near-empty entry of its own
+    CountAsLinks,
+}
+
+impl SymlinkMode {
+    /// Gets the name shown in the settings
+    ///
+    /// # Returns
+    /// The display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::CountOnce => "Count targets once",
+            Self::FollowAll => "Follow every link",
+            Self::CountAsLinks => "Count as links",
+        }
+    }
+}
+
+/// Set of default ignore patterns a parser starts from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnorePreset {
+    /// Only the `.git` directory
+    Minimal,
+    /// Version control, dependency and build directories most projects share
+    #[default]
+    Standard,
+    /// Also build output, vendored dependencies, lockfiles and images
+    Strict,
+}
+
+impl IgnorePreset {
+    /// Every preset, in the order shown in the settings
+    pub const ALL: [IgnorePreset; 3] = [Self::Minimal, Self::Standard, Self::Strict];
+    
+    /// Gets the name shown in the settings
+    ///
+    /// # Returns
+    /// The display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Minimal => "Minimal",
+            Self::Standard => "Standard",
+            Self::Strict => "Strict",
+        }
+    }
+    
+    /// Gets the ignore patterns of the preset
+    ///
+    /// # Returns
+    /// Bare names and patterns relative to the repository root, in the
+    /// syntax of `DirectoryParser::add_ignore_pattern`
+    pub fn patterns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Minimal => &[".git"],
+            Self::Standard => &[".git", "node_modules", "target", ".DS_Store"],
+            Self::Strict => &[
+                ".git", "node_modules", "target", ".DS_Store",
+                // Build output and caches
+                "build", "dist", "out", ".next", ".gradle", "__pycache__", ".venv", "venv",
+                // Vendored dependencies
+                "vendor", "third_party", "bower_components",
+                // Lockfiles
+                "Cargo.lock", "package-lock.json", "yarn.lock", "pnpm-lock.yaml",
+                "poetry.lock", "Gemfile.lock", "composer.lock", "go.sum",
+                // Images
+                "**/*.png", "**/*.jpg", "**/*.jpeg", "**/*.gif", "**/*.bmp", "**/*.ico", "**/*.webp",
+            ],
+        }
+    }
+}
+
 /// Handles parsing and filtering of directory structures
+#[derive(Clone)]
 pub struct DirectoryParser {
     /// Patterns to ignore when parsing directories
     ignore_patterns: Vec<String>,
+    
+    /// Patterns containing a `/`, compiled to match paths relative to the root
+    anchored_patterns: Vec<Regex>,
+    
+    /// Whether directories left empty by ignore patterns stay in the tree
+    keep_ignored_only_directories: bool,
+    
+    /// How symlinked files contribute to sizes and token counts
+    symlink_mode: SymlinkMode,
+    
+    /// Preset whose patterns are among the ignore patterns, if any
+    ignore_preset: Option<IgnorePreset>,
+    
+    /// Whether `.git/info/exclude` and the global excludes file are honored
+    git_excludes: bool,
+    
+    /// Whether the global excludes file is read along with `.git/info/exclude`
+    global_excludes: bool,
 }
 
-impl DirectoryParser {
-    /// Collects all files recursively into a flat list
-    pub fn get_all_files(&self, entry: &DirectoryEntry) -> Vec<PathBuf> {
-        let mut files = Vec::new();
-        self.collect_files_recursive(entry, &mut files);
-        files
+/// Converts a line of a Git exclude file to an ignore pattern
+///
+/// Negated patterns (`!`) aren't supported and are skipped. Wildcard names
+/// without a `/` match at any depth, as in Git, and a trailing `/` is dropped,
+/// so the pattern also ignores files of that name.
+///
+/// # Arguments
+/// * `line` - The line, in `.gitignore` syntax
+///
+/// # Returns
+/// The pattern, or `None` for blank lines, comments and negations
+fn git_exclude_pattern(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+        return None;
+    }
+    let pattern = line.trim_end_matches('/');
+    if pattern.is_empty() {
+        None
+    } else if pattern.contains('/') {
+        Some(pattern.to_string())
+    } else if pattern.contains(['*', '?']) {
+        Some(format!("**/{}", pattern))
+    } else {
+        Some(pattern.to_string())
     }
+}
 
-    /// Helper method to recursively collect files
-    fn collect_files_recursive(&self, entry: &DirectoryEntry, files: &mut Vec<PathBuf>) {
-        if !entry.is_directory {
-            if !self.should_ignore(&entry.path) {
-                files.push(entry.path.clone());
-            }
-        } else {
-            for child in &entry.children {
-                self.collect_files_recursive(child, files);
+/// Finds the user's global excludes file
+///
+/// Uses `core.excludesFile` from the Git configuration, falling back to Git's
+/// default of `$XDG_CONFIG_HOME/git/ignore` or `~/.config/git/ignore`.
+///
+/// # Returns
+/// The path of the file, which may not exist, or `None` if none can be determined
+fn global_excludes_file() -> Option<PathBuf> {
+    let configured = git2::Config::open_default()
+        .and_then(|config| config.get_path("core.excludesFile"))
+        .ok();
+    configured.or_else(|| {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|home| !home.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_home.join("git").join("ignore"))
+    })
+}
+
+/// Reads the exclude patterns Git applies to a repository besides its `.gitignore` files
+///
+/// Missing or unreadable files are skipped without an error.
+///
+/// # Arguments
+/// * `root_path` - Path to the repository root
+/// * `global` - Whether to read the global excludes file too
+///
+/// # Returns
+/// The patterns of `.git/info/exclude` followed by those of the global excludes file
+pub fn git_exclude_patterns(root_path: &Path, global: bool) -> Vec<String> {
+    let global_file = if global { global_excludes_file() } else { None };
+    let files = [Some(root_path.join(".git").join("info").join("exclude")), global_file];
+    files.into_iter()
+        .flatten()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|contents| contents.lines().filter_map(git_exclude_pattern).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Largest file that can be a Git LFS pointer, per the LFS specification
+pub const LFS_POINTER_MAX_BYTES: u64 = 1024;
+
+/// First line of every Git LFS pointer file
+const LFS_POINTER_HEADER: &str = "version https://git-lfs.github.com/spec/";
+
+/// Reads the size of the real file a Git LFS pointer stands for
+///
+/// Repositories using LFS clone these small text stubs instead of the
+/// content unless LFS fetches it.
+///
+/// # Arguments
+/// * `path` - Path to the file
+///
+/// # Returns
+/// The size in bytes declared by the pointer, or `None` if the file isn't one
+pub fn lfs_pointer_size(path: &Path) -> Option<usize> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > LFS_POINTER_MAX_BYTES {
+        return None;
+    }
+    let contents = std::fs::read_to_string(path).ok()?;
+    if !contents.starts_with(LFS_POINTER_HEADER) {
+        return None;
+    }
+    contents.lines()
+        .find_map(|line| line.strip_prefix("size "))
+        .and_then(|size| size.trim().parse().ok())
+}
+
+/// Compiles an ignore pattern containing a `/` into a regex over relative paths
+///
+/// `*` and `?` match within one path component and `**` matches across
+/// components. A leading `/` is optional, and a trailing `/` or `/**` ignores
+/// the directory itself along with everything below it.
+///
+/// # Arguments
+/// * `pattern` - The ignore pattern, e.g. `src/generated/**`
+///
+/// # Returns
+/// The compiled pattern
+fn compile_anchored_pattern(pattern: &str) -> Regex {
+    let pattern = pattern.trim_start_matches('/');
+    let pattern = pattern.strip_suffix("/**").unwrap_or(pattern).trim_end_matches('/');
+    
+    let mut regex = String::from("^");
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // "**/" also matches no directories at all
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
             }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
         }
     }
+    regex.push('$');
+    
+    // Everything but the wildcards is escaped, so the regex is always valid
+    Regex::new(&regex).expect("escaped ignore pattern")
+}
+
+/// Sorts the children of a directory: directories first, then files, each by
+/// case-insensitive name
+///
+/// Names differing only in case fall back to a case-sensitive comparison, so
+/// the order never depends on how the filesystem listed them.
+///
+/// # Arguments
+/// * `entries` - The children to sort
+fn sort_entries(entries: &mut [DirectoryEntry]) {
+    entries.sort_by(|a, b| {
+        b.is_directory.cmp(&a.is_directory)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+impl DirectoryParser {
+    /// Collects all files recursively into a flat list
+    pub fn get_all_files(&self, entry: &DirectoryEntry) -> Vec<PathBuf> {
+        entry.iter()
+            .filter(|(_, child)| !child.is_directory && !self.should_ignore_in(&entry.path, &child.path))
+            .map(|(_, child)| child.path.clone())
+            .collect()
+    }
 
     /// Creates a new DirectoryParser with default ignore patterns
     ///
     /// # Returns
     /// A new DirectoryParser instance
     pub fn new() -> Self {
-        Self {
-            // Default patterns to ignore
-            ignore_patterns: vec![
-                ".git".to_string(),
-                "node_modules".to_string(),
-                "target".to_string(),
-                ".DS_Store".to_string(),
-            ],
-        }
+        // Default patterns to ignore
+        let mut parser = Self::with_ignore_patterns(Vec::new());
+        parser.set_ignore_preset(IgnorePreset::default());
+        parser.git_excludes = true;
+        parser
+    }
+    
+    /// Creates a DirectoryParser like `new` that never reads the global excludes file
+    ///
+    /// Tests use it so that the Git configuration of the machine running them
+    /// can't hide their files.
+    ///
+    /// # Returns
+    /// A new DirectoryParser instance that only honors `.git/info/exclude`
+    #[cfg(test)]
+    pub fn isolated() -> Self {
+        let mut parser = Self::new();
+        parser.global_excludes = false;
+        parser
     }
     
     /// Creates a new DirectoryParser with custom ignore patterns
@@ -67,27 +416,204 @@ impl DirectoryParser {
     /// # Returns
     /// A new DirectoryParser instance with custom ignore patterns
     pub fn with_ignore_patterns(ignore_patterns: Vec<String>) -> Self {
-        Self {
-            ignore_patterns,
+        let mut parser = Self {
+            ignore_patterns: Vec::new(),
+            anchored_patterns: Vec::new(),
+            keep_ignored_only_directories: false,
+            symlink_mode: SymlinkMode::default(),
+            ignore_preset: None,
+            git_excludes: false,
+            global_excludes: true,
+        };
+        for pattern in ignore_patterns {
+            parser.add_ignore_pattern(pattern);
         }
+        parser
     }
     
     /// Adds an ignore pattern
+    ///
+    /// Bare names such as `generated` are matched against the name of every
+    /// file and directory. Patterns containing a `/`, such as
+    /// `src/generated/**`, are matched against the whole path relative to the
+    /// repository root.
     /// 
     /// # Arguments
     /// * `pattern` - Pattern to ignore
     pub fn add_ignore_pattern(&mut self, pattern: String) {
-        self.ignore_patterns.push(pattern);
+        if pattern.contains('/') {
+            self.anchored_patterns.push(compile_anchored_pattern(&pattern));
+        } else {
+            self.ignore_patterns.push(pattern);
+        }
+    }
+    
+    /// Replaces the patterns of the current ignore preset with those of another
+    ///
+    /// Patterns added with `add_ignore_pattern` are kept, so a project's own
+    /// patterns survive switching presets.
+    ///
+    /// # Arguments
+    /// * `preset` - The preset to ignore from now on
+    pub fn set_ignore_preset(&mut self, preset: IgnorePreset) {
+        if let Some(previous) = self.ignore_preset.take() {
+            for pattern in previous.patterns() {
+                self.remove_ignore_pattern(pattern);
+            }
+        }
+        for pattern in preset.patterns() {
+            self.add_ignore_pattern(pattern.to_string());
+        }
+        self.ignore_preset = Some(preset);
+    }
+    
+    /// Gets the ignore preset the parser started from
+    ///
+    /// # Returns
+    /// The preset, or `None` for a parser created with only custom patterns
+    pub fn ignore_preset(&self) -> Option<IgnorePreset> {
+        self.ignore_preset
+    }
+    
+    /// Removes one occurrence of an ignore pattern, if present
+    ///
+    /// # Arguments
+    /// * `pattern` - Pattern as it was added
+    fn remove_ignore_pattern(&mut self, pattern: &str) {
+        if pattern.contains('/') {
+            let compiled = compile_anchored_pattern(pattern);
+            if let Some(index) = self.anchored_patterns.iter().position(|regex| regex.as_str() == compiled.as_str()) {
+                self.anchored_patterns.remove(index);
+            }
+        } else if let Some(index) = self.ignore_patterns.iter().position(|existing| existing == pattern) {
+            self.ignore_patterns.remove(index);
+        }
+    }
+    
+    /// Sets whether directories whose contents are all ignored are kept
+    ///
+    /// By default such directories, e.g. one holding only `node_modules`, are
+    /// pruned from parsed trees. Directories that are empty on disk are always kept.
+    ///
+    /// # Arguments
+    /// * `keep` - Whether to keep them as empty directories
+    pub fn set_keep_ignored_only_directories(&mut self, keep: bool) {
+        self.keep_ignored_only_directories = keep;
+    }
+    
+    /// Gets whether directories whose contents are all ignored are kept
+    ///
+    /// # Returns
+    /// True if they are kept as empty directories
+    pub fn keeps_ignored_only_directories(&self) -> bool {
+        self.keep_ignored_only_directories
     }
     
-    /// Parses a directory structure
+    /// Sets whether `.git/info/exclude` and the global excludes file are honored
+    ///
+    /// Parsers from `new` honor them, so ignored files match `git status`.
+    /// The files are read on every parse.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to add their patterns when parsing
+    pub fn set_git_excludes(&mut self, enabled: bool) {
+        self.git_excludes = enabled;
+    }
+    
+    /// Gets whether `.git/info/exclude` and the global excludes file are honored
+    ///
+    /// # Returns
+    /// True if their patterns are added when parsing
+    pub fn uses_git_excludes(&self) -> bool {
+        self.git_excludes
+    }
+    
+    /// Sets how symlinked files contribute to sizes and token counts
+    ///
+    /// # Arguments
+    /// * `mode` - The symlink mode
+    pub fn set_symlink_mode(&mut self, mode: SymlinkMode) {
+        self.symlink_mode = mode;
+    }
+    
+    /// Gets how symlinked files contribute to sizes and token counts
+    ///
+    /// # Returns
+    /// The symlink mode
+    pub fn symlink_mode(&self) -> SymlinkMode {
+        self.symlink_mode
+    }
+    
+    /// Finds the symlinked files that count as links rather than as their targets
+    ///
+    /// With `CountOnce` these are links whose target is also a regular file in
+    /// the tree, so the target isn't counted twice.
+    ///
+    /// # Arguments
+    /// * `root` - The parsed directory structure
+    ///
+    /// # Returns
+    /// Paths of the files to count as links
+    pub fn link_only_files(&self, root: &DirectoryEntry) -> HashSet<PathBuf> {
+        let files = self.get_all_files(root);
+        let is_link = |path: &PathBuf| std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink());
+        
+        match self.symlink_mode {
+            SymlinkMode::FollowAll => HashSet::new(),
+            SymlinkMode::CountAsLinks => files.into_iter().filter(is_link).collect(),
+            SymlinkMode::CountOnce => {
+                let (links, regular): (Vec<PathBuf>, Vec<PathBuf>) = files.into_iter().partition(is_link);
+                let regular: HashSet<PathBuf> = regular.iter()
+                    .filter_map(|path| path.canonicalize().ok())
+                    .collect();
+                links.into_iter()
+                    .filter(|link| link.canonicalize().is_ok_and(|target| regular.contains(&target)))
+                    .collect()
+            }
+        }
+    }
+    
+    /// Parses a directory structure, leaving out entries that can't be read
     ///
     /// # Arguments
     /// * `root_path` - Path to the root directory
     ///
     /// # Returns
     /// Result with the parsed directory structure or an error
+    #[cfg(test)]
     pub fn parse_directory(&self, root_path: &Path) -> Result<DirectoryEntry, String> {
+        self.parse_directory_with_warnings(root_path)
+            .map(|(entry, _)| entry)
+    }
+    
+    /// Parses a directory structure and finds the files that count as links
+    ///
+    /// Both touch the file system for every file, so this is meant to run on
+    /// a worker thread rather than the UI thread.
+    ///
+    /// # Arguments
+    /// * `root_path` - Path to the root directory
+    ///
+    /// # Returns
+    /// Result with the parsed repository, or an error if the root itself can't be read
+    pub fn parse_repository(&self, root_path: &Path) -> Result<ParsedRepository, String> {
+        let (root_entry, warnings) = self.parse_directory_with_warnings(root_path)?;
+        let link_only = self.link_only_files(&root_entry);
+        Ok(ParsedRepository { root_entry, warnings, link_only })
+    }
+    
+    /// Parses a directory structure, reporting entries that can't be read
+    ///
+    /// A subdirectory or file that can't be read, e.g. for lack of permission,
+    /// is left out of the tree instead of failing the whole parse.
+    ///
+    /// # Arguments
+    /// * `root_path` - Path to the root directory
+    ///
+    /// # Returns
+    /// Result with the parsed directory structure and a message for each
+    /// skipped entry, or an error if the root itself can't be read
+    pub fn parse_directory_with_warnings(&self, root_path: &Path) -> Result<(DirectoryEntry, Vec<String>), String> {
         if !root_path.exists() {
             return Err(format!("Path does not exist: {:?}", root_path));
         }
@@ -102,48 +628,73 @@ impl DirectoryParser {
             .unwrap_or("root")
             .to_string();
         
-        // Recursively parse the directory structure
-        self.parse_directory_recursive(root_path, &root_name)
+        // Git's exclude files apply to this repository only, so they go into a copy
+        let mut excluding = None;
+        if self.git_excludes {
+            let patterns = git_exclude_patterns(root_path, self.global_excludes);
+            if !patterns.is_empty() {
+                let mut parser = self.clone();
+                for pattern in patterns {
+                    parser.add_ignore_pattern(pattern);
+                }
+                excluding = Some(parser);
+            }
+        }
+        let parser = excluding.as_ref().unwrap_or(self);
+        
+        // Recursively parse the directory structure; the root is kept even if it ends up empty
+        let mut warnings = Vec::new();
+        parser.parse_directory_recursive(root_path, root_path, &root_name, &mut warnings)
+            .map(|(entry, _)| (entry, warnings))
+            .map_err(|e| format!("Failed to read directory: {}", e))
     }
     
     /// Recursively parses a directory structure
     ///
     /// # Arguments
+    /// * `root_path` - Path to the root directory, for anchored ignore patterns
     /// * `dir_path` - Path to the directory
     /// * `dir_name` - Name of the directory
+    /// * `warnings` - Collects a message for each entry below it that can't be read
     ///
     /// # Returns
-    /// Result with the parsed directory structure or an error
-    fn parse_directory_recursive(&self, dir_path: &Path, dir_name: &str) -> Result<DirectoryEntry, String> {
+    /// Result with the parsed directory structure and whether anything below
+    /// it was ignored, or the error reading the directory itself
+    fn parse_directory_recursive(&self, root_path: &Path, dir_path: &Path, dir_name: &str, warnings: &mut Vec<String>) -> std::io::Result<(DirectoryEntry, bool)> {
         // Create a vector to store child entries
         let mut children = Vec::new();
+        let mut ignored_any = false;
         
         // Read the directory entries
-        let entries = match std::fs::read_dir(dir_path) {
-            Ok(entries) => entries,
-            Err(e) => return Err(format!("Failed to read directory: {}", e)),
-        };
+        let entries = std::fs::read_dir(dir_path)?;
         
         // Process each entry
         for entry_result in entries {
             // Get the directory entry
             let entry = match entry_result {
                 Ok(entry) => entry,
-                Err(e) => return Err(format!("Failed to read directory entry: {}", e)),
+                Err(e) => {
+                    warnings.push(format!("Skipped an entry of {}: {}", dir_path.display(), e));
+                    continue;
+                }
             };
             
             // Get the path of the entry
             let path = entry.path();
             
             // Skip if the entry should be ignored
-            if self.should_ignore(&path) {
+            if self.should_ignore_in(root_path, &path) {
+                ignored_any = true;
                 continue;
             }
             
             // Get the metadata of the entry
             let metadata = match entry.metadata() {
                 Ok(metadata) => metadata,
-                Err(e) => return Err(format!("Failed to get metadata: {}", e)),
+                Err(e) => {
+                    warnings.push(format!("Skipped {}: {}", path.display(), e));
+                    continue;
+                }
             };
             
             // Get the name of the entry
@@ -155,10 +706,20 @@ impl DirectoryParser {
             // Create a DirectoryEntry for the entry
             if metadata.is_dir() {
                 // Recursively parse subdirectories
-                match self.parse_directory_recursive(&path, &name) {
-                    Ok(child_entry) => children.push(child_entry),
-                    Err(e) => return Err(e),
+                let (child_entry, child_ignored_any) = match self.parse_directory_recursive(root_path, &path, &name, warnings) {
+                    Ok(parsed) => parsed,
+                    Err(e) => {
+                        warnings.push(format!("Skipped {}: {}", path.display(), e));
+                        continue;
+                    }
+                };
+                ignored_any |= child_ignored_any;
+                
+                // Drop directories that only held ignored content
+                if child_ignored_any && child_entry.children.is_empty() && !self.keep_ignored_only_directories {
+                    continue;
                 }
+                children.push(child_entry);
             } else {
                 // Add file entry
                 children.push(DirectoryEntry {
@@ -170,16 +731,22 @@ impl DirectoryParser {
             }
         }
         
+        // `read_dir` order depends on the filesystem, so sort for stable output
+        sort_entries(&mut children);
+        
         // Create and return the DirectoryEntry for this directory
-        Ok(DirectoryEntry {
+        Ok((DirectoryEntry {
             name: dir_name.to_string(),
             path: dir_path.to_path_buf(),
             is_directory: true,
             children,
-        })
+        }, ignored_any))
     }
     
     /// Checks if a path should be ignored
+    ///
+    /// Bare patterns must equal the whole file or directory name, so `target`
+    /// ignores `target/` but not `my_target_notes.md`.
     /// 
     /// # Arguments
     /// * `path` - Path to check
@@ -189,17 +756,138 @@ impl DirectoryParser {
     fn should_ignore(&self, path: &Path) -> bool {
         if let Some(file_name) = path.file_name() {
             if let Some(file_name_str) = file_name.to_str() {
-                return self.ignore_patterns.iter().any(|pattern| {
-                    file_name_str == pattern || file_name_str.contains(pattern)
-                });
+                return self.ignore_patterns.iter().any(|pattern| file_name_str == pattern);
             }
         }
         
         false
     }
     
+    /// Checks if a path matches an ignore pattern, either by name or by its
+    /// path relative to the repository root
+    ///
+    /// Parent directories are not checked; see `is_ignored` for that.
+    ///
+    /// # Arguments
+    /// * `root` - Path to the repository root
+    /// * `path` - Path to check
+    ///
+    /// # Returns
+    /// `true` if the path should be ignored, `false` otherwise
+    fn should_ignore_in(&self, root: &Path, path: &Path) -> bool {
+        if self.should_ignore(path) {
+            return true;
+        }
+        
+        match path.strip_prefix(root) {
+            Ok(relative) => self.matches_anchored(relative),
+            Err(_) => false,
+        }
+    }
+    
+    /// Checks a path relative to the repository root against the patterns
+    /// containing a `/`
+    ///
+    /// # Arguments
+    /// * `relative` - Path relative to the repository root
+    ///
+    /// # Returns
+    /// `true` if an anchored pattern matches, `false` otherwise
+    fn matches_anchored(&self, relative: &Path) -> bool {
+        if self.anchored_patterns.is_empty() || relative.as_os_str().is_empty() {
+            return false;
+        }
+        
+        // Patterns always use `/`, whatever the platform separator is
+        let relative = relative.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.anchored_patterns.iter().any(|pattern| pattern.is_match(&relative))
+    }
+    
+    /// Checks if a path inside a repository is ignored, either itself or
+    /// through one of its parent directories
+    ///
+    /// # Arguments
+    /// * `root` - Path to the repository root
+    /// * `path` - Path to check
+    ///
+    /// # Returns
+    /// `true` if the path is ignored or lies outside `root`, `false` otherwise
+    pub fn is_ignored(&self, root: &Path, path: &Path) -> bool {
+        path.strip_prefix(root).map_or(true, |relative| {
+            relative.ancestors().any(|ancestor| {
+                self.should_ignore(ancestor) || self.matches_anchored(ancestor)
+            })
+        })
+    }
+    
+    /// Renders a directory structure in the format of the `tree` command
+    ///
+    /// Entries are sorted by name, with ignored entries left out, and a
+    /// `N directories, M files` line is added at the end.
+    ///
+    /// # Arguments
+    /// * `root` - The directory entry to render
+    /// * `title` - Name shown on the first line, e.g. the repository name
+    ///
+    /// # Returns
+    /// The tree as text, one entry per line
+    pub fn tree_text(&self, root: &DirectoryEntry, title: &str) -> String {
+        let mut text = format!("{}\n", title);
+        let (directories, files) = self.tree_text_recursive(&root.path, root, "", &mut text);
+        text.push_str(&format!(
+            "\n{} {}, {} {}\n",
+            directories, if directories == 1 { "directory" } else { "directories" },
+            files, if files == 1 { "file" } else { "files" }
+        ));
+        text
+    }
+    
+    /// Appends the children of an entry to a `tree` rendering
+    ///
+    /// # Arguments
+    /// * `root` - Path to the repository root, for ignore patterns
+    /// * `entry` - The directory whose children are rendered
+    /// * `prefix` - Indentation drawn before each child
+    /// * `text` - The text to append to
+    ///
+    /// # Returns
+    /// The number of directories and files rendered
+    fn tree_text_recursive(&self, root: &Path, entry: &DirectoryEntry, prefix: &str, text: &mut String) -> (usize, usize) {
+        let mut children: Vec<&DirectoryEntry> = entry.children.iter()
+            .filter(|child| !self.should_ignore_in(root, &child.path))
+            .collect();
+        children.sort_by(|a, b| a.name.cmp(&b.name));
+        
+        let (mut directories, mut files) = (0, 0);
+        for (i, child) in children.iter().enumerate() {
+            let is_last = i == children.len() - 1;
+            text.push_str(prefix);
+            text.push_str(if is_last { "└── " } else { "├── " });
+            text.push_str(&child.name);
+            text.push('\n');
+            
+            if child.is_directory {
+                directories += 1;
+                let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+                let (child_directories, child_files) = self.tree_text_recursive(root, child, &child_prefix, text);
+                directories += child_directories;
+                files += child_files;
+            } else {
+                files += 1;
+            }
+        }
+        (directories, files)
+    }
+    
     /// Gets statistics for a directory structure
     ///
+    /// Symlinked files count their target's size, or the link's own size
+    /// depending on the symlink mode. Directories add nothing to the total
+    /// size; it is made up of the files they contain.
+    ///
     /// # Arguments
     /// * `entry` - The directory entry to analyze
     ///
@@ -214,53 +902,52 @@ impl DirectoryParser {
             file_types: HashMap::new(),
         };
         
-        // Calculate statistics recursively
-        self.calculate_statistics_recursive(entry, &mut stats, 0);
-        
-        stats
-    }
-    
-    /// Recursively calculates statistics for a directory structure
-    ///
-    /// # Arguments
-    /// * `entry` - The directory entry to analyze
-    /// * `stats` - The statistics to update
-    /// * `depth` - The current depth in the directory tree
-    fn calculate_statistics_recursive(&self, entry: &DirectoryEntry, stats: &mut DirectoryStatistics, depth: usize) {
-        // Update max depth
-        if depth > stats.max_depth {
-            stats.max_depth = depth;
-        }
-        
-        if entry.is_directory {
-            // Count this directory
-            stats.total_directories += 1;
+        let link_only = self.link_only_files(entry);
+        for (depth, child) in entry.iter() {
+            // Update max depth
+            stats.max_depth = stats.max_depth.max(depth);
             
-            // Recursively process children
-            for child in &entry.children {
-                self.calculate_statistics_recursive(child, stats, depth + 1);
+            if child.is_directory {
+                // Count this directory
+                stats.total_directories += 1;
+                continue;
             }
-        } else {
+            
             // Count this file
             stats.total_files += 1;
             
             // Get file size
-            if let Ok(metadata) = std::fs::metadata(&entry.path) {
+            let metadata = if link_only.contains(&child.path) {
+                std::fs::symlink_metadata(&child.path)
+            } else {
+                std::fs::metadata(&child.path)
+            };
+            if let Ok(metadata) = metadata {
                 stats.total_size_bytes += metadata.len();
             }
             
             // Extract file extension
-            if let Some(extension) = entry.path.extension() {
-                if let Some(ext_str) = extension.to_str() {
-                    let count = stats.file_types.entry(ext_str.to_string())
-                        .or_insert(0);
-                    *count += 1;
-                }
+            if let Some(ext_str) = child.path.extension().and_then(|extension| extension.to_str()) {
+                *stats.file_types.entry(ext_str.to_string()).or_insert(0) += 1;
             }
         }
+        
+        stats
     }
 }
 
+/// A parsed directory structure with what counting its files needs to know
+pub struct ParsedRepository {
+    /// The parsed directory structure
+    pub root_entry: DirectoryEntry,
+    
+    /// Messages for the entries that couldn't be read
+    pub warnings: Vec<String>,
+    
+    /// Symlinked files that count as links rather than as their targets
+    pub link_only: HashSet<PathBuf>,
+}
+
 /// Statistics for a directory structure
 pub struct DirectoryStatistics {
     /// Total number of files
@@ -280,7 +967,7 @@ pub struct DirectoryStatistics {
 }
 
 // Import HashMap for DirectoryStatistics
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[cfg(test)]
 mod tests {
@@ -288,7 +975,7 @@ mod tests {
     
     #[test]
     fn test_should_ignore() {
-        let parser = DirectoryParser::new();
+        let parser = DirectoryParser::isolated();
         
         // Should ignore .git directory
         assert!(parser.should_ignore(Path::new(".git")));
@@ -303,6 +990,24 @@ mod tests {
         assert!(!parser.should_ignore(Path::new("/path/to/src")));
     }
     
+    #[test]
+    fn test_should_ignore_whole_names_only() {
+        let parser = DirectoryParser::isolated();
+        
+        // Names that merely contain a default pattern are kept
+        assert!(!parser.should_ignore(Path::new("my_target_notes.md")));
+        assert!(!parser.should_ignore(Path::new("/path/to/targets")));
+        assert!(!parser.should_ignore(Path::new("/path/to/.gitignore")));
+        assert!(!parser.should_ignore(Path::new("/path/to/.github")));
+        assert!(!parser.should_ignore(Path::new("old_node_modules_backup")));
+        assert!(parser.should_ignore(Path::new("/path/to/target")));
+        
+        let parser = DirectoryParser::with_ignore_patterns(vec!["test".to_string()]);
+        assert!(!parser.should_ignore(Path::new("contest.rs")));
+        assert!(!parser.should_ignore(Path::new("tests")));
+        assert!(parser.should_ignore(Path::new("src/test")));
+    }
+    
     #[test]
     fn test_custom_ignore_patterns() {
         let parser = DirectoryParser::with_ignore_patterns(vec![
@@ -319,9 +1024,437 @@ mod tests {
         assert!(!parser.should_ignore(Path::new("node_modules")));
     }
     
+    #[test]
+    fn test_git_info_exclude() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git").join("info")).unwrap();
+        std::fs::write(
+            dir.path().join(".git").join("info").join("exclude"),
+            "# local notes\nsecret.txt\n*.log\n!keep.log\nscratch/\n",
+        ).unwrap();
+        for name in ["main.rs", "secret.txt", "debug.log", "keep.log"] {
+            std::fs::write(dir.path().join(name), "x").unwrap();
+        }
+        std::fs::create_dir(dir.path().join("scratch")).unwrap();
+        std::fs::write(dir.path().join("scratch").join("notes.md"), "x").unwrap();
+        
+        let mut parser = DirectoryParser::isolated();
+        let root = parser.parse_directory(dir.path()).unwrap();
+        let names: Vec<String> = parser.get_all_files(&root).iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        // Negations aren't supported, so keep.log stays excluded by *.log
+        assert!(names.contains(&"main.rs".to_string()));
+        assert!(!names.contains(&"secret.txt".to_string()));
+        assert!(!names.contains(&"debug.log".to_string()));
+        assert!(!names.contains(&"keep.log".to_string()));
+        assert!(!names.contains(&"notes.md".to_string()));
+        
+        // Turned off, the exclude file is not read
+        parser.set_git_excludes(false);
+        let root = parser.parse_directory(dir.path()).unwrap();
+        assert_eq!(parser.get_all_files(&root).len(), 5);
+        
+        // A missing exclude file is skipped
+        std::fs::remove_dir_all(dir.path().join(".git")).unwrap();
+        parser.set_git_excludes(true);
+        assert!(parser.parse_directory(dir.path()).is_ok());
+    }
+    
+    #[test]
+    fn test_lfs_pointer_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let pointer = dir.path().join("model.bin");
+        std::fs::write(&pointer, "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "version 2 of the notes\nsize 5\n").unwrap();
+        
+        assert_eq!(lfs_pointer_size(&pointer), Some(12345));
+        assert_eq!(lfs_pointer_size(&dir.path().join("notes.txt")), None);
+        
+        // Files larger than a pointer can be aren't read
+        std::fs::write(dir.path().join("big.txt"), format!("{}{}", LFS_POINTER_HEADER, "x".repeat(2000))).unwrap();
+        assert_eq!(lfs_pointer_size(&dir.path().join("big.txt")), None);
+    }
+    
+    #[test]
+    fn test_git_exclude_pattern() {
+        assert_eq!(git_exclude_pattern("build/"), Some("build".to_string()));
+        assert_eq!(git_exclude_pattern("/docs/*.pdf"), Some("/docs/*.pdf".to_string()));
+        assert_eq!(git_exclude_pattern("*.swp  "), Some("**/*.swp".to_string()));
+        assert_eq!(git_exclude_pattern("# comment"), None);
+        assert_eq!(git_exclude_pattern("!important.log"), None);
+        assert_eq!(git_exclude_pattern("   "), None);
+    }
+    
+    #[test]
+    fn test_ignore_presets() {
+        let root = Path::new("/repo");
+        let mut parser = DirectoryParser::isolated();
+        assert_eq!(parser.ignore_preset(), Some(IgnorePreset::Standard));
+        parser.add_ignore_pattern("target".to_string());
+        parser.add_ignore_pattern("docs/generated".to_string());
+        
+        parser.set_ignore_preset(IgnorePreset::Strict);
+        assert!(parser.is_ignored(root, Path::new("/repo/Cargo.lock")));
+        assert!(parser.is_ignored(root, Path::new("/repo/assets/logo.png")));
+        assert!(parser.is_ignored(root, Path::new("/repo/vendor/lib.c")));
+        
+        // Switching drops the preset's patterns but keeps the added ones
+        parser.set_ignore_preset(IgnorePreset::Minimal);
+        assert!(parser.is_ignored(root, Path::new("/repo/.git/config")));
+        assert!(!parser.is_ignored(root, Path::new("/repo/Cargo.lock")));
+        assert!(!parser.is_ignored(root, Path::new("/repo/assets/logo.png")));
+        assert!(!parser.is_ignored(root, Path::new("/repo/node_modules/a.js")));
+        assert!(parser.is_ignored(root, Path::new("/repo/target/debug")));
+        assert!(parser.is_ignored(root, Path::new("/repo/docs/generated/a.md")));
+        assert_eq!(parser.ignore_preset(), Some(IgnorePreset::Minimal));
+    }
+    
+    #[test]
+    fn test_children_sorted_deterministically() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.rs", "README.md", "a.rs", "Zeta.txt"] {
+            std::fs::write(dir.path().join(name), "x").unwrap();
+        }
+        for name in ["src", "Docs", "assets"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+            std::fs::write(dir.path().join(name).join("keep.txt"), "x").unwrap();
+        }
+        
+        // Directories come first, then files, each ignoring case
+        let root = DirectoryParser::isolated().parse_directory(dir.path()).unwrap();
+        let names: Vec<&str> = root.children.iter().map(|child| child.name.as_str()).collect();
+        assert_eq!(names, vec!["assets", "Docs", "src", "a.rs", "b.rs", "README.md", "Zeta.txt"]);
+        
+        // Names differing only in case still get a fixed order
+        let entry = |name: &str| DirectoryEntry {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            is_directory: false,
+            children: Vec::new(),
+        };
+        let mut entries = vec![entry("readme"), entry("README"), entry("Readme")];
+        sort_entries(&mut entries);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["README", "Readme", "readme"]);
+    }
+    
+    /// Builds a small tree: root/{a.rs, src/{lib.rs, util/{mod.rs}}, empty/}
+    fn sample_tree() -> DirectoryEntry {
+        fn file(path: &str) -> DirectoryEntry {
+            let path = PathBuf::from(path);
+            DirectoryEntry {
+                name: path.file_name().unwrap().to_string_lossy().to_string(),
+                path,
+                is_directory: false,
+                children: Vec::new(),
+            }
+        }
+        fn dir(path: &str, children: Vec<DirectoryEntry>) -> DirectoryEntry {
+            DirectoryEntry {
+                is_directory: true,
+                children,
+                ..file(path)
+            }
+        }
+        
+        dir("/root", vec![
+            file("/root/a.rs"),
+            dir("/root/src", vec![
+                file("/root/src/lib.rs"),
+                dir("/root/src/util", vec![file("/root/src/util/mod.rs")]),
+            ]),
+            dir("/root/empty", vec![]),
+        ])
+    }
+    
+    #[test]
+    fn test_iter_depth_first() {
+        let tree = sample_tree();
+        let visited: Vec<(usize, &str)> = tree.iter()
+            .map(|(depth, entry)| (depth, entry.name.as_str()))
+            .collect();
+        
+        assert_eq!(visited, vec![
+            (0, "root"),
+            (1, "a.rs"),
+            (1, "src"),
+            (2, "lib.rs"),
+            (2, "util"),
+            (3, "mod.rs"),
+            (1, "empty"),
+        ]);
+    }
+    
+    #[test]
+    fn test_find_by_path() {
+        let tree = sample_tree();
+        
+        assert_eq!(tree.find_by_path(Path::new("/root")).unwrap().name, "root");
+        assert_eq!(tree.find_by_path(Path::new("/root/src/util/mod.rs")).unwrap().name, "mod.rs");
+        assert!(tree.find_by_path(Path::new("/root/src/util")).unwrap().is_directory);
+        
+        // Missing paths and paths outside the tree
+        assert!(tree.find_by_path(Path::new("/root/src/missing.rs")).is_none());
+        assert!(tree.find_by_path(Path::new("/other/a.rs")).is_none());
+    }
+    
+    #[test]
+    fn test_descendant_file_count() {
+        let tree = sample_tree();
+        
+        assert_eq!(tree.descendant_file_count(), 3);
+        assert_eq!(tree.find_by_path(Path::new("/root/src")).unwrap().descendant_file_count(), 2);
+        assert_eq!(tree.find_by_path(Path::new("/root/empty")).unwrap().descendant_file_count(), 0);
+        assert_eq!(tree.find_by_path(Path::new("/root/a.rs")).unwrap().descendant_file_count(), 1);
+    }
+    
+    #[test]
+    fn test_statistics_size_only_counts_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src").join("nested")).unwrap();
+        std::fs::create_dir(dir.path().join("empty")).unwrap();
+        std::fs::write(dir.path().join("src").join("nested").join("a.rs"), "0123456789").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "abc").unwrap();
+        
+        let parser = DirectoryParser::isolated();
+        let statistics = parser.get_statistics(&parser.parse_directory(dir.path()).unwrap());
+        assert_eq!(statistics.total_size_bytes, 13);
+    }
+    
+    #[test]
+    fn test_empty_directories() {
+        let mut tree = sample_tree();
+        let names = |tree: &DirectoryEntry| tree.empty_directories().iter()
+            .map(|entry| entry.name.clone())
+            .collect::<Vec<_>>();
+        
+        assert_eq!(names(&tree), vec!["empty"]);
+        
+        // A directory holding only empty directories is reported once, at the top
+        tree.children[2].children.push(DirectoryEntry {
+            name: "nested".to_string(),
+            path: PathBuf::from("/root/empty/nested"),
+            is_directory: true,
+            children: Vec::new(),
+        });
+        assert_eq!(names(&tree), vec!["empty"]);
+        
+        // Empty directories inside non-empty ones are found too
+        tree.children[1].children.push(DirectoryEntry {
+            name: "fixtures".to_string(),
+            path: PathBuf::from("/root/src/fixtures"),
+            is_directory: true,
+            children: Vec::new(),
+        });
+        assert_eq!(names(&tree), vec!["fixtures", "empty"]);
+    }
+    
+    #[test]
+    fn test_retain_files() {
+        let mut tree = sample_tree();
+        let keep: HashSet<PathBuf> = [PathBuf::from("/root/src/util/mod.rs")].into_iter().collect();
+        
+        tree.retain_files(&keep);
+        let paths: Vec<PathBuf> = tree.iter().map(|(_, entry)| entry.path.clone()).collect();
+        assert_eq!(paths, vec![
+            PathBuf::from("/root"),
+            PathBuf::from("/root/src"),
+            PathBuf::from("/root/src/util"),
+            PathBuf::from("/root/src/util/mod.rs"),
+        ]);
+        
+        tree.retain_files(&HashSet::new());
+        assert!(tree.children.is_empty());
+    }
+    
+    #[test]
+    fn test_tree_text() {
+        let tree = sample_tree();
+        
+        assert_eq!(DirectoryParser::isolated().tree_text(&tree, "root"), "\
+root
+├── a.rs
+├── empty
+└── src
+    ├── lib.rs
+    └── util
+        └── mod.rs
+
+3 directories, 3 files
+");
+        
+        // Ignored entries are left out
+        let parser = DirectoryParser::with_ignore_patterns(vec!["util".to_string(), "empty".to_string()]);
+        assert_eq!(parser.tree_text(&tree, "root"), "\
+root
+├── a.rs
+└── src
+    └── lib.rs
+
+1 directory, 2 files
+");
+    }
+    
+    #[test]
+    fn test_is_ignored() {
+        let parser = DirectoryParser::isolated();
+        let root = Path::new("/repo");
+        
+        // Ignored through a parent directory
+        assert!(parser.is_ignored(root, Path::new("/repo/.git/index")));
+        assert!(parser.is_ignored(root, Path::new("/repo/web/node_modules/pkg/index.js")));
+        
+        // Only components inside the repository are checked
+        assert!(!parser.is_ignored(Path::new("/target/repo"), Path::new("/target/repo/src/main.rs")));
+        
+        // Paths outside the repository are ignored
+        assert!(parser.is_ignored(root, Path::new("/other/src/main.rs")));
+    }
+    
+    #[test]
+    fn test_anchored_ignore_patterns() {
+        let root = Path::new("/repo");
+        
+        // A bare name is ignored anywhere in the tree
+        let parser = DirectoryParser::with_ignore_patterns(vec!["generated".to_string()]);
+        assert!(parser.is_ignored(root, Path::new("/repo/src/generated/api.rs")));
+        assert!(parser.is_ignored(root, Path::new("/repo/tests/generated/fixture.rs")));
+        
+        // A pattern with a separator only matches from the root
+        let parser = DirectoryParser::with_ignore_patterns(vec!["src/generated".to_string()]);
+        assert!(parser.is_ignored(root, Path::new("/repo/src/generated/api.rs")));
+        assert!(!parser.is_ignored(root, Path::new("/repo/tests/generated/fixture.rs")));
+        assert!(!parser.is_ignored(root, Path::new("/repo/lib/src/generated/api.rs")));
+        
+        // "/**", a leading "/" and wildcards
+        for pattern in ["src/generated/**", "/src/generated/", "src/gen*"] {
+            let parser = DirectoryParser::with_ignore_patterns(vec![pattern.to_string()]);
+            assert!(parser.is_ignored(root, Path::new("/repo/src/generated/api.rs")), "{}", pattern);
+            assert!(!parser.is_ignored(root, Path::new("/repo/src/main.rs")), "{}", pattern);
+        }
+        
+        let parser = DirectoryParser::with_ignore_patterns(vec!["**/fixtures/*.json".to_string()]);
+        assert!(parser.is_ignored(root, Path::new("/repo/fixtures/a.json")));
+        assert!(parser.is_ignored(root, Path::new("/repo/tests/unit/fixtures/a.json")));
+        assert!(!parser.is_ignored(root, Path::new("/repo/tests/fixtures/a.rs")));
+        assert!(!parser.is_ignored(root, Path::new("/repo/tests/fixtures/nested/a.rs")));
+    }
+    
+    #[test]
+    fn test_parse_with_anchored_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["src/generated/api.rs", "src/main.rs", "tests/generated/fixture.rs"] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "fn main() {}").unwrap();
+        }
+        
+        let parser = DirectoryParser::with_ignore_patterns(vec!["src/generated/**".to_string()]);
+        let root_entry = parser.parse_directory(dir.path()).unwrap();
+        let mut files: Vec<PathBuf> = parser.get_all_files(&root_entry).iter()
+            .map(|path| path.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        files.sort();
+        
+        assert_eq!(files, vec![
+            PathBuf::from("src").join("main.rs"),
+            PathBuf::from("tests").join("generated").join("fixture.rs"),
+        ]);
+    }
+    
+    #[test]
+    fn test_prune_ignored_only_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("web").join("node_modules").join("lib")).unwrap();
+        std::fs::write(dir.path().join("web").join("node_modules").join("lib").join("index.js"), "x").unwrap();
+        std::fs::create_dir(dir.path().join("empty")).unwrap();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+        
+        // `web` only held ignored content, so it is pruned; `empty` is empty on disk and stays
+        let mut parser = DirectoryParser::isolated();
+        let root_entry = parser.parse_directory(dir.path()).unwrap();
+        let mut names: Vec<&str> = root_entry.children.iter().map(|child| child.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["empty", "main.rs"]);
+        
+        parser.set_keep_ignored_only_directories(true);
+        let root_entry = parser.parse_directory(dir.path()).unwrap();
+        let web = root_entry.find_by_path(&dir.path().join("web")).unwrap();
+        assert!(web.children.is_empty());
+        
+        // The root is never pruned
+        let only_ignored = tempfile::tempdir().unwrap();
+        std::fs::create_dir(only_ignored.path().join("node_modules")).unwrap();
+        std::fs::write(only_ignored.path().join("node_modules").join("a.js"), "x").unwrap();
+        assert!(DirectoryParser::isolated().parse_directory(only_ignored.path()).unwrap().children.is_empty());
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.txt"), "0123456789").unwrap();
+        std::fs::write(outside.path().join("shared.txt"), "01234").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("data.txt"), dir.path().join("alias.txt")).unwrap();
+        std::os::unix::fs::symlink(outside.path().join("shared.txt"), dir.path().join("shared.txt")).unwrap();
+        let alias = dir.path().join("alias.txt");
+        let alias_size = std::fs::symlink_metadata(&alias).unwrap().len();
+        let shared_size = std::fs::symlink_metadata(dir.path().join("shared.txt")).unwrap().len();
+        
+        // By default only the link to a file that is listed directly counts as a link
+        let mut parser = DirectoryParser::isolated();
+        let root = parser.parse_directory(dir.path()).unwrap();
+        assert_eq!(parser.link_only_files(&root), HashSet::from([alias.clone()]));
+        assert_eq!(parser.parse_repository(dir.path()).unwrap().link_only, HashSet::from([alias.clone()]));
+        assert_eq!(parser.get_statistics(&root).total_size_bytes, 10 + alias_size + 5);
+        
+        parser.set_symlink_mode(SymlinkMode::FollowAll);
+        assert!(parser.link_only_files(&root).is_empty());
+        assert_eq!(parser.get_statistics(&root).total_size_bytes, 10 + 10 + 5);
+        
+        parser.set_symlink_mode(SymlinkMode::CountAsLinks);
+        assert_eq!(parser.link_only_files(&root).len(), 2);
+        assert_eq!(parser.get_statistics(&root).total_size_bytes, 10 + alias_size + shared_size);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_unreadable_directory_is_skipped() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("main.rs"), "fn main() {}").unwrap();
+        let locked = dir.path().join("locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::write(locked.join("secret.rs"), "fn secret() {}").unwrap();
+        
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // Root reads the directory regardless, in which case nothing is skipped
+        let unreadable = std::fs::read_dir(&locked).is_err();
+        let result = DirectoryParser::isolated().parse_directory_with_warnings(dir.path());
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        
+        // The rest of the tree still parses
+        let (root, warnings) = result.unwrap();
+        assert!(root.find_by_path(&dir.path().join("src").join("main.rs")).is_some());
+        if unreadable {
+            assert_eq!(warnings.len(), 1);
+            assert!(warnings[0].contains("locked"));
+            assert!(root.find_by_path(&locked).is_none());
+        } else {
+            assert!(warnings.is_empty());
+        }
+        
+        // A missing root still fails the parse
+        assert!(DirectoryParser::isolated().parse_directory(&dir.path().join("missing")).is_err());
+    }
+    
     #[test]
     fn test_add_ignore_pattern() {
-        let mut parser = DirectoryParser::new();
+        let mut parser = DirectoryParser::isolated();
         
         // Initially should not ignore "temp"
         assert!(!parser.should_ignore(Path::new("temp")));
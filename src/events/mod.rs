@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use chrono::{DateTime, Local};
+
+use crate::ui::StatusKind;
+
+/// Maximum number of events kept; older ones are dropped first
+pub const EVENT_LOG_CAPACITY: usize = 500;
+
+/// Something that happened while the application was running
+#[derive(Debug, Clone)]
+pub struct Event {
+    /// When the event happened
+    pub time: DateTime<Local>,
+    
+    /// Whether an operation started, succeeded or failed
+    pub kind: StatusKind,
+    
+    /// What happened
+    pub message: String,
+}
+
+impl Event {
+    /// Formats the event as a line of the exported log
+    ///
+    /// # Returns
+    /// The time, a tag for the kind and the message, e.g.
+    /// `2024-01-01 12:00:00 [ERROR] Failed to clone`
+    pub fn to_line(&self) -> String {
+        let tag = match self.kind {
+            StatusKind::Info => "INFO",
+            StatusKind::Loading => "START",
+            StatusKind::Success => "OK",
+            StatusKind::Error => "ERROR",
+        };
+        format!("{} [{}] {}", self.time.format("%Y-%m-%d %H:%M:%S"), tag, self.message)
+    }
+}
+
+/// In-memory history of the events of a session, oldest first
+pub struct EventLog {
+    events: VecDeque<Event>,
+    capacity: usize,
+}
+
+impl EventLog {
+    /// Creates an empty log
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of events to keep
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity,
+        }
+    }
+    
+    /// Records an event at the current time, dropping the oldest over capacity
+    ///
+    /// # Arguments
+    /// * `kind` - Whether an operation started, succeeded or failed
+    /// * `message` - What happened
+    pub fn push(&mut self, kind: StatusKind, message: impl Into<String>) {
+        self.events.push_back(Event {
+            time: Local::now(),
+            kind,
+            message: message.into(),
+        });
+        while self.events.len() > self.capacity {
+            self.events.pop_front();
+        }
+    }
+    
+    /// Gets the recorded events, oldest first
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter()
+    }
+    
+    /// Gets the number of recorded events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+    
+    /// Checks whether no events are recorded
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+    
+    /// Drops all events
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+    
+    /// Formats the log for exporting to a text file
+    ///
+    /// # Returns
+    /// One line per event, oldest first
+    pub fn to_text(&self) -> String {
+        self.events.iter()
+            .map(|event| event.to_line() + "\n")
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_log_drops_oldest_over_capacity() {
+        let mut log = EventLog::new(2);
+        log.push(StatusKind::Loading, "Cloning repo");
+        log.push(StatusKind::Success, "Repository parsed successfully");
+        log.push(StatusKind::Error, "Failed to export");
+        
+        let messages: Vec<&str> = log.events().map(|event| event.message.as_str()).collect();
+        assert_eq!(messages, vec!["Repository parsed successfully", "Failed to export"]);
+        assert_eq!(log.len(), 2);
+        
+        log.clear();
+        assert!(log.is_empty());
+    }
+    
+    #[test]
+    fn test_to_text() {
+        let mut log = EventLog::new(EVENT_LOG_CAPACITY);
+        log.push(StatusKind::Loading, "Cloning repo");
+        log.push(StatusKind::Error, "Failed to clone");
+        
+        let text = log.to_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(" [START] Cloning repo"));
+        assert!(lines[1].ends_with(" [ERROR] Failed to clone"));
+        
+        // Lines start with the timestamp
+        let time = log.events().next().unwrap().time.format("%Y-%m-%d %H:%M:%S").to_string();
+        assert!(lines[0].starts_with(&time));
+    }
+}
@@ -0,0 +1,300 @@
+use std::path::Path;
+use serde::Serialize;
+
+use crate::app::{FileInfo, MetricBasis, format_file_size};
+
+/// Output formats for exporting the file list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Every format, in the order they are offered
+    pub const ALL: [ExportFormat; 3] = [Self::Csv, Self::Json, Self::Markdown];
+    
+
+    /// Looks up a format by name
+    ///
+    /// # Arguments
+    /// * `name` - The format name, e.g. "csv" or "JSON"
+    ///
+    /// # Returns
+    /// The matching format, or `None` if the name is unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+    
+    /// Gets the file extension for the format
+    ///
+    /// # Returns
+    /// The extension without a leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Markdown => "md",
+        }
+    }
+    
+    /// Gets the name shown on export buttons
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Json => "JSON",
+            Self::Markdown => "Markdown",
+        }
+    }
+}
+
+/// Totals for an exported file list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportSummary {
+    /// Number of exported files
+    pub total_files: usize,
+    
+    /// Number of text files
+    pub text_files: usize,
+    
+    /// Number of binary files
+    pub binary_files: usize,
+    
+    /// Total tokens across text files
+    pub total_tokens: usize,
+    
+    /// Total size of binary files in bytes
+    pub total_binary_size: usize,
+}
+
+impl ExportSummary {
+    /// Calculates the totals for a file list
+    ///
+    /// # Arguments
+    /// * `files` - The files to summarize
+    ///
+    /// # Returns
+    /// The summary of the files
+    pub fn from_files(files: &[FileInfo]) -> Self {
+        let text_files = files.iter().filter(|f| !f.is_binary).count();
+        Self {
+            total_files: files.len(),
+            text_files,
+            binary_files: files.len() - text_files,
+            total_tokens: files.iter().filter(|f| !f.is_binary).map(|f| f.tokens).sum(),
+            total_binary_size: files.iter().filter(|f| f.is_binary).map(|f| f.tokens).sum(),
+        }
+    }
+    
+    /// Prints the summary to stdout after an export
+    ///
+    /// # Arguments
+    /// * `path` - The file the list was exported to
+    pub fn print(&self, path: &Path) {
+        println!("Exported to {}:", path.display());
+        println!("  - Total files: {} ({} text, {} binary)", self.total_files, self.text_files, self.binary_files);
+        println!("  - Total tokens (text files): {}", self.total_tokens);
+        if self.binary_files > 0 {
+            println!("  - Total binary size: {}", format_file_size(self.total_binary_size));
+        }
+    }
+}
+
+/// A file entry in the JSON export
+#[derive(Serialize)]
+struct JsonFile {
+    index: usize,
+    path: String,
+    tokens: usize,
+    is_binary: bool,
+    size_bytes: usize,
+}
+
+/// The JSON export document
+#[derive(Serialize)]
+struct JsonExport {
+    summary: ExportSummary,
+    files: Vec<JsonFile>,
+}
+
+/// Renders the file list as CSV
+///
+/// # Arguments
+/// * `files` - The files to export
+/// * `basis` - What the value columns measure; files without a value get empty cells
+///
+/// # Returns
+/// The CSV text, with a header row
+pub fn to_csv(files: &[FileInfo], basis: MetricBasis) -> String {
+    let mut csv = String::from("Index,Path,Value,FormattedValue,IsBinary,Type\n");
+    
+    for file in files {
+        let file_type = if file.is_binary { "Binary" } else { "Text" };
+        let value = basis.value(file).map_or(String::new(), |value| value.to_string());
+        let formatted_value = match basis.value(file) {
+            Some(value) if basis.is_size(file) => format_file_size(value),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+        
+        csv.push_str(&format!(
+            "{},{},{},\"{}\",{},{}\n",
+            file.index,
+            file.path.to_string_lossy().replace(',', "\\,"), // Escape commas in paths
+            value,
+            formatted_value,
+            file.is_binary,
+            file_type
+        ));
+    }
+    
+    csv
+}
+
+/// Renders the file list as JSON, with a summary of the totals
+///
+/// # Arguments
+/// * `files` - The files to export
+///
+/// # Returns
+/// The pretty-printed JSON text
+pub fn to_json(files: &[FileInfo]) -> String {
+    let export = JsonExport {
+        summary: ExportSummary::from_files(files),
+        files: files.iter().map(|file| JsonFile {
+            index: file.index,
+            path: file.path.to_string_lossy().to_string(),
+            tokens: file.tokens,
+            is_binary: file.is_binary,
+            size_bytes: file.size_bytes,
+        }).collect(),
+    };
+    
+    // Serializing plain structs cannot fail
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+/// Renders the file list as a Markdown table, e.g. for an LLM prompt manifest
+///
+/// # Arguments
+/// * `files` - The files to export
+/// * `basis` - What the value column measures; files without a value show "—"
+///
+/// # Returns
+/// The Markdown text, with the totals below the table
+pub fn to_markdown(files: &[FileInfo], basis: MetricBasis) -> String {
+    let mut markdown = format!("| # | Path | {} | Type |\n|---|------|------|------|\n", basis.column_header());
+    for file in files {
+        let file_type = if file.is_binary { "Binary" } else { "Text" };
+        let path = file.path.to_string_lossy().replace('|', "\\|");
+        markdown.push_str(&format!(
+            "| {} | `{}` | {} | {} |\n",
+            file.index, path, basis.format(file, |tokens| tokens.to_string()), file_type
+        ));
+    }
+    
+    let summary = ExportSummary::from_files(files);
+    markdown.push_str(&format!(
+        "\n**Total:** {} files ({} text, {} binary), {} tokens\n",
+        summary.total_files, summary.text_files, summary.binary_files, summary.total_tokens
+    ));
+    markdown
+}
+
+/// Writes the file list to a file in the given format
+///
+/// # Arguments
+/// * `files` - The files to export
+/// * `format` - The output format
+/// * `basis` - What the CSV and Markdown value columns measure; JSON always has both tokens and sizes
+/// * `path` - The file to write
+///
+/// # Returns
+/// Result with the summary of the exported files or an error message
+pub fn write_export(files: &[FileInfo], format: ExportFormat, basis: MetricBasis, path: &Path) -> Result<ExportSummary, String> {
+    let contents = match format {
+        ExportFormat::Csv => to_csv(files, basis),
+        ExportFormat::Json => to_json(files),
+        ExportFormat::Markdown => to_markdown(files, basis),
+    };
+    
+    std::fs::write(path, contents)
+        .map_err(|e| format!("Failed to export to {}: {}", path.display(), e))?;
+    
+    Ok(ExportSummary::from_files(files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    
+    fn sample_files() -> Vec<FileInfo> {
+        vec![
+            FileInfo { index: 0, path: PathBuf::from("src/main.rs"), tokens: 120, is_binary: false, size_bytes: 600, content_hash: 1, ..FileInfo::default() },
+            FileInfo { index: 1, path: PathBuf::from("logo,dark.png"), tokens: 2048, is_binary: true, size_bytes: 2048, content_hash: 2, ..FileInfo::default() },
+        ]
+    }
+    
+    #[test]
+    fn test_format_from_name() {
+        assert_eq!(ExportFormat::from_name("CSV"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_name("json"), Some(ExportFormat::Json));
+        assert_eq!(ExportFormat::from_name("md"), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::from_name("xml"), None);
+    }
+    
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&sample_files(), MetricBasis::Hybrid);
+        let lines: Vec<&str> = csv.lines().collect();
+        
+        assert_eq!(lines[0], "Index,Path,Value,FormattedValue,IsBinary,Type");
+        assert_eq!(lines[1], "0,src/main.rs,120,\"120\",false,Text");
+        assert!(lines[2].starts_with("1,logo\\,dark.png,2048,\"2.00 KB"));
+        assert!(lines[2].ends_with(",true,Binary"));
+    }
+    
+    #[test]
+    fn test_to_csv_by_basis() {
+        // Binary files have no token count
+        let csv = to_csv(&sample_files(), MetricBasis::Tokens);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[1], "0,src/main.rs,120,\"120\",false,Text");
+        assert_eq!(lines[2], "1,logo\\,dark.png,,\"\",true,Binary");
+        
+        // Every file is measured by its size on disk
+        let csv = to_csv(&sample_files(), MetricBasis::Bytes);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[1], "0,src/main.rs,600,\"600 bytes\",false,Text");
+        assert!(lines[2].starts_with("1,logo\\,dark.png,2048,\"2.00 KB"));
+    }
+    
+    #[test]
+    fn test_to_json() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&sample_files())).unwrap();
+        
+        assert_eq!(json["summary"]["total_files"], 2);
+        assert_eq!(json["summary"]["total_tokens"], 120);
+        assert_eq!(json["summary"]["total_binary_size"], 2048);
+        assert_eq!(json["files"][0]["path"], "src/main.rs");
+        assert_eq!(json["files"][1]["is_binary"], true);
+    }
+    
+    #[test]
+    fn test_to_markdown() {
+        let markdown = to_markdown(&sample_files(), MetricBasis::Hybrid);
+        let lines: Vec<&str> = markdown.lines().collect();
+        
+        assert_eq!(lines[0], "| # | Path | Tokens/Size | Type |");
+        assert_eq!(lines[2], "| 0 | `src/main.rs` | 120 | Text |");
+        assert!(lines[3].starts_with("| 1 | `logo,dark.png` | 2.00 KB"));
+        assert_eq!(lines.last(), Some(&"**Total:** 2 files (1 text, 1 binary), 120 tokens"));
+    }
+}
@@ -1,10 +1,243 @@
 use std::path::{Path, PathBuf};
 use regex::Regex;
 
+/// Error reported when neither an SSH agent nor a private key file is available
+pub const SSH_UNAVAILABLE_MESSAGE: &str = "SSH key not found / agent unavailable";
+
+/// Gets the user's home directory
+///
+/// # Returns
+/// `HOME`, or `USERPROFILE` on Windows, or `None` if neither is set
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from)
+}
+
+/// Expands a leading `~` in a path to the user's home directory
+///
+/// # Arguments
+/// * `path` - The path, e.g. `~/.ssh/id_ed25519`
+///
+/// # Returns
+/// The expanded path, or the path unchanged if it doesn't start with `~` or no home is known
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// SSH authentication settings used when cloning SSH URLs
+#[derive(Clone, Debug, Default)]
+pub struct SshConfig {
+    /// Path to the private key, where a leading `~` is the home directory;
+    /// defaults to `~/.ssh/id_ed25519` or `~/.ssh/id_rsa`
+    pub private_key_path: Option<PathBuf>,
+    
+    /// Optional passphrase for the private key
+    pub passphrase: Option<String>,
+}
+
+impl SshConfig {
+    /// Resolves the private key file to use
+    ///
+    /// # Returns
+    /// The configured key path, or the first existing default key, if any
+    pub fn resolve_private_key(&self) -> Option<PathBuf> {
+        if let Some(path) = &self.private_key_path {
+            let path = expand_home(path);
+            return if path.exists() { Some(path) } else { None };
+        }
+        
+        let ssh_dir = home_dir()?.join(".ssh");
+        ["id_ed25519", "id_rsa"].iter()
+            .map(|name| ssh_dir.join(name))
+            .find(|path| path.exists())
+    }
+    
+    /// Creates a credentials callback for `RemoteCallbacks::credentials`
+    ///
+    /// The SSH agent is tried first, then the private key file. If neither is
+    /// available the callback fails with `SSH_UNAVAILABLE_MESSAGE`, which is
+    /// distinct from the generic authentication failure reported by git2.
+    ///
+    /// # Returns
+    /// A callback producing SSH credentials
+    pub fn credentials_callback(&self) -> impl FnMut(&str, Option<&str>, git2::CredentialType) -> Result<git2::Cred, git2::Error> {
+        let config = self.clone();
+        let mut tried_agent = false;
+        let mut tried_key = false;
+        
+        move |_url, username_from_url, allowed_types| {
+            let username = username_from_url.unwrap_or("git");
+            
+            if allowed_types.contains(git2::CredentialType::USERNAME) {
+                return git2::Cred::username(username);
+            }
+            
+            if !allowed_types.contains(git2::CredentialType::SSH_KEY) {
+                return Err(git2::Error::from_str("Unsupported credential type requested for SSH"));
+            }
+            
+            // libgit2 calls back again after a rejected credential, so each source is tried once
+            let agent_available = cfg!(windows) || std::env::var_os("SSH_AUTH_SOCK").is_some();
+            if !tried_agent && agent_available {
+                tried_agent = true;
+                return git2::Cred::ssh_key_from_agent(username);
+            }
+            
+            let private_key = config.resolve_private_key();
+            if !tried_key {
+                tried_key = true;
+                if let Some(key_path) = &private_key {
+                    return git2::Cred::ssh_key(username, None, key_path, config.passphrase.as_deref());
+                }
+            }
+            
+            if !tried_agent && private_key.is_none() {
+                Err(git2::Error::from_str(SSH_UNAVAILABLE_MESSAGE))
+            } else {
+                Err(git2::Error::from_str("SSH authentication failed: the agent and key file were rejected"))
+            }
+        }
+    }
+}
+
+/// Name of the remote a clone is made from unless another is chosen
+pub const DEFAULT_REMOTE_NAME: &str = "origin";
+
+/// Which branches a clone fetches and what its remote is called
+#[derive(Clone, Debug)]
+pub struct FetchConfig {
+    /// Name of the remote created for the clone
+    pub remote_name: String,
+    
+    /// Whether every branch is fetched rather than only the one checked out
+    pub all_branches: bool,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            remote_name: DEFAULT_REMOTE_NAME.to_string(),
+            all_branches: false,
+        }
+    }
+}
+
+impl FetchConfig {
+    /// Gets the fetch refspec of a clone
+    ///
+    /// # Arguments
+    /// * `branch` - The only branch to fetch, or `None` for every branch
+    ///
+    /// # Returns
+    /// A refspec mapping the branches to remote-tracking branches of the remote
+    pub fn refspec(&self, branch: Option<&str>) -> String {
+        let branch = branch.unwrap_or("*");
+        format!("+refs/heads/{0}:refs/remotes/{1}/{0}", branch, self.remote_name)
+    }
+    
+    /// Clones a repository into a remote with this name, fetching the chosen branches
+    ///
+    /// Only the branch checked out is fetched unless all branches are asked
+    /// for. Without a named branch, the remote's default branch is read from
+    /// the connection that then fetches it, so the remote is contacted once.
+    ///
+    /// # Arguments
+    /// * `url` - The Git URL to clone
+    /// * `destination` - The empty directory to clone into
+    /// * `branch` - The branch to check out, or `None` for the remote's default branch
+    /// * `follow_redirects` - Whether HTTP redirects to other hosts are followed at every stage
+    /// * `callbacks` - Creates the credential and progress callbacks, once for each connection setup
+    ///
+    /// # Returns
+    /// The cloned repository or the git2 error
+    pub fn clone_repository<'cb>(
+        &self,
+        url: &str,
+        destination: &Path,
+        branch: Option<&str>,
+        follow_redirects: bool,
+        callbacks: impl Fn() -> git2::RemoteCallbacks<'cb>,
+    ) -> Result<git2::Repository, git2::Error> {
+        let mut fetch_options = git2::FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks());
+        fetch_options.follow_redirects(GitHandler::redirect_policy(follow_redirects));
+        
+        if branch.is_some() || self.all_branches {
+            let refspec = self.refspec(branch.filter(|_| !self.all_branches));
+            let remote_name = self.remote_name.clone();
+            let mut builder = git2::build::RepoBuilder::new();
+            builder.fetch_options(fetch_options);
+            if let Some(branch) = branch {
+                builder.branch(branch);
+            }
+            builder.remote_create(move |repo, _name, url| repo.remote_with_fetch(&remote_name, url, &refspec));
+            return builder.clone(url, destination);
+        }
+        
+        std::fs::create_dir_all(destination).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+        let repo = git2::Repository::init(destination)?;
+        let has_head = {
+            let mut remote = repo.remote_anonymous(url)?;
+            let mut connection = remote.connect_auth(git2::Direction::Fetch, Some(callbacks()), None)?;
+            let default_branch = match connection.default_branch() {
+                Ok(name) => name.as_str().and_then(|name| name.strip_prefix("refs/heads/")).map(str::to_string),
+                // An empty repository or a detached HEAD
+                Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+                Err(e) => return Err(e),
+            };
+            
+            match default_branch {
+                Some(name) => {
+                    let refspec = self.refspec(Some(&name));
+                    connection.remote().fetch(&[&refspec], Some(&mut fetch_options), None)?;
+                    repo.remote_with_fetch(&self.remote_name, url, &refspec)?;
+                    
+                    // Check out a local branch tracking the fetched one, as a plain clone does
+                    let tracking = format!("refs/remotes/{}/{}", self.remote_name, name);
+                    let commit = repo.find_reference(&tracking)?.peel_to_commit()?;
+                    let mut local = repo.branch(&name, &commit, false)?;
+                    local.set_upstream(Some(&format!("{}/{}", self.remote_name, name)))?;
+                    repo.reference_symbolic(&format!("refs/remotes/{}/HEAD", self.remote_name), &tracking, true, "clone")?;
+                    repo.set_head(&format!("refs/heads/{}", name))?;
+                    true
+                }
+                // A detached HEAD is fetched on its own; an empty repository has nothing to check out
+                None => {
+                    connection.remote().fetch(&["HEAD"], Some(&mut fetch_options), None)?;
+                    repo.remote_with_fetch(&self.remote_name, url, &self.refspec(None))?;
+                    let head_id = repo.find_reference("FETCH_HEAD").ok().and_then(|head| head.target());
+                    if let Some(head_id) = head_id {
+                        repo.set_head_detached(head_id)?;
+                    }
+                    head_id.is_some()
+                }
+            }
+        };
+        if has_head {
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+        }
+        Ok(repo)
+    }
+}
+
 /// Handles Git repository operations
 pub struct GitHandler {
     /// Whether to keep the repository after cloning
     keep_repository: bool,
+    
+    /// SSH authentication settings for SSH URLs
+    ssh_config: SshConfig,
+    
+    /// Branch to check out, or `None` for the remote's default branch
+    branch: Option<String>,
+    
+    /// Whether HTTP redirects to other hosts are followed at every stage
+    follow_redirects: bool,
+    
+    /// Remote name and which branches are fetched
+    fetch_config: FetchConfig,
 }
 
 impl GitHandler {
@@ -18,21 +251,133 @@ impl GitHandler {
     pub fn new(keep_repository: bool) -> Self {
         Self {
             keep_repository,
+            ssh_config: SshConfig::default(),
+            branch: None,
+            follow_redirects: false,
+            fetch_config: FetchConfig::default(),
+        }
+    }
+    
+    /// Sets the SSH authentication settings used for SSH URLs
+    ///
+    /// # Arguments
+    /// * `ssh_config` - The SSH settings
+    pub fn set_ssh_config(&mut self, ssh_config: SshConfig) {
+        self.ssh_config = ssh_config;
+    }
+    
+    /// Sets the branch checked out by `clone_repository`
+    ///
+    /// # Arguments
+    /// * `branch` - The branch name, or `None` for the remote's default branch
+    pub fn set_branch(&mut self, branch: Option<String>) {
+        self.branch = branch;
+    }
+    
+    /// Sets whether `clone_repository` follows HTTP redirects to other hosts
+    ///
+    /// By default only a redirect on the initial request is followed, which
+    /// covers most moved repositories.
+    ///
+    /// # Arguments
+    /// * `follow_redirects` - `true` to follow redirects at any stage
+    pub fn set_follow_redirects(&mut self, follow_redirects: bool) {
+        self.follow_redirects = follow_redirects;
+    }
+    
+    /// Sets the remote name and which branches `clone_repository` fetches
+    ///
+    /// # Arguments
+    /// * `fetch_config` - The fetch settings
+    pub fn set_fetch_config(&mut self, fetch_config: FetchConfig) {
+        self.fetch_config = fetch_config;
+    }
+    
+    /// Gets the redirect policy for fetches
+    ///
+    /// # Arguments
+    /// * `follow_redirects` - Whether redirects to other hosts are followed at any stage
+    ///
+    /// # Returns
+    /// The git2 redirect setting
+    pub fn redirect_policy(follow_redirects: bool) -> git2::RemoteRedirect {
+        if follow_redirects {
+            git2::RemoteRedirect::All
+        } else {
+            git2::RemoteRedirect::Initial
+        }
+    }
+    
+    /// Checks whether a URL uses SSH transport
+    ///
+    /// # Arguments
+    /// * `url` - The URL to check
+    ///
+    /// # Returns
+    /// `true` for `git@host:path` shorthand and `ssh://` URLs
+    pub fn is_ssh_url(url: &str) -> bool {
+        url.starts_with("ssh://") || (url.starts_with("git@") && url.contains(':'))
+    }
+    
+    /// Clones a repository, attaching SSH credentials for SSH URLs
+    ///
+    /// # Arguments
+    /// * `url` - The Git URL to clone
+    /// * `destination` - The destination path
+    ///
+    /// # Returns
+    /// The cloned repository or the git2 error
+    fn clone_with_credentials(&self, url: &str, destination: &Path) -> Result<git2::Repository, git2::Error> {
+        self.fetch_config.clone_repository(url, destination, self.branch.as_deref(), self.follow_redirects, || {
+            let mut callbacks = git2::RemoteCallbacks::new();
+            if Self::is_ssh_url(url) {
+                callbacks.credentials(self.ssh_config.credentials_callback());
+            }
+            callbacks
+        })
+    }
+    
+    /// Normalizes a Git URL before it is validated and cloned
+    ///
+    /// Surrounding whitespace, e.g. a newline copied along with the URL, is
+    /// trimmed and a single trailing slash removed. `git@host:path` shorthand
+    /// keeps its form, since its path is relative to the remote user's home
+    /// and would change meaning as an `ssh://` URL.
+    ///
+    /// # Arguments
+    /// * `url` - The URL as entered
+    ///
+    /// # Returns
+    /// The normalized URL
+    pub fn normalize_url(url: &str) -> String {
+        let url = url.trim();
+        // Keep `/` and `file:///` intact so they are still rejected as paths without a name
+        match url.strip_suffix('/') {
+            Some(stripped) if !stripped.is_empty() && !stripped.ends_with('/') => stripped.to_string(),
+            _ => url.to_string(),
         }
     }
     
     /// Validates a Git URL format
     ///
+    /// Remote URLs need a host and a non-empty path, and local paths need at
+    /// least one component below the root, so `https://`, `git@` or `/` alone
+    /// are rejected. Whether the repository exists is only known when cloning.
+    ///
     /// # Arguments
     /// * `url` - The URL to validate
     ///
     /// # Returns
     /// `true` if the URL is valid, `false` otherwise
     pub fn validate_url(url: &str) -> bool {
-        // Enhanced validation for Git URLs supporting HTTPS, SSH, and local paths
-        // with optional .git suffix
-        let re = Regex::new(r"^(https://|git@|file://|/).*(\.git)?$").unwrap();
-        re.is_match(url)
+        // HTTP(S), SSH and git protocol URLs: optional user, host, optional port, path
+        let remote = Regex::new(r"^(https?|ssh|git)://([^@/\s]+@)?[A-Za-z0-9.-]+(:\d+)?/[^\s]*[^/\s][^\s]*$").unwrap();
+        // SSH shorthand: git@host:path
+        let scp_like = Regex::new(r"^git@[A-Za-z0-9.-]+:[^\s]*[^/\s][^\s]*$").unwrap();
+        // Local repositories: file:///path or an absolute path
+        let local = Regex::new(r"^(file://)?/.*[^/\s].*$").unwrap();
+        
+        remote.is_match(url) || scp_like.is_match(url) || local.is_match(url)
     }
     
     /// Clones a Git repository with improved error handling
@@ -51,13 +396,13 @@ impl GitHandler {
         }
         
         // Attempt to clone the repository
-        let repo = match git2::Repository::clone(url, destination) {
+        let repo = match self.clone_with_credentials(url, destination) {
             Ok(repo) => repo,
             Err(e) => {
                 // If cloning fails and the URL doesn't end with .git, try appending .git
                 if !url.ends_with(".git") {
                     let url_with_git = format!("{}.git", url);
-                    match git2::Repository::clone(&url_with_git, destination) {
+                    match self.clone_with_credentials(&url_with_git, destination) {
                         Ok(repo) => repo,
                         Err(e2) => {
                             // Provide detailed error information for both attempts
@@ -70,6 +415,10 @@ impl GitHandler {
                 } else {
                     // Categorize common errors for better user feedback
                     let error_msg = match e.code() {
+                        _ if e.message().contains(SSH_UNAVAILABLE_MESSAGE) => format!(
+                            "{} for {}. Start an SSH agent or configure a private key path.",
+                            SSH_UNAVAILABLE_MESSAGE, url
+                        ),
                         git2::ErrorCode::Auth => format!(
                             "Authentication failed for {}. Check your credentials or ensure the repository is public.",
                             url
@@ -117,6 +466,197 @@ impl GitHandler {
         }
     }
     
+    /// Lists the branches of a repository, local and remote-tracking
+    ///
+    /// Remote-tracking branches are listed without their remote name, so
+    /// `origin/develop` and a local `develop` appear once as `develop`.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// Result with the sorted branch names or an error message
+    pub fn list_branches(&self, repo_path: &Path) -> Result<Vec<String>, String> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        let branches = repo.branches(None)
+            .map_err(|e| format!("Failed to list branches: {}", e))?;
+        
+        let mut names = Vec::new();
+        for (branch, branch_type) in branches.flatten() {
+            let Ok(Some(name)) = branch.name() else {
+                continue;
+            };
+            let name = match branch_type {
+                git2::BranchType::Local => name,
+                // Skip the remote's HEAD, which only points at its default branch
+                git2::BranchType::Remote => match name.split_once('/') {
+                    Some((_, "HEAD")) | None => continue,
+                    Some((_, name)) => name,
+                },
+            };
+            names.push(name.to_string());
+        }
+        
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+    
+    /// Checks whether a directory is the working tree of a repository
+    ///
+    /// Only the directory itself is checked, not its parents, so a folder
+    /// inside another repository doesn't count.
+    ///
+    /// # Arguments
+    /// * `path` - The directory to check
+    ///
+    /// # Returns
+    /// `true` if the directory holds a `.git` repository that can be opened
+    pub fn is_repository(path: &Path) -> bool {
+        git2::Repository::open(path)
+            .ok()
+            .and_then(|repo| repo.workdir().map(|workdir| workdir.canonicalize().ok() == path.canonicalize().ok()))
+            .unwrap_or(false)
+    }
+    
+    /// Gets the URL of a remote of a repository
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `remote_name` - Name of the remote, e.g. `origin`
+    ///
+    /// # Returns
+    /// The remote's URL, or `None` if the repository or remote can't be read
+    pub fn remote_url(&self, repo_path: &Path, remote_name: &str) -> Option<String> {
+        let repo = git2::Repository::open(repo_path).ok()?;
+        let remote = repo.find_remote(remote_name).ok()?;
+        remote.url().map(|url| url.to_string())
+    }
+    
+    /// Gets the commit HEAD points to, without walking the history
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// The full commit hash, or `None` for a repository without commits or an unreadable one
+    pub fn head_commit_id(&self, repo_path: &Path) -> Option<String> {
+        let repo = git2::Repository::open(repo_path).ok()?;
+        let commit = repo.head().ok()?.peel_to_commit().ok()?;
+        Some(commit.id().to_string())
+    }
+    
+    /// Gets the branch checked out in a repository
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// The branch name, or `None` for a detached HEAD or an unreadable repository
+    pub fn current_branch(&self, repo_path: &Path) -> Option<String> {
+        let repo = git2::Repository::open(repo_path).ok()?;
+        let head = repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        head.shorthand().map(|name| name.to_string())
+    }
+    
+    /// Checks out a branch in the working tree
+    ///
+    /// A branch that only exists on the remote gets a local branch tracking
+    /// it. The checkout is refused rather than overwriting local changes.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    /// * `name` - Branch name, without a remote prefix
+    ///
+    /// # Returns
+    /// Result indicating success or an error message
+    pub fn checkout_branch(&self, repo_path: &Path, name: &str) -> Result<(), String> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        
+        let branch = match repo.find_branch(name, git2::BranchType::Local) {
+            Ok(branch) => branch,
+            Err(_) => {
+                let remote_branch = repo.branches(Some(git2::BranchType::Remote))
+                    .map_err(|e| format!("Failed to list branches: {}", e))?
+                    .flatten()
+                    .map(|(branch, _)| branch)
+                    .find(|branch| {
+                        branch.name().ok().flatten()
+                            .and_then(|remote_name| remote_name.split_once('/'))
+                            .is_some_and(|(_, branch_name)| branch_name == name)
+                    })
+                    .ok_or_else(|| format!("Branch not found: {}", name))?;
+                let remote_name = remote_branch.name().ok().flatten().unwrap_or(name).to_string();
+                let commit = remote_branch.get().peel_to_commit()
+                    .map_err(|e| format!("Failed to read branch {}: {}", remote_name, e))?;
+                
+                let mut branch = repo.branch(name, &commit, false)
+                    .map_err(|e| format!("Failed to create branch {}: {}", name, e))?;
+                branch.set_upstream(Some(&remote_name))
+                    .map_err(|e| format!("Failed to track {}: {}", remote_name, e))?;
+                branch
+            }
+        };
+        
+        let commit = branch.get().peel_to_commit()
+            .map_err(|e| format!("Failed to read branch {}: {}", name, e))?;
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.safe();
+        repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+            .map_err(|e| match e.code() {
+                git2::ErrorCode::Conflict => format!("Cannot switch to {}: local changes would be overwritten", name),
+                _ => format!("Failed to check out {}: {}", name, e),
+            })?;
+        
+        let reference = branch.get().name()
+            .ok_or_else(|| format!("Invalid branch name: {}", name))?;
+        repo.set_head(reference)
+            .map_err(|e| format!("Failed to switch HEAD to {}: {}", name, e))
+    }
+    
+    /// Lists the files with uncommitted changes in a working tree
+    ///
+    /// Untracked files count as changed; ignored, deleted and submodule
+    /// entries are left out since there is nothing on disk to analyze.
+    ///
+    /// # Arguments
+    /// * `repo_path` - Path to the repository
+    ///
+    /// # Returns
+    /// Result with the sorted paths, relative to the repository root, or an error message
+    pub fn changed_files(&self, repo_path: &Path) -> Result<Vec<PathBuf>, String> {
+        let repo = git2::Repository::open(repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))?;
+        let workdir = repo.workdir()
+            .ok_or_else(|| "Repository has no working tree".to_string())?
+            .to_path_buf();
+        
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false)
+            .exclude_submodules(true);
+        let statuses = repo.statuses(Some(&mut options))
+            .map_err(|e| format!("Failed to read status: {}", e))?;
+        
+        let changed = git2::Status::WT_NEW | git2::Status::WT_MODIFIED | git2::Status::WT_RENAMED | git2::Status::WT_TYPECHANGE
+            | git2::Status::INDEX_NEW | git2::Status::INDEX_MODIFIED | git2::Status::INDEX_RENAMED | git2::Status::INDEX_TYPECHANGE;
+        let mut paths: Vec<PathBuf> = statuses.iter()
+            .filter(|entry| entry.status().intersects(changed))
+            .filter_map(|entry| entry.path().map(PathBuf::from))
+            .filter(|path| workdir.join(path).is_file())
+            .collect();
+        
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+    
     /// Gets repository metadata
     ///
     /// # Arguments
@@ -137,9 +677,12 @@ impl GitHandler {
             .unwrap_or("unknown")
             .to_string();
         
-        // Get current branch
+        // Get current branch; a new or emptied repository has no commits for HEAD to point at
         let head = match repo.head() {
             Ok(head) => head,
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch || e.code() == git2::ErrorCode::NotFound => {
+                return Ok(RepositoryMetadata::without_commits(name, &repo));
+            }
             Err(e) => return Err(format!("Failed to get HEAD: {}", e)),
         };
         
@@ -166,7 +709,7 @@ impl GitHandler {
         };
         
         // Get last commit date
-        let last_commit = match repo.head().and_then(|head| head.peel_to_commit()) {
+        let last_commit = match head.peel_to_commit() {
             Ok(commit) => commit,
             Err(e) => return Err(format!("Failed to get last commit: {}", e)),
         };
@@ -202,6 +745,32 @@ pub struct RepositoryMetadata {
     pub last_commit_date: String,
 }
 
+impl RepositoryMetadata {
+    /// Placeholder for the last commit date of a repository without commits
+    pub const NO_COMMITS: &'static str = "(no commits)";
+    
+    /// Builds the metadata of a repository whose HEAD has no commits yet
+    ///
+    /// # Arguments
+    /// * `name` - Repository name
+    /// * `repo` - The repository, used to read the branch HEAD will create
+    ///
+    /// # Returns
+    /// Metadata with no commits and the unborn branch name, or "unknown"
+    fn without_commits(name: String, repo: &git2::Repository) -> Self {
+        let branch = repo.find_reference("HEAD").ok()
+            .and_then(|head| head.symbolic_target().map(|target| target.trim_start_matches("refs/heads/").to_string()))
+            .unwrap_or_else(|| "unknown".to_string());
+        
+        Self {
+            name,
+            branch,
+            commit_count: 0,
+            last_commit_date: Self::NO_COMMITS.to_string(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,11 +785,252 @@ mod tests {
         assert!(GitHandler::validate_url("file:///path/to/repo")); // Local file URL is valid
         assert!(GitHandler::validate_url("/absolute/path/to/repo")); // Absolute path is valid
         
+        assert!(GitHandler::validate_url("http://git.internal/team/repo.git")); // Plain HTTP is valid
+        assert!(GitHandler::validate_url("ssh://git@git.internal:2222/team/repo.git")); // SSH URL is valid
+        assert!(GitHandler::validate_url("git://git.internal/team/repo.git")); // Git protocol is valid
+        
         // Invalid URLs
         assert!(!GitHandler::validate_url("invalid-url")); // No protocol or path format
+        assert!(!GitHandler::validate_url("github.com/user/repo")); // Bare hostname
         assert!(!GitHandler::validate_url("ftp://github.com/user/repo.git")); // Unsupported protocol
     }
     
+    #[test]
+    fn test_validate_url_rejects_malformed() {
+        // Schemes and prefixes without a host or path
+        assert!(!GitHandler::validate_url("https://"));
+        assert!(!GitHandler::validate_url("https://github.com"));
+        assert!(!GitHandler::validate_url("https://github.com/"));
+        assert!(!GitHandler::validate_url("ssh://"));
+        assert!(!GitHandler::validate_url("git@"));
+        assert!(!GitHandler::validate_url("git@github.com"));
+        assert!(!GitHandler::validate_url("git@github.com:"));
+        assert!(!GitHandler::validate_url("file://"));
+        assert!(!GitHandler::validate_url("/"));
+        assert!(!GitHandler::validate_url("//"));
+        
+        // Whitespace inside a remote URL
+        assert!(!GitHandler::validate_url("https://github.com/user/my repo"));
+        
+        // Minimal but complete URLs still pass
+        assert!(GitHandler::validate_url("https://git.internal/repo"));
+        assert!(GitHandler::validate_url("https://github.com/user/repo/"));
+        assert!(GitHandler::validate_url("git@github.com:repo"));
+        assert!(GitHandler::validate_url("/repo"));
+    }
+    
+    #[test]
+    fn test_normalize_url() {
+        // Whitespace around the URL is trimmed
+        assert_eq!(GitHandler::normalize_url("  https://github.com/user/repo.git\n"), "https://github.com/user/repo.git");
+        assert_eq!(GitHandler::normalize_url("\t/home/user/repo \r\n"), "/home/user/repo");
+        
+        // A single trailing slash is removed
+        assert_eq!(GitHandler::normalize_url("https://github.com/user/repo/"), "https://github.com/user/repo");
+        assert_eq!(GitHandler::normalize_url("file:///home/user/repo/"), "file:///home/user/repo");
+        assert_eq!(GitHandler::normalize_url("/"), "/");
+        assert_eq!(GitHandler::normalize_url("file:///"), "file:///");
+        assert!(GitHandler::validate_url(&GitHandler::normalize_url(" https://github.com/user/repo/ ")));
+        
+        // SSH shorthand keeps its form
+        assert_eq!(GitHandler::normalize_url(" git@github.com:user/repo.git/ "), "git@github.com:user/repo.git");
+        assert!(GitHandler::is_ssh_url(&GitHandler::normalize_url("git@github.com:user/repo\n")));
+    }
+    
+    #[test]
+    fn test_is_ssh_url() {
+        assert!(GitHandler::is_ssh_url("git@github.com:user/repo.git"));
+        assert!(GitHandler::is_ssh_url("ssh://git@github.com/user/repo.git"));
+        assert!(!GitHandler::is_ssh_url("https://github.com/user/repo.git"));
+        assert!(!GitHandler::is_ssh_url("/absolute/path/to/repo"));
+    }
+    
+    #[test]
+    fn test_resolve_private_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("custom_key");
+        
+        // A configured key that doesn't exist is not silently replaced by a default
+        let config = SshConfig { private_key_path: Some(key_path.clone()), passphrase: None };
+        assert_eq!(config.resolve_private_key(), None);
+        
+        std::fs::write(&key_path, "not a real key").unwrap();
+        assert_eq!(config.resolve_private_key(), Some(key_path));
+        
+        // A leading `~` is the home directory, as in the settings hint
+        let home = home_dir().unwrap();
+        assert_eq!(expand_home(Path::new("~/.ssh/id_ed25519")), home.join(".ssh").join("id_ed25519"));
+        assert_eq!(expand_home(Path::new("/keys/~id")), PathBuf::from("/keys/~id"));
+    }
+    
+    #[test]
+    fn test_empty_repository() {
+        let source = tempfile::tempdir().unwrap();
+        git2::Repository::init(source.path()).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        
+        // Cloning a repository without commits leaves an empty working tree
+        let handler = GitHandler::new(false);
+        let repo_path = handler.clone_repository(&source.path().to_string_lossy(), &destination.path().join("empty")).unwrap();
+        
+        let metadata = handler.get_repository_metadata(&repo_path).unwrap();
+        assert_eq!(metadata.name, "empty");
+        assert_eq!(metadata.commit_count, 0);
+        assert_eq!(metadata.last_commit_date, RepositoryMetadata::NO_COMMITS);
+        assert_eq!(handler.head_commit_id(&repo_path), None);
+        assert!(!metadata.branch.is_empty());
+        
+        let parser = crate::directory::DirectoryParser::isolated();
+        let root = parser.parse_directory(&repo_path).unwrap();
+        assert!(parser.get_all_files(&root).is_empty());
+    }
+    
+    #[test]
+    fn test_is_repository() {
+        let source = tempfile::tempdir().unwrap();
+        git2::Repository::init(source.path()).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let repo_path = GitHandler::new(false).clone_repository(&source.path().to_string_lossy(), &destination.path().join("clone")).unwrap();
+        
+        assert!(GitHandler::is_repository(&repo_path));
+        assert_eq!(GitHandler::new(false).remote_url(&repo_path, DEFAULT_REMOTE_NAME).as_deref(), Some(&*source.path().to_string_lossy()));
+        
+        // Neither the folder holding the clone nor a folder inside it is a repository
+        assert!(!GitHandler::is_repository(destination.path()));
+        std::fs::create_dir(repo_path.join("src")).unwrap();
+        assert!(!GitHandler::is_repository(&repo_path.join("src")));
+    }
+    
+    #[test]
+    fn test_switch_branches() {
+        // Source repository with a default branch and a `feature` branch holding one more file
+        let source = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(source.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        std::fs::write(source.path().join("README.md"), "hello").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let initial = repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+        let default_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        
+        std::fs::write(source.path().join("feature.rs"), "fn feature() {}").unwrap();
+        index.add_path(Path::new("feature.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.find_commit(initial).unwrap();
+        repo.commit(Some("refs/heads/feature"), &signature, &signature, "Add feature", &tree, &[&parent]).unwrap();
+        
+        let destination = tempfile::tempdir().unwrap();
+        let mut handler = GitHandler::new(false);
+        handler.set_fetch_config(FetchConfig { all_branches: true, ..FetchConfig::default() });
+        let repo_path = handler.clone_repository(&source.path().to_string_lossy(), &destination.path().join("clone")).unwrap();
+        
+        let mut expected = vec![default_branch.clone(), "feature".to_string()];
+        expected.sort();
+        assert_eq!(handler.list_branches(&repo_path).unwrap(), expected);
+        assert_eq!(handler.current_branch(&repo_path), Some(default_branch.clone()));
+        assert_eq!(handler.head_commit_id(&repo_path), Some(initial.to_string()));
+        
+        // The remote-only branch gets a local tracking branch
+        handler.checkout_branch(&repo_path, "feature").unwrap();
+        assert_eq!(handler.current_branch(&repo_path), Some("feature".to_string()));
+        assert!(repo_path.join("feature.rs").exists());
+        
+        handler.checkout_branch(&repo_path, &default_branch).unwrap();
+        assert!(!repo_path.join("feature.rs").exists());
+        
+        assert!(handler.checkout_branch(&repo_path, "missing").is_err());
+    }
+    
+    #[test]
+    fn test_changed_files() {
+        let source = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(source.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        std::fs::write(source.path().join("README.md"), "hello").unwrap();
+        std::fs::write(source.path().join("old.txt"), "old").unwrap();
+        std::fs::write(source.path().join("stable.txt"), "stable").unwrap();
+        let mut index = repo.index().unwrap();
+        for path in ["README.md", "old.txt", "stable.txt"] {
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+        
+        let handler = GitHandler::new(false);
+        assert!(handler.changed_files(source.path()).unwrap().is_empty());
+        
+        // Modified, untracked (also inside new folders) and deleted files
+        std::fs::write(source.path().join("README.md"), "hello again").unwrap();
+        std::fs::create_dir(source.path().join("src")).unwrap();
+        std::fs::write(source.path().join("src").join("new.rs"), "fn new() {}").unwrap();
+        std::fs::remove_file(source.path().join("old.txt")).unwrap();
+        
+        assert_eq!(handler.changed_files(source.path()).unwrap(), vec![PathBuf::from("README.md"), PathBuf::from("src/new.rs")]);
+        
+        let plain = tempfile::tempdir().unwrap();
+        assert!(handler.changed_files(plain.path()).is_err());
+    }
+    
+    #[test]
+    fn test_fetch_named_branch() {
+        let source = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(source.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let initial = repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+        repo.branch("feature", &repo.find_commit(initial).unwrap(), false).unwrap();
+        let default_branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let destination = tempfile::tempdir().unwrap();
+        let url = source.path().to_string_lossy();
+        
+        // By default only the remote's default branch is fetched, and checked out tracking it
+        let mut handler = GitHandler::new(false);
+        let default_path = handler.clone_repository(&url, &destination.path().join("default")).unwrap();
+        assert_eq!(handler.list_branches(&default_path).unwrap(), vec![default_branch.clone()]);
+        assert_eq!(handler.current_branch(&default_path), Some(default_branch.clone()));
+        assert_eq!(handler.head_commit_id(&default_path), Some(initial.to_string()));
+        let clone = git2::Repository::open(&default_path).unwrap();
+        let local = clone.find_branch(&default_branch, git2::BranchType::Local).unwrap();
+        assert_eq!(local.upstream().unwrap().name().unwrap(), Some(format!("origin/{}", default_branch).as_str()));
+        
+        // Naming a branch fetches only that one
+        handler.set_branch(Some("feature".to_string()));
+        let single_path = handler.clone_repository(&url, &destination.path().join("single")).unwrap();
+        assert_eq!(handler.list_branches(&single_path).unwrap(), vec!["feature".to_string()]);
+        
+        handler.set_fetch_config(FetchConfig {
+            remote_name: "upstream".to_string(),
+            all_branches: true,
+        });
+        let repo_path = handler.clone_repository(&url, &destination.path().join("all")).unwrap();
+        assert!(git2::Repository::open(&repo_path).unwrap().find_remote("upstream").is_ok());
+        assert_eq!(handler.list_branches(&repo_path).unwrap().len(), 2);
+    }
+    
+    #[test]
+    fn test_fetch_refspec() {
+        let config = FetchConfig::default();
+        assert_eq!(config.refspec(None), "+refs/heads/*:refs/remotes/origin/*");
+        assert_eq!(config.refspec(Some("main")), "+refs/heads/main:refs/remotes/origin/main");
+        
+        let config = FetchConfig { remote_name: "upstream".to_string(), all_branches: true };
+        assert_eq!(config.refspec(Some("dev")), "+refs/heads/dev:refs/remotes/upstream/dev");
+    }
+    
+    #[test]
+    fn test_bare_repository_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().join("repo.git");
+        git2::Repository::init_bare(&repo_path).unwrap();
+        
+        let metadata = GitHandler::new(false).get_repository_metadata(&repo_path).unwrap();
+        assert_eq!(metadata.name, "repo.git");
+        assert_eq!(metadata.commit_count, 0);
+        assert_eq!(metadata.last_commit_date, RepositoryMetadata::NO_COMMITS);
+    }
+    
     #[test]
     fn test_new_git_handler() {
         let handler = GitHandler::new(true);
@@ -0,0 +1,246 @@
+use std::convert::Infallible;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::app::{FileInfo, MetricBasis, CountSettings, count_repository_files, parse_extension_list, DEFAULT_TEXT_EXTENSIONS};
+use crate::config::ProjectConfig;
+use crate::directory::{DirectoryEntry, DirectoryParser, ParsedRepository};
+use crate::export::{self, ExportFormat, ExportSummary};
+use crate::git::{FetchConfig, GitHandler, SshConfig, DEFAULT_REMOTE_NAME};
+
+/// Number of counted files between progress lines
+const PROGRESS_INTERVAL: usize = 1000;
+
+/// Settings for a headless export run
+pub struct HeadlessOptions {
+    /// Repository URL to analyze
+    pub url: String,
+    
+    /// File to write the export to
+    pub out_path: PathBuf,
+    
+    /// Output format
+    pub format: ExportFormat,
+    
+    /// Branch, SSH key, ignore patterns, text extensions and tokenizer; filters only apply in the GUI
+    pub config: ProjectConfig,
+    
+    /// Parser the ignore preset and patterns of `config` are added to
+    pub parser: DirectoryParser,
+}
+
+/// Clones, analyzes and exports a repository without opening a window
+///
+/// The clone goes into a temporary directory that is removed afterwards, so
+/// exported paths are relative to the repository root.
+///
+/// # Arguments
+/// * `options` - What to analyze and where to write the result
+///
+/// # Returns
+/// Result with the summary of the exported files or an error message
+pub fn run(options: &HeadlessOptions) -> Result<ExportSummary, String> {
+    let temp_dir = tempfile::Builder::new()
+        .prefix("git_scroll_")
+        .tempdir()
+        .map_err(|e| format!("Failed to create temporary directory: {}", e))?;
+    
+    let mut git_handler = GitHandler::new(false);
+    git_handler.set_branch(options.config.branch.clone());
+    git_handler.set_follow_redirects(options.config.follow_redirects.unwrap_or(false));
+    git_handler.set_ssh_config(SshConfig {
+        private_key_path: options.config.ssh_key_path.clone(),
+        passphrase: None,
+    });
+    git_handler.set_fetch_config(FetchConfig {
+        remote_name: options.config.remote_name.clone().unwrap_or_else(|| DEFAULT_REMOTE_NAME.to_string()),
+        all_branches: options.config.fetch_all_branches.unwrap_or(false),
+    });
+    
+    let url = GitHandler::normalize_url(&options.url);
+    println!("Cloning {}...", url);
+    let repo_path = git_handler.clone_repository(&url, temp_dir.path())?;
+    
+    println!("Counting tokens...");
+    let (_, mut files) = analyze(&repo_path, options.parser.clone(), &options.config, |counted, total| {
+        if counted % PROGRESS_INTERVAL == 0 || counted == total {
+            println!("Counted {} of {} files", counted, total);
+        }
+    })?;
+    for file in &mut files {
+        if let Ok(relative_path) = file.path.strip_prefix(&repo_path) {
+            file.path = relative_path.to_path_buf();
+        }
+    }
+    
+    export::write_export(&files, options.format, MetricBasis::Hybrid, &options.out_path)
+}
+
+/// Parses a repository and counts tokens for every file
+///
+/// The parse and the count are the ones the GUI runs on its worker threads,
+/// only waited for here.
+///
+/// # Arguments
+/// * `repo_path` - Path to the repository
+/// * `parser` - Parser the ignore preset and patterns of `config` are added to
+/// * `config` - Supplies the ignore preset, extra ignore patterns, the text extensions and the tokenizer
+/// * `on_progress` - Called with the number of files counted so far and the total, from the counting threads
+///
+/// # Returns
+/// Result with the parsed directory structure and the counted files in parse
+/// order, or an error message
+pub fn analyze(
+    repo_path: &Path,
+    mut parser: DirectoryParser,
+    config: &ProjectConfig,
+    on_progress: impl Fn(usize, usize) + Sync,
+) -> Result<(DirectoryEntry, Vec<FileInfo>), String> {
+    if let Some(ignore_preset) = config.ignore_preset {
+        parser.set_ignore_preset(ignore_preset);
+    }
+    for pattern in &config.ignore_patterns {
+        parser.add_ignore_pattern(pattern.clone());
+    }
+    let settings = CountSettings {
+        text_extensions: config.text_extensions.clone()
+            .unwrap_or_else(|| parse_extension_list(DEFAULT_TEXT_EXTENSIONS)),
+        tokenizer: config.tokenizer.unwrap_or_default(),
+        threads: rayon::current_num_threads(),
+    };
+    
+    let ParsedRepository { root_entry, warnings, link_only } = parser.parse_repository(repo_path)?;
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    let files: Vec<(usize, PathBuf)> = parser.get_all_files(&root_entry).into_iter().enumerate().collect();
+    
+    // Results arrive in any order, so they are put back in parse order afterwards
+    let counted_files = Mutex::new(Vec::with_capacity(files.len()));
+    let counted = AtomicUsize::new(0);
+    let _ = count_repository_files(&files, &link_only, &settings, None, None, |result| {
+        counted_files.lock().unwrap().push(result);
+        on_progress(counted.fetch_add(1, Ordering::Relaxed) + 1, files.len());
+        Ok::<(), Infallible>(())
+    });
+    let mut counted_files = counted_files.into_inner().unwrap();
+    counted_files.sort_by_key(|(index, ..)| *index);
+    
+    let files = counted_files.into_iter()
+        .map(|(index, path, tokens, is_binary, size_bytes, content_hash, lfs_size, modified)| FileInfo {
+            index,
+            path,
+            tokens,
+            selected: false,
+            is_binary,
+            size_bytes,
+            content_hash,
+            lfs_size,
+            pinned: false,
+            modified,
+        })
+        .collect();
+    Ok((root_entry, files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Creates a git repository with a few committed files
+    fn create_source_repository(path: &Path) {
+        let repo = git2::Repository::init(path).unwrap();
+        std::fs::write(path.join("README.md"), "hello headless world").unwrap();
+        std::fs::create_dir(path.join("dist")).unwrap();
+        std::fs::write(path.join("dist").join("bundle.js"), "a b c d").unwrap();
+        
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.add_path(Path::new("dist/bundle.js")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+    }
+    
+    #[test]
+    fn test_analyze_respects_ignore_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        create_source_repository(dir.path());
+        
+        let (_, files) = analyze(dir.path(), DirectoryParser::isolated(), &ProjectConfig::default(), |_, _| {}).unwrap();
+        assert_eq!(files.len(), 2);
+        
+        let config = ProjectConfig {
+            ignore_patterns: vec!["dist".to_string()],
+            ..ProjectConfig::default()
+        };
+        let (_, files) = analyze(dir.path(), DirectoryParser::isolated(), &config, |_, _| {}).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].tokens, 3);
+        assert!(!files[0].is_binary);
+    }
+    
+    #[test]
+    fn test_clone_parse_and_count() {
+        let source = tempfile::tempdir().unwrap();
+        create_source_repository(source.path());
+        let destination = tempfile::tempdir().unwrap();
+        
+        let git_handler = GitHandler::new(false);
+        let url = format!("file://{}", source.path().display());
+        let repo_path = git_handler.clone_repository(&url, &destination.path().join("repo")).unwrap();
+        
+        let progress = Mutex::new(Vec::new());
+        let (root_entry, files) = analyze(&repo_path, DirectoryParser::isolated(), &ProjectConfig::default(), |counted, total| {
+            progress.lock().unwrap().push((counted, total));
+        }).unwrap();
+        let counted: Vec<(PathBuf, usize, bool)> = files.iter()
+            .map(|file| (file.path.strip_prefix(&repo_path).unwrap().to_path_buf(), file.tokens, file.is_binary))
+            .collect();
+        // Directories are listed before files
+        assert_eq!(counted, vec![
+            (PathBuf::from("dist").join("bundle.js"), 4, false),
+            (PathBuf::from("README.md"), 3, false),
+        ]);
+        assert!(files.iter().enumerate().all(|(position, file)| file.index == position));
+        
+        // Progress is reported once per file
+        let mut progress = progress.into_inner().unwrap();
+        progress.sort();
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+        
+        // The .git directory of the clone is ignored
+        let statistics = DirectoryParser::isolated().get_statistics(&root_entry);
+        assert_eq!(statistics.total_files, 2);
+        assert_eq!(statistics.total_size_bytes, ("hello headless world".len() + "a b c d".len()) as u64);
+        assert_eq!(statistics.file_types.get("md"), Some(&1));
+        assert_eq!(statistics.file_types.get("js"), Some(&1));
+        
+        git_handler.cleanup(&repo_path).unwrap();
+        assert!(!repo_path.exists());
+    }
+    
+    #[test]
+    fn test_run_exports_json() {
+        let source = tempfile::tempdir().unwrap();
+        create_source_repository(source.path());
+        let out_dir = tempfile::tempdir().unwrap();
+        let out_path = out_dir.path().join("report.json");
+        
+        let summary = run(&HeadlessOptions {
+            url: source.path().to_string_lossy().to_string(),
+            out_path: out_path.clone(),
+            format: ExportFormat::Json,
+            config: ProjectConfig::default(),
+            parser: DirectoryParser::isolated(),
+        }).unwrap();
+        
+        assert_eq!(summary.total_files, 2);
+        assert_eq!(summary.total_tokens, 7);
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(out_path).unwrap()).unwrap();
+        assert_eq!(json["files"].as_array().unwrap().len(), 2);
+        assert!(json["files"].as_array().unwrap().iter().any(|file| file["path"] == "README.md"));
+    }
+}
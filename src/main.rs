@@ -2,9 +2,162 @@ mod app;
 mod git;
 mod directory;
 mod ui;
+mod config;
+mod export;
+mod headless;
+mod preview;
+mod dialogs;
+mod events;
+
+use std::path::PathBuf;
+
+/// Usage text printed for `--help` and argument errors
+const USAGE: &str = "Usage: git_scroll [--config <file>] [--clone]
+       git_scroll --headless --url <url> --out <path> [--format <csv|json|md>] [--config <file>]
+
+Options:
+  -c, --config <file>  Load a JSON or YAML project file before starting
+      --clone          Start cloning the configured URL right away
+      --headless       Export without opening a window, then exit
+      --url <url>      Repository to analyze (overrides the project file)
+      --out <path>     File to write the export to
+      --format <fmt>   Export format: csv, json or md (default: from --out, else csv)
+  -h, --help           Show this help";
+
+/// Options given on the command line
+#[derive(Debug, Default, PartialEq)]
+struct CliOptions {
+    /// Project file to load
+    config_path: Option<PathBuf>,
+    
+    /// Start cloning even if the project file doesn't ask for it
+    auto_clone: bool,
+    
+    /// Print the usage text and exit
+    show_help: bool,
+    
+    /// Export without opening a window
+    headless: bool,
+    
+    /// Repository URL for a headless run
+    url: Option<String>,
+    
+    /// Export file for a headless run
+    out_path: Option<PathBuf>,
+    
+    /// Export format name for a headless run
+    format: Option<String>,
+}
+
+/// Parses command line arguments
+///
+/// # Arguments
+/// * `args` - The arguments, without the program name
+///
+/// # Returns
+/// Result with the parsed options or an error message
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<CliOptions, String> {
+    let mut options = CliOptions::default();
+    let mut args = args.into_iter();
+    
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-c" | "--config" => {
+                let path = args.next().ok_or_else(|| format!("{} requires a file path", arg))?;
+                options.config_path = Some(PathBuf::from(path));
+            }
+            "--clone" => options.auto_clone = true,
+            "--headless" => options.headless = true,
+            "--url" => options.url = Some(args.next().ok_or("--url requires a URL")?),
+            "--out" => options.out_path = Some(PathBuf::from(args.next().ok_or("--out requires a file path")?)),
+            "--format" => options.format = Some(args.next().ok_or("--format requires csv, json or md")?),
+            "-h" | "--help" => options.show_help = true,
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+    
+    Ok(options)
+}
+
+/// Builds the headless run settings from the command line and project file
+///
+/// # Arguments
+/// * `options` - The parsed command line
+/// * `project_config` - The loaded project file, if any
+///
+/// # Returns
+/// Result with the headless settings or an error message
+fn headless_options(options: &CliOptions, project_config: Option<config::ProjectConfig>) -> Result<headless::HeadlessOptions, String> {
+    let project_config = project_config.unwrap_or_default();
+    let url = options.url.clone()
+        .or_else(|| project_config.url.clone())
+        .ok_or("--headless requires --url or a project file with a url")?;
+    let out_path = options.out_path.clone().ok_or("--headless requires --out")?;
+    
+    // Without --format, use the output file's extension
+    let format = match &options.format {
+        Some(name) => export::ExportFormat::from_name(name)
+            .ok_or_else(|| format!("Unknown export format: {}", name))?,
+        None => out_path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(export::ExportFormat::from_name)
+            .unwrap_or(export::ExportFormat::Csv),
+    };
+    
+    Ok(headless::HeadlessOptions {
+        url,
+        out_path,
+        format,
+        config: project_config,
+        parser: directory::DirectoryParser::new(),
+    })
+}
 
 /// Entry point for the Git Scroll application
 fn main() {
+    // Read the command line before opening any window
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.show_help {
+        println!("{}", USAGE);
+        return;
+    }
+    
+    // Load the project file, if any
+    let project_config = match &options.config_path {
+        Some(path) => match config::ProjectConfig::load(path) {
+            Ok(mut project_config) => {
+                project_config.auto_clone |= options.auto_clone;
+                Some(project_config)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    
+    // Headless mode exports and exits without starting the GUI
+    if options.headless {
+        let result = headless_options(&options, project_config)
+            .and_then(|headless_options| {
+                headless::run(&headless_options).map(|summary| summary.print(&headless_options.out_path))
+            });
+        match result {
+            Ok(()) => std::process::exit(0),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    
     // Log startup information
     println!("Starting Git Scroll...");
     
@@ -23,7 +176,13 @@ fn main() {
     match eframe::run_native(
         "Git Scroll",
         native_options,
-        Box::new(|_cc| Ok(Box::new(app::GitScrollApp::new()))),
+        Box::new(move |cc| {
+            let mut app = app::GitScrollApp::new(cc.storage);
+            if let Some(project_config) = &project_config {
+                app.apply_config(project_config);
+            }
+            Ok(Box::new(app))
+        }),
     ) {
         Ok(_) => println!("Application closed successfully"),
         Err(e) => eprintln!("Error running application: {}", e),
@@ -65,9 +224,53 @@ mod tests {
     #[test]
     fn test_app_creation() {
         // This is a simple test to ensure the app can be created
-        let app = crate::app::GitScrollApp::new();
+        let app = crate::app::GitScrollApp::new(None);
         
         // If we got here, the app was created successfully
         assert!(true);
     }
+    
+    #[test]
+    fn test_parse_args() {
+        let args = |list: &[&str]| super::parse_args(list.iter().map(|arg| arg.to_string()));
+        
+        assert_eq!(args(&[]).unwrap(), super::CliOptions::default());
+        
+        let options = args(&["--config", "audit.yaml", "--clone"]).unwrap();
+        assert_eq!(options.config_path, Some(std::path::PathBuf::from("audit.yaml")));
+        assert!(options.auto_clone);
+        assert!(args(&["-h"]).unwrap().show_help);
+        
+        let options = args(&["--headless", "--url", "https://example.com/r.git", "--out", "r.json"]).unwrap();
+        assert!(options.headless);
+        assert_eq!(options.url.as_deref(), Some("https://example.com/r.git"));
+        
+        // Missing values and unknown arguments are errors
+        assert!(args(&["-c"]).is_err());
+        assert!(args(&["--headless", "--out"]).is_err());
+        assert!(args(&["--verbose"]).is_err());
+    }
+    
+    #[test]
+    fn test_headless_options() {
+        let options = |list: &[&str]| super::parse_args(list.iter().map(|arg| arg.to_string())).unwrap();
+        
+        // The format comes from the output extension unless given
+        let headless = super::headless_options(&options(&["--url", "/repo", "--out", "r.json"]), None).unwrap();
+        assert_eq!(headless.format, crate::export::ExportFormat::Json);
+        let headless = super::headless_options(&options(&["--url", "/repo", "--out", "r.txt"]), None).unwrap();
+        assert_eq!(headless.format, crate::export::ExportFormat::Csv);
+        
+        // The URL can come from the project file
+        let project_config = crate::config::ProjectConfig {
+            url: Some("/configured".to_string()),
+            ..Default::default()
+        };
+        let headless = super::headless_options(&options(&["--out", "r.csv"]), Some(project_config)).unwrap();
+        assert_eq!(headless.url, "/configured");
+        
+        assert!(super::headless_options(&options(&["--out", "r.csv"]), None).is_err());
+        assert!(super::headless_options(&options(&["--url", "/repo"]), None).is_err());
+        assert!(super::headless_options(&options(&["--url", "/repo", "--out", "r", "--format", "xml"]), None).is_err());
+    }
 }
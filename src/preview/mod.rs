@@ -0,0 +1,488 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Default maximum number of bytes of a file read for previews
+pub const PREVIEW_MAX_BYTES: usize = 64 * 1024;
+
+/// Number of leading bytes checked for NUL bytes before reading the rest of a preview
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Number of bytes shown in the hex summary of a binary file
+pub const PREVIEW_HEX_BYTES: usize = 256;
+
+/// Number of file previews kept in memory
+pub const PREVIEW_CACHE_ENTRIES: usize = 32;
+
+/// Content loaded for the preview pane and file tooltips
+#[derive(Clone)]
+pub enum FilePreview {
+    /// Text content, possibly truncated to the cache's read limit
+    Text {
+        content: String,
+        line_count: usize,
+        truncated: bool,
+    },
+    /// Binary file summarized by its size and leading bytes
+    Binary {
+        size: u64,
+        hex: String,
+    },
+    /// The file could not be read
+    Error(String),
+}
+
+/// Cached file contents shared by every part of the UI that shows them
+///
+/// Cloning the cache is cheap and gives another handle to the same entries,
+/// so the preview pane and the file list tooltips never read a file twice.
+/// Loads run on background threads; see `get_or_load`.
+#[derive(Clone)]
+pub struct FileContentCache {
+    state: Arc<Mutex<CacheState>>,
+}
+
+/// Entries of a `FileContentCache`, guarded by its mutex
+///
+/// Holds at most `capacity` previews and evicts the least recently used one
+/// when full. Clearing the cache also cancels loads still in flight: their
+/// results are dropped instead of being cached for the wrong repository.
+struct CacheState {
+    entries: HashMap<PathBuf, FilePreview>,
+    order: VecDeque<PathBuf>,  // Least recently used first
+    loading: HashSet<PathBuf>, // Files currently being loaded
+    generation: usize,         // Bumped by `clear` to cancel loads in flight
+    capacity: usize,
+    max_bytes: usize,          // Most bytes read from a file for its preview
+}
+
+impl FileContentCache {
+    /// Creates an empty cache
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of previews to keep
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(CacheState::new(capacity))),
+        }
+    }
+    
+    /// Gets the preview for a file, starting a background load if it isn't cached
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context, repainted once loading finishes
+    /// * `path` - Path to the file
+    ///
+    /// # Returns
+    /// * `Option<FilePreview>` - The preview, or `None` while it is loading
+    pub fn get_or_load(&self, ctx: &egui::Context, path: &Path) -> Option<FilePreview> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(preview) = state.get(path) {
+            return Some(preview);
+        }
+        
+        // Only start one load per file
+        if let Some(generation) = state.start_loading(path) {
+            let cache = self.clone();
+            let path = path.to_path_buf();
+            let ctx = ctx.clone();
+            let max_bytes = state.max_bytes;
+            thread::spawn(move || {
+                let preview = load_file_preview(&path, max_bytes);
+                cache.state.lock().unwrap().finish_loading(path, generation, preview);
+                ctx.request_repaint();
+            });
+        }
+        
+        None
+    }
+    
+    /// Checks whether a file is being loaded
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    ///
+    /// # Returns
+    /// * `bool` - True while a background load for the file is running
+    pub fn is_loading(&self, path: &Path) -> bool {
+        self.state.lock().unwrap().loading.contains(path)
+    }
+    
+    /// Removes a preview so it is reloaded on next use
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    pub fn invalidate(&self, path: &Path) {
+        self.state.lock().unwrap().remove(path);
+    }
+    
+    /// Drops all previews and cancels loads in flight
+    pub fn clear(&self) {
+        self.state.lock().unwrap().clear();
+    }
+    
+    /// Sets how much of a file is read for its preview
+    ///
+    /// Previews loaded with another limit are dropped so they are read again.
+    ///
+    /// # Arguments
+    /// * `max_bytes` - Most bytes read from a file
+    pub fn set_max_bytes(&self, max_bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        if state.max_bytes != max_bytes {
+            state.max_bytes = max_bytes;
+            state.clear();
+        }
+    }
+}
+
+impl CacheState {
+    /// Creates an empty cache state
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of previews to keep
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            loading: HashSet::new(),
+            generation: 0,
+            capacity,
+            max_bytes: PREVIEW_MAX_BYTES,
+        }
+    }
+    
+    /// Gets a cached preview, marking it as recently used
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    ///
+    /// # Returns
+    /// * `Option<FilePreview>` - The preview, if cached
+    fn get(&mut self, path: &Path) -> Option<FilePreview> {
+        let preview = self.entries.get(path)?.clone();
+        if let Some(position) = self.order.iter().position(|p| p == path) {
+            let recent = self.order.remove(position).unwrap();
+            self.order.push_back(recent);
+        }
+        Some(preview)
+    }
+    
+    /// Adds a preview, evicting the least recently used ones over capacity
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    /// * `preview` - The loaded preview
+    fn insert(&mut self, path: PathBuf, preview: FilePreview) {
+        if self.entries.insert(path.clone(), preview).is_some() {
+            self.order.retain(|p| p != &path);
+        }
+        self.order.push_back(path);
+        
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+    
+    /// Removes a preview so it is reloaded on next use
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    fn remove(&mut self, path: &Path) {
+        if self.entries.remove(path).is_some() {
+            self.order.retain(|p| p != path);
+        }
+    }
+    
+    /// Marks a file as loading
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    ///
+    /// # Returns
+    /// * `Option<usize>` - The generation to pass to `finish_loading`, or `None`
+    ///   if the file is already loading
+    fn start_loading(&mut self, path: &Path) -> Option<usize> {
+        if self.loading.insert(path.to_path_buf()) {
+            Some(self.generation)
+        } else {
+            None
+        }
+    }
+    
+    /// Stores the result of a load, unless the cache was cleared meanwhile
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    /// * `generation` - The generation returned by `start_loading`
+    /// * `preview` - The loaded preview
+    fn finish_loading(&mut self, path: PathBuf, generation: usize, preview: FilePreview) {
+        if generation == self.generation {
+            self.loading.remove(&path);
+            self.insert(path, preview);
+        }
+    }
+    
+    /// Drops all previews and cancels loads in flight
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.loading.clear();
+        self.generation += 1;
+    }
+}
+
+/// Loads a file for previewing
+///
+/// Reads at most `max_bytes` of the file. Files with a NUL byte in their
+/// first `BINARY_SNIFF_BYTES` are treated as binary without reading further.
+///
+/// # Arguments
+/// * `path` - Path to the file
+/// * `max_bytes` - Most bytes read from the file
+///
+/// # Returns
+/// * `FilePreview` - The preview content
+pub fn load_file_preview(path: &Path, max_bytes: usize) -> FilePreview {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) => return FilePreview::Error(format!("Failed to open file: {}", e)),
+    };
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    read_preview(file, size, max_bytes)
+}
+
+/// Builds a preview from the contents of a file
+///
+/// # Arguments
+/// * `reader` - The file's contents
+/// * `size` - Size of the whole file in bytes
+/// * `max_bytes` - Most bytes read from `reader`
+///
+/// # Returns
+/// * `FilePreview` - The preview content
+fn read_preview(reader: impl std::io::Read, size: u64, max_bytes: usize) -> FilePreview {
+    use std::io::Read;
+    
+    let mut reader = reader.take(max_bytes as u64);
+    let mut head = Vec::with_capacity(max_bytes.min(size as usize));
+    if let Err(e) = reader.by_ref().take(BINARY_SNIFF_BYTES as u64).read_to_end(&mut head) {
+        return FilePreview::Error(format!("Failed to read file: {}", e));
+    }
+    
+    if head.contains(&0) {
+        let hex = head.iter()
+            .take(PREVIEW_HEX_BYTES)
+            .collect::<Vec<_>>()
+            .chunks(16)
+            .enumerate()
+            .map(|(row, bytes)| {
+                let hex_bytes = bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ");
+                format!("{:08x}  {}", row * 16, hex_bytes)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        return FilePreview::Binary { size, hex };
+    }
+    
+    // Text is read up to the limit; lines past it aren't counted
+    if let Err(e) = reader.read_to_end(&mut head) {
+        return FilePreview::Error(format!("Failed to read file: {}", e));
+    }
+    let mut line_count = head.iter().filter(|&&b| b == b'\n').count();
+    if head.last().is_some_and(|&b| b != b'\n') {
+        // A final line without a trailing newline still counts
+        line_count += 1;
+    }
+    
+    FilePreview::Text {
+        content: String::from_utf8_lossy(&head).into_owned(),
+        line_count,
+        truncated: (size as usize) > head.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{Duration, Instant};
+    
+    #[test]
+    fn test_preview_cache_eviction() {
+        let preview = |text: &str| FilePreview::Text { content: text.to_string(), line_count: 1, truncated: false };
+        let content = |preview: Option<FilePreview>| match preview {
+            Some(FilePreview::Text { content, .. }) => Some(content),
+            _ => None,
+        };
+        let mut cache = CacheState::new(2);
+        
+        cache.insert(PathBuf::from("a"), preview("a"));
+        cache.insert(PathBuf::from("b"), preview("b"));
+        
+        // Using "a" makes "b" the oldest, so it is evicted first
+        assert_eq!(content(cache.get(Path::new("a"))), Some("a".to_string()));
+        cache.insert(PathBuf::from("c"), preview("c"));
+        assert!(cache.get(Path::new("b")).is_none());
+        assert!(cache.get(Path::new("a")).is_some());
+        assert!(cache.get(Path::new("c")).is_some());
+        assert_eq!(cache.entries.len(), 2);
+        
+        // Replacing an entry doesn't grow the cache
+        cache.insert(PathBuf::from("c"), preview("c2"));
+        assert_eq!(cache.order.len(), 2);
+        assert_eq!(content(cache.get(Path::new("c"))), Some("c2".to_string()));
+    }
+    
+    #[test]
+    fn test_preview_cache_cancels_loads_on_clear() {
+        let mut cache = CacheState::new(4);
+        let preview = FilePreview::Error("unused".to_string());
+        
+        // Only one load per file at a time
+        let generation = cache.start_loading(Path::new("a")).unwrap();
+        assert!(cache.start_loading(Path::new("a")).is_none());
+        
+        // A load finishing after a clear is dropped
+        cache.clear();
+        cache.finish_loading(PathBuf::from("a"), generation, preview.clone());
+        assert!(cache.get(Path::new("a")).is_none());
+        
+        // A load started after the clear is kept
+        let generation = cache.start_loading(Path::new("a")).unwrap();
+        cache.finish_loading(PathBuf::from("a"), generation, preview);
+        assert!(cache.get(Path::new("a")).is_some());
+        assert!(cache.loading.is_empty());
+    }
+    
+    #[test]
+    fn test_shared_handles_load_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.txt");
+        fs::write(&path, "hello").unwrap();
+        
+        let ctx = egui::Context::default();
+        let preview_pane = FileContentCache::new(4);
+        let tooltip = preview_pane.clone();
+        
+        // The first request starts a load that both handles see
+        assert!(preview_pane.get_or_load(&ctx, &path).is_none());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while tooltip.is_loading(&path) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+        match tooltip.get_or_load(&ctx, &path) {
+            Some(FilePreview::Text { content, .. }) => assert_eq!(content, "hello"),
+            _ => panic!("expected the shared text preview"),
+        }
+        
+        // Invalidating through one handle reloads for all of them
+        tooltip.invalidate(&path);
+        assert!(preview_pane.get_or_load(&ctx, &path).is_none());
+    }
+    
+    #[test]
+    fn test_load_file_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        
+        // Small text file is shown in full
+        let text_file = dir.path().join("small.txt");
+        fs::write(&text_file, "first\nsecond\nthird").unwrap();
+        match load_file_preview(&text_file, PREVIEW_MAX_BYTES) {
+            FilePreview::Text { content, line_count, truncated } => {
+                assert_eq!(content, "first\nsecond\nthird");
+                assert_eq!(line_count, 3);
+                assert!(!truncated);
+            }
+            _ => panic!("expected a text preview"),
+        }
+        
+        // Large text file is truncated and only the lines read are counted
+        let large_file = dir.path().join("large.txt");
+        fs::write(&large_file, "line\n".repeat(PREVIEW_MAX_BYTES)).unwrap();
+        match load_file_preview(&large_file, PREVIEW_MAX_BYTES) {
+            FilePreview::Text { content, line_count, truncated } => {
+                assert_eq!(content.len(), PREVIEW_MAX_BYTES);
+                assert_eq!(line_count, PREVIEW_MAX_BYTES / 5 + 1);
+                assert!(truncated);
+            }
+            _ => panic!("expected a text preview"),
+        }
+        
+        // Binary file gets a hex summary
+        let binary_file = dir.path().join("data.bin");
+        fs::write(&binary_file, [0u8, 1, 2, 255]).unwrap();
+        match load_file_preview(&binary_file, PREVIEW_MAX_BYTES) {
+            FilePreview::Binary { size, hex } => {
+                assert_eq!(size, 4);
+                assert_eq!(hex, "00000000  00 01 02 ff");
+            }
+            _ => panic!("expected a binary preview"),
+        }
+    }
+    
+    /// Reader that records how many bytes were read from it
+    struct CountingReader<R> {
+        inner: R,
+        read: usize,
+    }
+    
+    impl<R: std::io::Read> std::io::Read for CountingReader<R> {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            let read = self.inner.read(buffer)?;
+            self.read += read;
+            Ok(read)
+        }
+    }
+    
+    #[test]
+    fn test_binary_preview_stops_after_sniffing() {
+        // A large binary is recognized from its first bytes
+        let mut contents = vec![0u8; 1024 * 1024];
+        contents[0] = 0x89;
+        let mut reader = CountingReader { inner: contents.as_slice(), read: 0 };
+        match read_preview(&mut reader, contents.len() as u64, 512 * 1024) {
+            FilePreview::Binary { size, .. } => assert_eq!(size, 1024 * 1024),
+            _ => panic!("expected a binary preview"),
+        }
+        assert!(reader.read <= BINARY_SNIFF_BYTES);
+        
+        // Text is read up to the configured limit and no further
+        let text = "words\n".repeat(10_000);
+        let mut reader = CountingReader { inner: text.as_bytes(), read: 0 };
+        match read_preview(&mut reader, text.len() as u64, 16 * 1024) {
+            FilePreview::Text { content, truncated, .. } => {
+                assert_eq!(content.len(), 16 * 1024);
+                assert!(truncated);
+            }
+            _ => panic!("expected a text preview"),
+        }
+        assert_eq!(reader.read, 16 * 1024);
+    }
+    
+    #[test]
+    fn test_set_max_bytes_reloads_previews() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "x".repeat(4096)).unwrap();
+        
+        let ctx = egui::Context::default();
+        let cache = FileContentCache::new(4);
+        cache.set_max_bytes(1024);
+        let wait = |cache: &FileContentCache| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while cache.get_or_load(&ctx, &path).is_none() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(5));
+            }
+            cache.get_or_load(&ctx, &path)
+        };
+        assert!(matches!(wait(&cache), Some(FilePreview::Text { truncated: true, .. })));
+        
+        cache.set_max_bytes(PREVIEW_MAX_BYTES);
+        assert!(matches!(wait(&cache), Some(FilePreview::Text { truncated: false, .. })));
+    }
+}
@@ -2,6 +2,76 @@ use eframe::egui;
 use std::time::Duration;
 use egui::LayerId;
 use std::ops::Add;
+use serde::{Deserialize, Serialize};
+
+/// Kind of message shown in the status bar
+///
+/// Determines the color of the status indicator instead of inspecting the message text
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StatusKind {
+    /// Neutral informational message
+    Info,
+    /// A background operation is in progress
+    Loading,
+    /// An operation completed successfully
+    Success,
+    /// An operation failed
+    Error,
+}
+
+/// How much progress detail the status bar shows
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum StatusVerbosity {
+    /// Only the start and end of each phase, e.g. cloning or counting
+    Quiet,
+    /// Progress too, at most a few updates per second
+    #[default]
+    Normal,
+    /// Every progress update, with the size of each batch of counted files
+    Verbose,
+}
+
+impl StatusVerbosity {
+    /// Gets the name shown in the settings
+    ///
+    /// # Returns
+    /// * `&'static str` - The display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Quiet => "Quiet",
+            Self::Normal => "Normal",
+            Self::Verbose => "Verbose",
+        }
+    }
+}
+
+/// Which theme the UI uses
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ThemeMode {
+    /// Always use the light theme
+    Light,
+    /// Always use the dark theme
+    Dark,
+    /// Follow the operating system's appearance
+    System,
+    /// Dark theme with maximum contrast and heavier borders
+    HighContrast,
+}
+
+impl ThemeMode {
+    /// Gets the name shown in the settings
+    ///
+    /// # Returns
+    /// * `&'static str` - The display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+            Self::System => "System",
+            Self::HighContrast => "High Contrast",
+        }
+    }
+}
 
 /// Handles UI components and interactions
 pub struct UiHandler {
@@ -9,7 +79,9 @@ pub struct UiHandler {
     is_loading: bool,
     /// Progress value for operations (0.0 to 1.0)
     progress: f32,
-    /// Whether to use dark mode
+    /// The chosen theme
+    theme_mode: ThemeMode,
+    /// Whether to use dark mode, resolved from the theme mode
     dark_mode: bool,
 }
 
@@ -22,6 +94,7 @@ impl UiHandler {
         Self {
             is_loading: false,
             progress: 0.0,
+            theme_mode: ThemeMode::Dark,
             dark_mode: true, // Default to dark mode
         }
     }
@@ -216,8 +289,9 @@ impl UiHandler {
     /// # Arguments
     /// * `ui` - The egui UI to render to
     /// * `status_message` - The status message to display
+    /// * `status_kind` - The kind of status, used to color the indicator
     /// * `is_loading_tokens` - Whether tokens are currently being counted
-    pub fn render_status_bar(&self, ui: &mut egui::Ui, status_message: &str, is_loading_tokens: bool) {
+    pub fn render_status_bar(&self, ui: &mut egui::Ui, status_message: &str, status_kind: StatusKind, is_loading_tokens: bool) {
         // Create a frame for the status bar with a subtle background
         let frame = egui::Frame::NONE
             .fill(if self.dark_mode {
@@ -238,19 +312,7 @@ impl UiHandler {
         frame.show(ui, |ui| {
             ui.horizontal(|ui| {
                 // Status indicator (colored dot)
-                let status_color = if self.is_loading || is_loading_tokens {
-                    egui::Color32::from_rgb(100, 150, 255) // Blue for loading
-                } else if status_message.contains("error") || status_message.contains("fail") {
-                    egui::Color32::from_rgb(255, 100, 100) // Red for errors
-                } else if status_message.contains("success") {
-                    egui::Color32::from_rgb(100, 200, 100) // Green for success
-                } else {
-                    if self.dark_mode {
-                        egui::Color32::from_rgb(180, 180, 180) // Light gray for normal status in dark mode
-                    } else {
-                        egui::Color32::from_rgb(100, 100, 100) // Dark gray for normal status in light mode
-                    }
-                };
+                let status_color = self.status_color(status_kind, is_loading_tokens);
                 
                 // Draw status indicator dot
                 let dot_size = 8.0;
@@ -276,11 +338,11 @@ impl UiHandler {
                 if self.is_loading || is_loading_tokens {
                     ui.add_space(8.0);
                     
-                    if self.progress > 0.0 && self.is_loading {
-                        // Use progress bar when we have progress information
+                    if self.shows_progress_bar() {
+                        // Use progress bar when we have progress information; it isn't
+                        // animated, since that repaints every frame, and moves as progress arrives
                         ui.add(egui::ProgressBar::new(self.progress)
                             .desired_width(100.0)
-                            .animate(true)
                         );
                     } else {
                         // Use spinner for indeterminate progress
@@ -292,6 +354,33 @@ impl UiHandler {
         });
     }
     
+    /// Gets the status indicator color
+    ///
+    /// Loading state takes precedence over the status kind
+    ///
+    /// # Arguments
+    /// * `status_kind` - The kind of status being displayed
+    /// * `is_loading_tokens` - Whether tokens are currently being counted
+    ///
+    /// # Returns
+    /// * `egui::Color32` - The color for the status indicator and message
+    pub fn status_color(&self, status_kind: StatusKind, is_loading_tokens: bool) -> egui::Color32 {
+        if self.is_loading || is_loading_tokens {
+            return egui::Color32::from_rgb(100, 150, 255); // Blue for loading
+        }
+        
+        match status_kind {
+            StatusKind::Loading => egui::Color32::from_rgb(100, 150, 255), // Blue for loading
+            StatusKind::Error => egui::Color32::from_rgb(255, 100, 100), // Red for errors
+            StatusKind::Success => egui::Color32::from_rgb(100, 200, 100), // Green for success
+            StatusKind::Info => if self.dark_mode {
+                egui::Color32::from_rgb(180, 180, 180) // Light gray for normal status in dark mode
+            } else {
+                egui::Color32::from_rgb(100, 100, 100) // Dark gray for normal status in light mode
+            },
+        }
+    }
+    
     /// Renders the stats bar at the bottom of the screen
     ///
     /// # Arguments
@@ -400,14 +489,60 @@ impl UiHandler {
         self.progress = progress.clamp(0.0, 1.0);
     }
     
-    /// Sets the dark mode state
+    /// Checks whether the status bar shows a progress bar rather than a spinner
+    ///
+    /// # Returns
+    /// True while loading with known progress; phases without any, like
+    /// parsing, reset it to 0 to get the spinner
+    pub fn shows_progress_bar(&self) -> bool {
+        self.is_loading && self.progress > 0.0
+    }
+    
+    /// Sets the dark mode state, no longer following the system theme
     ///
     /// # Arguments
     /// * `dark_mode` - Whether to use dark mode
     pub fn set_dark_mode(&mut self, dark_mode: bool) {
+        self.theme_mode = if dark_mode { ThemeMode::Dark } else { ThemeMode::Light };
         self.dark_mode = dark_mode;
     }
     
+    /// Sets the theme mode
+    ///
+    /// The System mode takes effect on the next call to `resolve_theme`.
+    ///
+    /// # Arguments
+    /// * `theme_mode` - The theme to use
+    pub fn set_theme_mode(&mut self, theme_mode: ThemeMode) {
+        self.theme_mode = theme_mode;
+        match theme_mode {
+            ThemeMode::Light => self.dark_mode = false,
+            ThemeMode::Dark | ThemeMode::HighContrast => self.dark_mode = true,
+            ThemeMode::System => {}
+        }
+    }
+    
+    /// Gets the theme mode
+    ///
+    /// # Returns
+    /// * `ThemeMode` - The chosen theme
+    pub fn theme_mode(&self) -> ThemeMode {
+        self.theme_mode
+    }
+    
+    /// Updates dark mode from the system theme when following it
+    ///
+    /// Called every frame, so a change of the OS appearance applies right away.
+    ///
+    /// # Arguments
+    /// * `system_theme` - The OS theme reported by eframe, if known
+    pub fn resolve_theme(&mut self, system_theme: Option<egui::Theme>) {
+        if self.theme_mode == ThemeMode::System {
+            // Keep the dark default when the OS doesn't report a theme
+            self.dark_mode = system_theme.is_none_or(|theme| theme == egui::Theme::Dark);
+        }
+    }
+    
     /// Gets the dark mode state
     ///
     /// # Returns
@@ -416,6 +551,14 @@ impl UiHandler {
         self.dark_mode
     }
     
+    /// Gets whether the high-contrast theme is chosen
+    ///
+    /// # Returns
+    /// * `bool` - Whether the UI uses the high-contrast theme
+    pub fn is_high_contrast(&self) -> bool {
+        self.theme_mode == ThemeMode::HighContrast
+    }
+    
     /// Truncates a path with ellipsis for display
     ///
     /// # Arguments
@@ -458,8 +601,11 @@ pub mod style {
     /// # Arguments
     /// * `ctx` - The egui context
     /// * `dark_mode` - Whether to use dark mode
-    pub fn apply_style(ctx: &egui::Context, dark_mode: bool) {
-        if dark_mode {
+    /// * `high_contrast` - Whether to use the high-contrast theme, which is always dark
+    pub fn apply_style(ctx: &egui::Context, dark_mode: bool, high_contrast: bool) {
+        if high_contrast {
+            apply_high_contrast_style(ctx);
+        } else if dark_mode {
             apply_dark_style(ctx);
         } else {
             apply_light_style(ctx);
@@ -552,6 +698,65 @@ pub mod style {
         ctx.set_fonts(fonts);
     }
     
+    /// Applies high-contrast theme styling to the UI
+    ///
+    /// Text is white or black on plain black and yellow fills. Hovered,
+    /// pressed and selected widgets get progressively heavier borders, so
+    /// their states don't depend on telling colors apart.
+    ///
+    /// # Arguments
+    /// * `ctx` - The egui context
+    pub fn apply_high_contrast_style(ctx: &egui::Context) {
+        let mut style = egui::Style::default();
+        style.visuals.dark_mode = true;
+        
+        // Background and panel colors
+        style.visuals.panel_fill = egui::Color32::BLACK;
+        style.visuals.window_fill = egui::Color32::BLACK;
+        style.visuals.window_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+        style.visuals.extreme_bg_color = egui::Color32::BLACK;
+        style.visuals.faint_bg_color = egui::Color32::from_rgb(28, 28, 28);
+        
+        // Widget fills stay dark so white text keeps its contrast; borders carry the state
+        style.visuals.widgets.noninteractive.bg_fill = egui::Color32::BLACK;
+        style.visuals.widgets.noninteractive.bg_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 200, 200));
+        style.visuals.widgets.inactive.bg_fill = egui::Color32::from_rgb(20, 20, 20);
+        style.visuals.widgets.inactive.weak_bg_fill = egui::Color32::from_rgb(20, 20, 20);
+        style.visuals.widgets.inactive.bg_stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+        style.visuals.widgets.hovered.bg_fill = egui::Color32::from_rgb(40, 40, 40);
+        style.visuals.widgets.hovered.weak_bg_fill = egui::Color32::from_rgb(40, 40, 40);
+        style.visuals.widgets.hovered.bg_stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+        style.visuals.widgets.active.bg_fill = egui::Color32::from_rgb(60, 60, 60);
+        style.visuals.widgets.active.weak_bg_fill = egui::Color32::from_rgb(60, 60, 60);
+        style.visuals.widgets.active.bg_stroke = egui::Stroke::new(3.0, egui::Color32::YELLOW);
+        style.visuals.widgets.open.bg_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+        
+        // Text colors
+        style.visuals.widgets.noninteractive.fg_stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+        style.visuals.widgets.inactive.fg_stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+        style.visuals.widgets.hovered.fg_stroke = egui::Stroke::new(1.5, egui::Color32::YELLOW);
+        style.visuals.widgets.active.fg_stroke = egui::Stroke::new(2.0, egui::Color32::YELLOW);
+        
+        // Selected text and toggles are black on yellow with a white border
+        style.visuals.selection.bg_fill = egui::Color32::YELLOW;
+        style.visuals.selection.stroke = egui::Stroke::new(2.0, egui::Color32::BLACK);
+        style.visuals.hyperlink_color = egui::Color32::from_rgb(0, 255, 255);
+        
+        // Customize spacing
+        style.spacing.item_spacing = egui::vec2(8.0, 6.0);
+        style.spacing.button_padding = egui::vec2(6.0, 4.0);
+        
+        ctx.set_style(style);
+        
+        let mut fonts = egui::FontDefinitions::default();
+        fonts.font_data.insert(
+            "jetbrains_mono".to_owned(),
+            std::sync::Arc::new(egui::FontData::from_static(include_bytes!("../../assets/JetBrainsMono-Regular.ttf"))),
+        );
+        fonts.families.entry(egui::FontFamily::Monospace).or_default().insert(0, "jetbrains_mono".to_owned());
+        ctx.set_fonts(fonts);
+    }
+    
     /// Gets the color for a directory
     ///
     /// # Returns
@@ -568,6 +773,47 @@ pub mod style {
         egui::Color32::from_rgb(100, 180, 100) // Green
     }
     
+    /// Gets the color for a file based on its extension
+    ///
+    /// # Arguments
+    /// * `extension` - The file extension, without the leading dot
+    /// * `dark_mode` - Whether dark mode is enabled
+    ///
+    /// # Returns
+    /// * `egui::Color32` - The color for files of this type
+    pub fn extension_color(extension: &str, dark_mode: bool) -> egui::Color32 {
+        match extension.to_lowercase().as_str() {
+            "rs" => egui::Color32::from_rgb(255, 160, 80),  // Rust files - orange
+            "js" | "ts" => egui::Color32::from_rgb(240, 220, 80),  // JavaScript/TypeScript - yellow
+            "py" => egui::Color32::from_rgb(80, 160, 255),  // Python - blue
+            "html" | "css" => egui::Color32::from_rgb(100, 200, 100),  // Web files - green
+            "md" | "txt" => egui::Color32::from_rgb(200, 200, 200),  // Documentation - light gray
+            "json" | "toml" | "yaml" => egui::Color32::from_rgb(200, 150, 255),  // Config files - purple
+            _ => if dark_mode {
+                egui::Color32::from_rgb(180, 180, 180)  // Default - light gray
+            } else {
+                egui::Color32::from_rgb(80, 80, 80)  // Default - dark gray
+            }
+        }
+    }
+    
+    /// Gets the syntax highlighting language for a file
+    ///
+    /// Uses the extension, or the file name for extensionless files such as
+    /// `Makefile`, which the highlighter also recognizes
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file
+    ///
+    /// # Returns
+    /// * `Option<String>` - The language key, or `None` if it can't be inferred
+    pub fn syntax_language(path: &std::path::Path) -> Option<String> {
+        path.extension()
+            .or_else(|| path.file_name())
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_lowercase())
+    }
+    
     /// Gets the color for selected items
     ///
     /// # Returns
@@ -640,6 +886,25 @@ pub mod style {
             egui::Color32::from_rgb(r, g, b)
         }
     }
+    
+    /// Gets the token count background color for the high-contrast theme
+    ///
+    /// Ranges from black to a dark blue, dark enough for white text at any value.
+    ///
+    /// # Arguments
+    /// * `tokens` - The token count
+    /// * `max_tokens` - The maximum token count for scaling
+    ///
+    /// # Returns
+    /// * `egui::Color32` - The color for the token count background
+    pub fn high_contrast_count_color(tokens: usize, max_tokens: usize) -> egui::Color32 {
+        let ratio = if max_tokens > 0 {
+            (tokens as f32 / max_tokens as f32).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        egui::Color32::from_rgb(0, (ratio * 60.0) as u8, (ratio * 140.0) as u8)
+    }
 }
 
 #[cfg(test)]
@@ -703,6 +968,79 @@ mod tests {
         assert!(handler.is_dark_mode());
     }
     
+    #[test]
+    fn test_theme_mode() {
+        let mut handler = UiHandler::new();
+        assert_eq!(handler.theme_mode(), ThemeMode::Dark);
+        
+        // Following the system theme tracks every change
+        handler.set_theme_mode(ThemeMode::System);
+        handler.resolve_theme(Some(egui::Theme::Light));
+        assert!(!handler.is_dark_mode());
+        handler.resolve_theme(Some(egui::Theme::Dark));
+        assert!(handler.is_dark_mode());
+        handler.resolve_theme(None);
+        assert!(handler.is_dark_mode());
+        
+        // An explicit mode ignores the system theme
+        handler.set_theme_mode(ThemeMode::Light);
+        handler.resolve_theme(Some(egui::Theme::Dark));
+        assert!(!handler.is_dark_mode());
+        
+        // Toggling picks an explicit mode
+        handler.set_theme_mode(ThemeMode::System);
+        handler.set_dark_mode(true);
+        assert_eq!(handler.theme_mode(), ThemeMode::Dark);
+        
+        // High contrast is a dark theme of its own
+        handler.set_theme_mode(ThemeMode::HighContrast);
+        handler.resolve_theme(Some(egui::Theme::Light));
+        assert!(handler.is_dark_mode());
+        assert!(handler.is_high_contrast());
+        handler.set_dark_mode(false);
+        assert!(!handler.is_high_contrast());
+    }
+    
+    #[test]
+    fn test_high_contrast_count_color() {
+        // Relative luminance per WCAG, for the contrast of white text on the fill
+        let luminance = |color: egui::Color32| {
+            let channel = |value: u8| {
+                let value = value as f32 / 255.0;
+                if value <= 0.03928 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+            };
+            0.2126 * channel(color.r()) + 0.7152 * channel(color.g()) + 0.0722 * channel(color.b())
+        };
+        for tokens in [0, 500, 1000] {
+            let contrast = 1.05 / (luminance(style::high_contrast_count_color(tokens, 1000)) + 0.05);
+            assert!(contrast >= 7.0, "contrast {} at {} tokens", contrast, tokens);
+        }
+    }
+    
+    #[test]
+    fn test_status_color() {
+        let mut handler = UiHandler::new();
+        
+        // Color comes from the kind, not the message text
+        assert_eq!(handler.status_color(StatusKind::Error, false), egui::Color32::from_rgb(255, 100, 100));
+        assert_eq!(handler.status_color(StatusKind::Success, false), egui::Color32::from_rgb(100, 200, 100));
+        assert_eq!(handler.status_color(StatusKind::Info, false), egui::Color32::from_rgb(180, 180, 180));
+        
+        // Loading takes precedence over the kind
+        assert_eq!(handler.status_color(StatusKind::Error, true), egui::Color32::from_rgb(100, 150, 255));
+        handler.set_loading(true);
+        assert_eq!(handler.status_color(StatusKind::Success, false), egui::Color32::from_rgb(100, 150, 255));
+    }
+    
+    #[test]
+    fn test_syntax_language() {
+        use std::path::Path;
+        
+        assert_eq!(style::syntax_language(Path::new("src/main.RS")), Some("rs".to_string()));
+        assert_eq!(style::syntax_language(Path::new("Makefile")), Some("makefile".to_string()));
+        assert_eq!(style::syntax_language(Path::new("")), None);
+    }
+    
     #[test]
     fn test_truncate_path() {
         // Short path, no truncation